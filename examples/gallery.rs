@@ -0,0 +1,77 @@
+//! Renders a contact sheet of thumbnails showing off different parameters.
+//!
+//! cargo run --release --example gallery [output.png]
+
+use glam::{U8Vec3, USizeVec2, Vec2, Vec3};
+use layered_worley::{Buffer, CombineOp, Config, MaxDist, WorleyNoise, render};
+
+const THUMB: usize = 160;
+const COLUMNS: usize = 4;
+const GAP: usize = 4;
+
+fn main() -> Result<(), layered_worley::WorleyError> {
+    let output = std::env::args().nth(1).unwrap_or("gallery.png".into());
+
+    let base = Config {
+        noise: WorleyNoise {
+            seed: 1,
+            depth: 4,
+            cells: Vec2::splat(64.0),
+            ..Default::default()
+        },
+        max_dist: MaxDist::CellFraction(0.45),
+        ..Default::default()
+    };
+    let overlay = WorleyNoise {
+        seed: 2,
+        cells: Vec2::splat(48.0),
+        ..base.noise
+    };
+
+    let tweak = |f: &dyn Fn(&mut Config)| {
+        let mut config = base.clone();
+        f(&mut config);
+        config
+    };
+    let entries = [
+        base.clone(),
+        tweak(&|c| c.noise.depth = 1),
+        tweak(&|c| c.noise.depth = 7),
+        tweak(&|c| c.noise.growth = Vec2::splat(2.0)),
+        tweak(&|c| c.noise.relaxation_iterations = 2),
+        tweak(&|c| c.noise.cells = Vec2::new(96.0, 32.0)),
+        tweak(&|c| c.noise.toroidal_domain = Some(Vec2::splat(96.0))),
+        tweak(&|c| c.dist_power = 4.0),
+        tweak(&|c| c.background = Vec3::new(240.0, 230.0, 210.0)),
+        tweak(&|c| {
+            c.overlay = Some(overlay);
+            c.combine_op = CombineOp::Min;
+        }),
+        tweak(&|c| {
+            c.overlay = Some(overlay);
+            c.combine_op = CombineOp::Max;
+        }),
+        tweak(&|c| {
+            c.overlay = Some(overlay);
+            c.combine_op = CombineOp::Multiply;
+        }),
+    ];
+
+    let rows = entries.len().div_ceil(COLUMNS);
+    let mut sheet = Buffer::new(
+        COLUMNS * (THUMB + GAP) + GAP,
+        rows * (THUMB + GAP) + GAP,
+        U8Vec3::splat(24),
+    );
+    let mut thumb = Buffer::new(THUMB, THUMB, U8Vec3::ZERO);
+    for (i, config) in entries.iter().enumerate() {
+        config.validate()?;
+        render(&mut thumb, config);
+        let corner = USizeVec2::new(i % COLUMNS, i / COLUMNS) * (THUMB + GAP) + GAP;
+        sheet.paste(&thumb, corner);
+    }
+
+    sheet.save(&output)?;
+    println!("Wrote {output}");
+    Ok(())
+}
@@ -0,0 +1,61 @@
+//! Colors a render with custom shaders over a precomputed field: first one reproducing
+//! the built-in coloring, checked against a normal render, then a grayscale one of its
+//! own.
+//!
+//! cargo run --release --example shader [output.png]
+
+use glam::{IVec2, U8Vec3, Vec2};
+use layered_worley::{
+    Buffer, CellId, Config, MaxDist, PixelGeometry, WorleyNoise, render, render_cells,
+    render_with_shader, shade,
+};
+
+const WIDTH: usize = 640;
+const HEIGHT: usize = 360;
+
+fn main() -> Result<(), layered_worley::WorleyError> {
+    let output = std::env::args().nth(1).unwrap_or("shader.png".into());
+
+    let config = Config {
+        noise: WorleyNoise {
+            seed: 4,
+            depth: 5,
+            cells: Vec2::splat(128.0),
+            ..Default::default()
+        },
+        max_dist: MaxDist::CellFraction(0.45),
+        ..Default::default()
+    };
+    config.validate()?;
+
+    // Sampling is the expensive part, done once for both shaders
+    let mut field = Buffer::new(WIDTH, HEIGHT, (IVec2::ZERO, 0.0));
+    render_cells(&mut field, &config, IVec2::ZERO);
+
+    let builtin = render_with_shader(&field, |pos, cell, dist| {
+        let geometry = PixelGeometry {
+            cell: CellId(cell),
+            dist,
+            ..Default::default()
+        };
+        shade(&geometry, pos.as_vec2(), &config)
+    });
+    let mut full = Buffer::new(WIDTH, HEIGHT, U8Vec3::ZERO);
+    render(&mut full, &config);
+    assert!(
+        builtin.buff == full.buff,
+        "the built-in shader differs from a normal render"
+    );
+
+    // A gray per cell from its hash, darkening linearly out to max_dist
+    let max_dist = config.max_dist.to_world(config.noise.cells);
+    let grayscale = render_with_shader(&field, |_, cell, dist| {
+        let gray = 96.0 + (config.noise.cell_hash(cell) % 160) as f32;
+        let falloff = (1.0 - dist / max_dist).clamp(0.0, 1.0);
+        U8Vec3::splat((gray * falloff) as u8)
+    });
+
+    grayscale.save(&output)?;
+    println!("Wrote {output}; the built-in shader matched a normal render");
+    Ok(())
+}
@@ -0,0 +1,96 @@
+//! Alpha masks from the field, for compositing renders over other images, and a
+//! checkerboard preview that shows how a mask behaves before exporting it.
+
+use std::str::FromStr;
+
+use glam::{IVec2, U8Vec3, U8Vec4, USizeVec2};
+
+use crate::{
+    Buffer, Config, Named, WorleyError,
+    error::Result,
+    named::parse_named,
+    render::{Fields, sample_with},
+};
+
+// Side of a checkerboard square, in pixels
+pub const CHECKER_SIZE: usize = 8;
+const CHECKER_LIGHT: U8Vec3 = U8Vec3::splat(204);
+const CHECKER_DARK: U8Vec3 = U8Vec3::splat(153);
+
+// What a pixel's alpha is taken from
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AlphaSource {
+    // Opaque at the feature points, fading out to transparent at `max_dist`
+    #[default]
+    Distance,
+    // The gap between the nearest and second nearest coarse cells (F2 - F1), transparent
+    // along cell walls and opaque a `max_dist` away from them
+    EdgeGap,
+}
+
+impl AlphaSource {
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&s| s == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+impl Named for AlphaSource {
+    const KIND: &'static str = "alpha source";
+    const ALL: &'static [Self] = &[AlphaSource::Distance, AlphaSource::EdgeGap];
+
+    fn name(self) -> &'static str {
+        match self {
+            AlphaSource::Distance => "distance",
+            AlphaSource::EdgeGap => "f2-f1",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            AlphaSource::Distance => "opaque at the feature points, clear at max-dist",
+            AlphaSource::EdgeGap => "clear along cell walls, opaque max-dist inside them",
+        }
+    }
+}
+
+impl FromStr for AlphaSource {
+    type Err = WorleyError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        parse_named(s)
+    }
+}
+
+// Fills `buffer` with the alpha of each pixel of the window whose top left is `origin`
+pub fn render_alpha(buffer: &mut Buffer<u8>, config: &Config, origin: IVec2, source: AlphaSource) {
+    let max_dist = config.max_dist.to_world(config.noise.cells);
+    let fields = Fields::of(config);
+    buffer.par_for_each(|pos, alpha| {
+        let pos = (origin + pos.as_ivec2()).as_vec2();
+        let opacity = match source {
+            AlphaSource::Distance => 1.0 - sample_with(pos, config, &fields).1 / max_dist,
+            AlphaSource::EdgeGap => {
+                let [(_, f1), (_, f2)] = fields.noise.sample_f2(config.sample_pos(pos));
+                (f2 - f1) / max_dist
+            }
+        };
+        *alpha = (opacity.clamp(0.0, 1.0) * 255.0).round() as u8;
+    });
+}
+
+// The checkerboard color behind pixel `pos`, light in the top left square
+pub fn checkerboard(pos: USizeVec2) -> U8Vec3 {
+    if (pos.x / CHECKER_SIZE + pos.y / CHECKER_SIZE).is_multiple_of(2) {
+        CHECKER_LIGHT
+    } else {
+        CHECKER_DARK
+    }
+}
+
+// `rgba` drawn over the checkerboard at `pos`, so transparent areas show the squares
+pub fn composite_checkerboard(rgba: U8Vec4, pos: USizeVec2) -> U8Vec3 {
+    let alpha = rgba.w as u32;
+    let (fg, bg) = (rgba.truncate().as_uvec3(), checkerboard(pos).as_uvec3());
+    ((fg * alpha + bg * (255 - alpha) + 127) / 255).as_u8vec3()
+}
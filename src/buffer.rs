@@ -0,0 +1,314 @@
+use std::{fs::File, io::BufWriter, path::Path};
+
+use glam::{U8Vec3, U8Vec4, USizeVec2, Vec2, Vec3};
+use image::{DynamicImage, Rgb, RgbImage};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{
+    Named, OutputFormat, WorleyError, composite_checkerboard,
+    error::Result,
+    profile::{self, Stage},
+};
+
+// How far apart two images are, from `Buffer::diff`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DiffStats {
+    // Mean absolute difference over every channel of every pixel, 0 to 255
+    pub mean: f32,
+    // Largest difference in any channel
+    pub max: u8,
+    // Pixels with any difference
+    pub changed: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Buffer<T> {
+    pub buff: Vec<T>,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl<T: Clone> Buffer<T> {
+    pub fn new(width: usize, height: usize, val: T) -> Self {
+        Self {
+            buff: vec![val; width * height],
+            width,
+            height,
+        }
+    }
+
+    // The index of `pos` in `buff`, or None outside the buffer. Coordinates never wrap,
+    // so an x past the width is out of bounds rather than landing on the next row.
+    pub fn index(&self, pos: USizeVec2) -> Option<usize> {
+        (pos.x < self.width && pos.y < self.height).then(|| pos.x + self.width * pos.y)
+    }
+
+    // Does nothing outside the buffer, so shapes can be drawn clipped at the edges
+    pub fn set(&mut self, pos: USizeVec2, val: T) {
+        if let Some(i) = self.index(pos) {
+            self.buff[i] = val;
+        }
+    }
+
+    pub fn setf(&mut self, pos: Vec2, val: T) {
+        let pos = pos.round();
+        // Negative positions would saturate onto the first row or column
+        if pos.cmpge(Vec2::ZERO).all() {
+            self.set(pos.as_usizevec2(), val);
+        }
+    }
+
+    // None outside the buffer, where `set` does nothing
+    pub fn get(&self, pos: USizeVec2) -> Option<T> {
+        self.index(pos).map(|i| self.buff[i].clone())
+    }
+
+    pub fn reset(&mut self, val: T) {
+        self.buff = vec![val; self.width * self.height];
+    }
+
+    // Copies `src` in with its top left corner at `origin`, clipping at the edges
+    pub fn paste(&mut self, src: &Buffer<T>, origin: USizeVec2) {
+        let width = src.width.min(self.width.saturating_sub(origin.x));
+        for y in 0..src.height.min(self.height.saturating_sub(origin.y)) {
+            let start = origin.x + (origin.y + y) * self.width;
+            self.buff[start..start + width].clone_from_slice(&src.buff[y * src.width..][..width]);
+        }
+    }
+}
+
+impl<T: Send> Buffer<T> {
+    // Runs `f` on every pixel in parallel, along with the pixel's position. Without the
+    // rayon feature the pixels run in order on the calling thread.
+    pub fn par_for_each(&mut self, f: impl Fn(USizeVec2, &mut T) + Sync) {
+        let width = self.width;
+        let f = |(i, pixel): (usize, &mut T)| f(USizeVec2::new(i % width, i / width), pixel);
+        #[cfg(feature = "rayon")]
+        self.buff.par_iter_mut().enumerate().for_each(f);
+        #[cfg(not(feature = "rayon"))]
+        self.buff.iter_mut().enumerate().for_each(f);
+    }
+}
+
+impl<T: Clone + Send + Sync> Buffer<T> {
+    // Nearest neighbour resample of this buffer to fill `dst`
+    pub fn scale_into(&self, dst: &mut Buffer<T>) {
+        let (width, height) = (dst.width, dst.height);
+        dst.par_for_each(|pos, pixel| {
+            let x = pos.x * self.width / width;
+            let y = pos.y * self.height / height;
+            *pixel = self.buff[x + self.width * y].clone();
+        });
+    }
+}
+
+impl Buffer<U8Vec3> {
+    pub fn to_image(&self) -> RgbImage {
+        let _scope = profile::scope(Stage::Pack);
+        let mut img = RgbImage::new(self.width as u32, self.height as u32);
+        for (i, pixel) in self.buff.iter().enumerate() {
+            let x = (i % self.width) as u32;
+            let y = (i / self.width) as u32;
+            img.put_pixel(x, y, Rgb([pixel.x, pixel.y, pixel.z]));
+        }
+        img
+    }
+
+    // Saves with the encoder `path`'s extension names, see `OutputFormat::from_path`
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.save_as(&path, OutputFormat::from_path(&path)?)
+    }
+
+    pub fn save_as(&self, path: impl AsRef<Path>, format: OutputFormat) -> Result<()> {
+        save_image(self.to_image(), path, format)
+    }
+
+    // Saves as a PNG carrying `text` as chunks of keyword and value: tEXt for short
+    // Latin-1 values, and compressed iTXt for long or other values
+    pub fn save_png_with_text(
+        &self,
+        path: impl AsRef<Path>,
+        text: &[(String, String)],
+    ) -> Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let plain = |value: &str| value.len() < 1024 && value.chars().all(|c| c <= '\u{ff}');
+        for (keyword, value) in text.iter().filter(|(_, value)| plain(value)) {
+            encoder.add_text_chunk(keyword.clone(), value.clone())?;
+        }
+        let mut png = encoder.write_header()?;
+        for (keyword, value) in text.iter().filter(|(_, value)| !plain(value)) {
+            let mut chunk = png::text_metadata::ITXtChunk::new(keyword, value);
+            chunk.compressed = true;
+            png.write_text_chunk(&chunk)?;
+        }
+        png.write_image_data(&self.to_image())?;
+        png.finish()?;
+        Ok(())
+    }
+
+    // A 64 bit average hash for spotting near duplicates: the luma is box filtered down
+    // to 8x8 and each bit, row by row, is set where a block is brighter than the mean.
+    // Images that look alike share most bits, see `hash_distance`.
+    pub fn average_hash(&self) -> u64 {
+        let luma = |p: U8Vec3| 0.299 * p.x as f32 + 0.587 * p.y as f32 + 0.114 * p.z as f32;
+        // Block edges, at least one pixel apart even in images below 8 pixels
+        let span = |i: usize, len: usize| {
+            let start = (i * len / 8).min(len.saturating_sub(1));
+            start..((i + 1) * len / 8).max(start + 1).min(len)
+        };
+        let mut blocks = [0.0; 64];
+        for (i, block) in blocks.iter_mut().enumerate() {
+            let (xs, ys) = (span(i % 8, self.width), span(i / 8, self.height));
+            let count = (xs.len() * ys.len()).max(1) as f32;
+            let sum: f32 = ys
+                .flat_map(|y| xs.clone().map(move |x| (x, y)))
+                .map(|(x, y)| luma(self.buff[x + y * self.width]))
+                .sum();
+            *block = sum / count;
+        }
+        let mean = blocks.iter().sum::<f32>() / 64.0;
+        (0..64)
+            .filter(|&i| blocks[i] > mean)
+            .fold(0, |hash, i| hash | 1 << i)
+    }
+
+    // The per-channel absolute difference from `other` scaled by `amplify`, so small
+    // changes stand out, along with statistics of the unscaled difference. The buffers
+    // must be the same size.
+    pub fn diff(&self, other: &Buffer<U8Vec3>, amplify: f32) -> (Buffer<U8Vec3>, DiffStats) {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "diffed buffers must be the same size"
+        );
+        let mut out = Buffer::new(self.width, self.height, U8Vec3::ZERO);
+        let mut stats = DiffStats::default();
+        let mut total = 0u64;
+        for ((a, b), pixel) in self.buff.iter().zip(&other.buff).zip(&mut out.buff) {
+            let delta = a.max(*b) - a.min(*b);
+            total += delta.as_uvec3().element_sum() as u64;
+            stats.max = stats.max.max(delta.max_element());
+            stats.changed += usize::from(delta != U8Vec3::ZERO);
+            *pixel = (delta.as_vec3() * amplify)
+                .min(Vec3::splat(255.0))
+                .as_u8vec3();
+        }
+        stats.mean = total as f32 / (self.buff.len() * 3).max(1) as f32;
+        (out, stats)
+    }
+
+    // Packs into minifb's 0RGB format, reusing `out`'s allocation between frames
+    pub fn to_minifb_buffer(&self, out: &mut Vec<u32>) {
+        let _scope = profile::scope(Stage::Pack);
+        out.clear();
+        out.extend(self.buff.iter().map(|&rgb| rgb_from_vec(rgb)));
+    }
+
+    // Pairs each pixel with the alpha at the same index of `alpha`, which must be the
+    // same size
+    pub fn with_alpha(&self, alpha: &Buffer<u8>) -> Buffer<U8Vec4> {
+        assert_eq!((self.width, self.height), (alpha.width, alpha.height));
+        Buffer {
+            buff: self
+                .buff
+                .iter()
+                .zip(&alpha.buff)
+                .map(|(rgb, &a)| rgb.extend(a))
+                .collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl Buffer<U8Vec4> {
+    // Packs each pixel as 0xAARRGGBB, see `rgba_from_vec`
+    pub fn to_packed_rgba(&self, out: &mut Vec<u32>) {
+        let _scope = profile::scope(Stage::Pack);
+        out.clear();
+        out.extend(self.buff.iter().map(|&rgba| rgba_from_vec(rgba)));
+    }
+
+    // Flattens the image over a checkerboard, for previewing transparency on displays
+    // without an alpha channel
+    pub fn over_checkerboard(&self) -> Buffer<U8Vec3> {
+        let mut out = Buffer::new(self.width, self.height, U8Vec3::ZERO);
+        out.par_for_each(|pos, pixel| {
+            *pixel = composite_checkerboard(self.buff[pos.x + pos.y * self.width], pos);
+        });
+        out
+    }
+}
+
+impl Buffer<Vec2> {
+    // Encodes each vector's x and y from -1..1 into the red and green channels, leaving
+    // blue at 0, the usual layout for flow maps
+    pub fn to_flow_image(&self) -> RgbImage {
+        let encode = |v: f32| ((v.clamp(-1.0, 1.0) * 0.5 + 0.5) * 255.0).round() as u8;
+        let mut img = RgbImage::new(self.width as u32, self.height as u32);
+        for (i, flow) in self.buff.iter().enumerate() {
+            let x = (i % self.width) as u32;
+            let y = (i / self.width) as u32;
+            img.put_pixel(x, y, Rgb([encode(flow.x), encode(flow.y), 0]));
+        }
+        img
+    }
+
+    pub fn save_flow(&self, path: impl AsRef<Path>) -> Result<()> {
+        save_image(self.to_flow_image(), &path, OutputFormat::from_path(&path)?)
+    }
+}
+
+fn save_image(image: RgbImage, path: impl AsRef<Path>, format: OutputFormat) -> Result<()> {
+    let Some(image_format) = format.image_format() else {
+        return Err(WorleyError::UnsupportedFormat(format!(
+            "{} output holds distances or outlines, not colors",
+            format.name()
+        )));
+    };
+    let image = DynamicImage::ImageRgb8(image);
+    // The EXR encoder only takes floats
+    let image = match format {
+        OutputFormat::Exr => DynamicImage::ImageRgb32F(image.to_rgb32f()),
+        _ => image,
+    };
+    Ok(image.save_with_format(path, image_format)?)
+}
+
+// Bits that differ between two `average_hash`es. 0 is visually identical and a handful
+// of bits is a near duplicate.
+pub fn hash_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+pub fn rgb_from_u8(r: u8, g: u8, b: u8) -> u32 {
+    let (r, g, b) = (r as u32, g as u32, b as u32);
+    r << 16 | g << 8 | b
+}
+
+pub fn rgb_from_vec(rgb: U8Vec3) -> u32 {
+    let (r, g, b) = (rgb.x as u32, rgb.y as u32, rgb.z as u32);
+    r << 16 | g << 8 | b
+}
+
+// Packs alpha into the high byte, so it reads as 0xAARRGGBB
+pub fn rgba_from_u8(r: u8, g: u8, b: u8, a: u8) -> u32 {
+    (a as u32) << 24 | rgb_from_u8(r, g, b)
+}
+
+pub fn rgba_from_vec(rgba: U8Vec4) -> u32 {
+    rgba_from_u8(rgba.x, rgba.y, rgba.z, rgba.w)
+}
+
+// Ignores the high byte
+pub fn unpack_rgb(packed: u32) -> U8Vec3 {
+    U8Vec3::new((packed >> 16) as u8, (packed >> 8) as u8, packed as u8)
+}
+
+pub fn unpack_rgba(packed: u32) -> U8Vec4 {
+    unpack_rgb(packed).extend((packed >> 24) as u8)
+}
@@ -0,0 +1,130 @@
+//! Incremental rendering for tweaking colors. Sampling the hierarchy is by far the most
+//! expensive part of a render, and most parameters (palette, falloff, tone mapping,
+//! dithering) never touch it, so the sampled geometry is kept between renders and only
+//! resampled when a parameter that moves the cells changes. `RenderCache` does this
+//! automatically; `compute_field` and `colorize` split the two stages by hand.
+
+use glam::{IVec2, U8Vec3, Vec2};
+
+use crate::{
+    Buffer, CombineOp, Config, PixelGeometry, WorleyNoise,
+    render::{Fields, geometry_needs, sample_geometry_for, shade_with},
+};
+
+// Samples the complete geometry of a region once, ready for `colorize` with any colors.
+// Everything any color mode or blend could need is sampled, so only `noise`,
+// `render_depth`, `overlay` and `combine_op` of `config` are fixed by the field. The fade
+// of `continuous_cells` is sampled only as wide as `config`'s, which costs more the
+// wider it is, so coloring with a wider one fades less than a direct render would.
+pub fn compute_field(field: &mut Buffer<PixelGeometry>, config: &Config, origin: IVec2) {
+    let fields = Fields::of(config);
+    let needs = (true, true, true, config.continuous_cells);
+    field.par_for_each(|pos, geometry| {
+        let pos = (origin + pos.as_ivec2()).as_vec2();
+        *geometry = sample_geometry_for(pos, config, &fields, needs);
+    });
+}
+
+// Colors a field from `compute_field` rendered at the same `origin`, giving the same
+// image `render_region` would for `config`, provided its geometry matches the field's
+pub fn colorize(
+    buffer: &mut Buffer<U8Vec3>,
+    field: &Buffer<PixelGeometry>,
+    config: &Config,
+    origin: IVec2,
+) {
+    let width = buffer.width;
+    let fields = Fields::of(config);
+    buffer.par_for_each(|pos, pixel| {
+        let geometry = &field.buff[pos.x + pos.y * width];
+        *pixel = shade_with(
+            geometry,
+            (origin + pos.as_ivec2()).as_vec2(),
+            config,
+            &fields,
+        );
+    });
+}
+
+// Everything the cached geometry was sampled with
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct GeometryKey {
+    noise: WorleyNoise,
+    overlay: Option<WorleyNoise>,
+    combine_op: CombineOp,
+    // Every position is turned before it's sampled
+    rotation: f32,
+    rotation_center: Vec2,
+    origin: IVec2,
+    size: (usize, usize),
+    needs: (bool, bool, bool, f32),
+}
+
+impl GeometryKey {
+    fn new(config: &Config, fields: &Fields, origin: IVec2, buffer: &Buffer<U8Vec3>) -> Self {
+        Self {
+            noise: fields.noise,
+            overlay: fields.overlay,
+            combine_op: config.combine_op,
+            rotation: config.rotation,
+            rotation_center: config.rotation_center,
+            origin,
+            size: (buffer.width, buffer.height),
+            needs: geometry_needs(config),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RenderCache {
+    key: Option<GeometryKey>,
+    geometry: Buffer<PixelGeometry>,
+}
+
+impl Default for RenderCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RenderCache {
+    pub fn new() -> Self {
+        Self {
+            key: None,
+            geometry: Buffer::new(0, 0, PixelGeometry::default()),
+        }
+    }
+
+    // Renders exactly like `render_region`, reusing the geometry of the previous call
+    // when nothing affecting it has changed. Returns whether the geometry was resampled.
+    pub fn render_region(
+        &mut self,
+        buffer: &mut Buffer<U8Vec3>,
+        config: &Config,
+        origin: IVec2,
+    ) -> bool {
+        let fields = Fields::of(config);
+        let key = GeometryKey::new(config, &fields, origin, buffer);
+        let resample = self.key != Some(key);
+        if resample {
+            self.geometry = Buffer::new(buffer.width, buffer.height, PixelGeometry::default());
+            self.geometry.par_for_each(|pos, geometry| {
+                let pos = (origin + pos.as_ivec2()).as_vec2();
+                *geometry = sample_geometry_for(pos, config, &fields, key.needs);
+            });
+            self.key = Some(key);
+        }
+
+        colorize(buffer, &self.geometry, config, origin);
+        resample
+    }
+
+    pub fn render(&mut self, buffer: &mut Buffer<U8Vec3>, config: &Config) -> bool {
+        self.render_region(buffer, config, IVec2::ZERO)
+    }
+
+    // The geometry of the last render, such as for a `Histogram`
+    pub fn geometry(&self) -> Option<&Buffer<PixelGeometry>> {
+        self.key.map(|_| &self.geometry)
+    }
+}
@@ -0,0 +1,936 @@
+use std::{path::PathBuf, str::FromStr, time::Duration};
+
+use clap::{Args as ClapArgs, Parser, Subcommand, error::ErrorKind};
+use glam::Vec2;
+use layered_worley::{
+    BuiltinPalette, CellColoring, ColorMode, ColorSpace, Config, Date, DistanceMetric,
+    DitherTexture, FlowMode, HashMixer, Idw, MaxDist, MetricsPerLevel, Named, OutputFormat,
+    Palette, PointsPerLevel, Pulse, Region, RenderConfig, ToneMap, daily_seed, profile,
+    seed_from_str,
+};
+use minifb::{Scale, ScaleMode};
+
+use crate::viewer::{WindowScale, parse_scale, parse_scale_mode};
+
+// The viewer's keys, listed after the options of the commands that open it
+const VIEW_KEYS: &str = "\
+Keys in the viewer:
+  S      save output.png
+  H      toggle a histogram of distance / max_dist
+  C      reroll the color seed
+  P      cycle the built-in palettes
+  R      toggle rulers in world coordinates with the coarse cell lines marked, hidden
+         while --rotate turns the pattern
+  X      toggle the raw cell hashes as colors (--color-mode cellhash)
+  A      preview an alpha mask over a checkerboard, cycling through distance, F2 - F1
+         and off
+  Space  pause and resume a --pulse animation, settling the paused frame at full
+         resolution for saving";
+
+#[derive(Debug, Parser)]
+#[command(
+    about = "Hierarchical worley noise: an interactive viewer and headless renders",
+    after_help = VIEW_KEYS,
+    args_conflicts_with_subcommands = true,
+    allow_negative_numbers = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+    // The viewer's options, when no subcommand is given
+    #[command(flatten)]
+    view: ViewArgs,
+}
+
+#[derive(Debug, Subcommand)]
+enum CliCommand {
+    /// Open the interactive viewer (the default)
+    #[command(after_help = VIEW_KEYS)]
+    View(ViewArgs),
+    /// Render a single image headlessly
+    Render(RenderArgs),
+    /// Render one image per step of a parameter range
+    Sweep(SweepArgs),
+    /// Render a numbered frame sequence panning across the noise
+    Animate(AnimateArgs),
+    /// Render level_0.png (full detail) to level_N.png (coarsest cells only)
+    Levels(LevelsArgs),
+    /// Render the coarse grid's distance beside the hierarchy's, for comparison
+    Split(SplitArgs),
+    /// Render two configs and write their amplified difference, printing stats
+    Compare(CompareArgs),
+    /// Print the resolved config and seed
+    Info(InfoArgs),
+}
+
+// Options every subcommand takes
+#[derive(Debug, ClapArgs)]
+struct CommonArgs {
+    /// Start from a render's .json sidecar, or a .png render's embedded parameters.
+    /// Other options override it.
+    #[arg(long, value_name = "PATH", value_parser = load_render_config)]
+    config: Option<RenderConfig>,
+    /// Print the parameters a rendered .png carries
+    #[arg(long, value_name = "PATH")]
+    read_metadata: Option<PathBuf>,
+    /// Instead of rendering, print the first N pixels' cell, distance and colors with
+    /// floats in full, to diff between machines or builds when renders of the same seed
+    /// differ
+    #[arg(long, value_name = "N")]
+    dump_first_pixels: Option<usize>,
+    #[arg(long, group = "seeding")]
+    seed: Option<u64>,
+    /// Seed from a phrase, printing the number it becomes
+    #[arg(long, value_name = "TEXT", group = "seeding")]
+    seed_from_string: Option<String>,
+    /// Today's shared seed, from the UTC date as YYYY-MM-DD. An earlier day's image
+    /// comes back with --seed-from-string and that date.
+    #[arg(long, group = "seeding")]
+    daily: bool,
+    #[arg(long, value_name = "N", value_parser = parse_size)]
+    width: Option<usize>,
+    #[arg(long, value_name = "N", value_parser = parse_size)]
+    height: Option<usize>,
+    /// Turn the pattern clockwise about the image's center, such as 0.785 for
+    /// diagonals. A rotated --toroidal pattern no longer tiles.
+    #[arg(long, value_name = "RADIANS")]
+    rotate: Option<f32>,
+    /// Flip each channel of the palette, for a light version of a dark palette or the
+    /// reverse
+    #[arg(long)]
+    invert: bool,
+    /// Write the palette as swatches to PATH instead
+    #[arg(long, value_name = "PATH")]
+    palette_preview: Option<PathBuf>,
+    /// Render on N threads, all cores by default
+    #[arg(long, value_name = "N")]
+    threads: Option<usize>,
+    /// Shorthand for --threads 1, see Determinism in the readme
+    #[arg(long, conflicts_with = "threads")]
+    deterministic: bool,
+    /// Drop the finest levels until each image is projected to render in time, printing
+    /// the depth chosen. Trades detail for speed on slow machines.
+    #[arg(long, value_name = "SECONDS", value_parser = parse_seconds)]
+    max_render_time: Option<Duration>,
+    /// Set --max-dist to the Pth percentile of distances over a coarse sample of the
+    /// image, such as 95, and print it so it can be pinned
+    #[arg(long, value_name = "P", value_parser = parse_percentile)]
+    auto_max_dist: Option<f32>,
+    /// Print the time spent in each stage of rendering and write it to PATH as folded
+    /// stacks for flamegraph.pl or inferno. Needs a build with --features profile.
+    #[arg(long, value_name = "PATH")]
+    profile: Option<PathBuf>,
+    /// Print the distance metrics --metric accepts
+    #[arg(long)]
+    list_metrics: bool,
+    /// Print the color modes --color-mode accepts
+    #[arg(long)]
+    list_modes: bool,
+    /// Print the cell colorings --cell-coloring accepts
+    #[arg(long)]
+    list_colorings: bool,
+    /// Print the curves --tonemap accepts
+    #[arg(long)]
+    list_tonemaps: bool,
+    /// Print the built-in palettes --palette accepts by name
+    #[arg(long)]
+    list_palettes: bool,
+    /// Print the output formats --format and --output extensions accept
+    #[arg(long)]
+    list_formats: bool,
+    #[command(flatten)]
+    options: ConfigArgs,
+}
+
+// The options of the config itself, which `--stdin` lines can also set
+#[derive(Debug, Default, ClapArgs)]
+struct ConfigArgs {
+    #[arg(long, value_name = "N")]
+    depth: Option<usize>,
+    #[arg(long, value_name = "N")]
+    render_depth: Option<usize>,
+    /// Skip levels whose cells are under F pixels across, 0.01 by default. 1 drops the
+    /// levels finer than a pixel, which barely change the image.
+    #[arg(long, value_name = "F")]
+    min_cell_size: Option<f32>,
+    #[arg(long, value_name = "F[,F]", value_parser = parse_vec2)]
+    growth: Option<Vec2>,
+    #[arg(long, value_name = "W[,H]", value_parser = parse_vec2)]
+    cells: Option<Vec2>,
+    /// World pixels, or a percentage of the cell diagonal such as 20%, which keeps the
+    /// falloff proportionate when --cells changes
+    #[arg(long, value_name = "F|P%")]
+    max_dist: Option<MaxDist>,
+    #[arg(long, value_name = "F")]
+    dist_power: Option<f32>,
+    #[arg(long, value_name = "N")]
+    relax: Option<usize>,
+    /// Keep feature points at least F cells apart, below 1
+    #[arg(long, value_name = "F")]
+    min_spacing: Option<f32>,
+    /// Take the distance from level N up, 0 being the coarsest
+    #[arg(long, value_name = "N")]
+    base_level: Option<usize>,
+    /// Feature points per cell at each level, coarsest first, one count per level 0 to
+    /// depth. Each level's time grows with its count.
+    #[arg(long, value_name = "N,N,...")]
+    points_per_level: Option<PointsPerLevel>,
+    #[arg(long, value_name = "W[,H]", value_parser = parse_vec2)]
+    toroidal: Option<Vec2>,
+    #[arg(long, value_name = "NAME")]
+    metric: Option<DistanceMetric>,
+    /// A distance metric for each level 0 to depth, coarsest first, in place of --metric
+    #[arg(long, value_name = "NAME,NAME,...")]
+    metrics_per_level: Option<MetricsPerLevel>,
+    /// Stretch each cell up to 1 + F times along its own random axis, 0 to keep cells
+    /// round. Around 1 looks organic; much more and cells clip at their neighbors.
+    #[arg(long, value_name = "F")]
+    anisotropy: Option<f32>,
+    /// How cells are hashed, legacy or splitmix. Splitmix mixes better but moves every
+    /// feature point and color for a given seed.
+    #[arg(long, value_name = "NAME")]
+    hash: Option<HashMixer>,
+    /// Pick colors from their own seed, keeping the layout of --seed
+    #[arg(long, value_name = "N")]
+    color_seed: Option<u64>,
+    #[arg(long, value_name = "NAME")]
+    color_mode: Option<ColorMode>,
+    /// One jittered color per cell (flat), per-pixel grain (dithered) or neighbors
+    /// blended smoothly (interpolated)
+    #[arg(long, value_name = "NAME")]
+    cell_coloring: Option<CellColoring>,
+    #[arg(long, value_name = "N")]
+    bands: Option<usize>,
+    #[arg(long, value_name = "F")]
+    color_blend: Option<f32>,
+    /// Fade colors across the finest walls where the coarse cell changes, over F of a
+    /// finest cell up to 1, so animations and pans don't pop pixels between colors.
+    /// Wider fades sample more: 1 is several times slower.
+    #[arg(long, value_name = "F")]
+    continuous_cells: Option<f32>,
+    /// Drift palette picks by X and Y palette lengths per coarse cell, for color themes
+    /// across the world. 0 keeps every cell random.
+    #[arg(long, value_name = "X[,Y]", value_parser = parse_vec2, allow_hyphen_values = true)]
+    spatial_color_bias: Option<Vec2>,
+    /// Compress colors brighter than the display range
+    #[arg(long, value_name = "NAME")]
+    tonemap: Option<ToneMap>,
+    /// How the palette's colors are encoded, srgb or linear, so supersampled pixels
+    /// average as light. sRGB by default, as palettes usually are.
+    #[arg(long, value_name = "NAME")]
+    color_space: Option<ColorSpace>,
+    /// Dither with a tiling grayscale image, such as blue noise
+    #[arg(long, value_name = "PATH", value_parser = load_dither_texture)]
+    dither_texture: Option<DitherTexture>,
+    /// A built-in palette, or colors from a GIMP .gpl, Adobe .act or .hex/.txt list of
+    /// RRGGBB [weight]
+    #[arg(long, value_name = "NAME|PATH", value_parser = load_palette)]
+    palette: Option<Palette>,
+    /// Grow and shrink the cells by up to AMPLITUDE (a fraction below 1) at SPEED
+    /// radians per second, 1 by default
+    #[arg(long, value_name = "AMPLITUDE[,SPEED]", value_parser = parse_pulse)]
+    pulse: Option<Pulse>,
+}
+
+impl ConfigArgs {
+    fn apply(self, config: &mut Config) {
+        let noise = &mut config.noise;
+        set(&mut noise.depth, self.depth);
+        set(&mut noise.growth, self.growth);
+        set(&mut noise.cells, self.cells);
+        set(&mut noise.relaxation_iterations, self.relax);
+        set(&mut noise.min_spacing, self.min_spacing);
+        set(&mut noise.points_per_level, self.points_per_level);
+        set(&mut noise.metric, self.metric);
+        set(&mut noise.metrics_per_level, self.metrics_per_level);
+        set(&mut noise.anisotropy_strength, self.anisotropy);
+        set(&mut noise.hash, self.hash);
+        if self.base_level.is_some() {
+            noise.base_level = self.base_level;
+        }
+        if self.toroidal.is_some() {
+            noise.toroidal_domain = self.toroidal;
+        }
+        if self.render_depth.is_some() {
+            config.render_depth = self.render_depth;
+        }
+        if self.color_seed.is_some() {
+            config.color_seed = self.color_seed;
+        }
+        if self.dither_texture.is_some() {
+            config.dither = self.dither_texture;
+        }
+        if self.pulse.is_some() {
+            config.pulse = self.pulse;
+        }
+        set(&mut config.min_cell_size, self.min_cell_size);
+        set(&mut config.max_dist, self.max_dist);
+        set(&mut config.dist_power, self.dist_power);
+        set(&mut config.color_mode, self.color_mode);
+        set(&mut config.cell_coloring, self.cell_coloring);
+        set(&mut config.bands, self.bands);
+        set(&mut config.color_blend, self.color_blend);
+        set(&mut config.continuous_cells, self.continuous_cells);
+        set(&mut config.spatial_color_bias, self.spatial_color_bias);
+        set(&mut config.tonemap, self.tonemap);
+        set(&mut config.color_space, self.color_space);
+        set(&mut config.palette, self.palette);
+    }
+}
+
+fn set<T>(field: &mut T, value: Option<T>) {
+    if let Some(value) = value {
+        *field = value;
+    }
+}
+
+#[derive(Debug, ClapArgs)]
+#[command(allow_negative_numbers = true)]
+struct ViewArgs {
+    #[command(flatten)]
+    common: CommonArgs,
+    /// Fail instead of rendering headlessly without a display
+    #[arg(long)]
+    no_fallback: bool,
+    /// Apply key=value lines from stdin while running, keyed like the options without
+    /// dashes (seed=7, dist-power=1.5). Blank lines and # comments are skipped, and bad
+    /// lines are reported and ignored.
+    #[arg(long)]
+    stdin: bool,
+    /// Magnify the image in the window: 1, 2, 4, 8, 16, 32 or fit
+    #[arg(long, value_name = "SCALE", value_parser = parse_scale)]
+    window_scale: Option<Scale>,
+    /// Fit a resizable window: stretch, aspect, center or upper-left
+    #[arg(long, value_name = "MODE", value_parser = parse_scale_mode)]
+    scale_mode: Option<ScaleMode>,
+}
+
+#[derive(Debug, ClapArgs)]
+#[command(allow_negative_numbers = true)]
+struct RenderArgs {
+    #[command(flatten)]
+    common: CommonArgs,
+    /// The output's extension picks the encoder: .png, .jpg, .bmp, .tiff, .exr, .npy
+    /// (the raw f32 distances, shape (height, width)) or .svg (see --list-formats)
+    #[arg(long, value_name = "PATH", default_value = "output.png")]
+    output: PathBuf,
+    /// Render only this window of the image. The corner may be negative.
+    #[arg(long, value_name = "X,Y,W,H", allow_hyphen_values = true)]
+    region: Option<Region>,
+    /// Pixels per side of each tile, or auto to time a few sizes and keep the fastest
+    #[arg(long, value_name = "N|auto", default_value = "auto")]
+    tile_size: TileSize,
+    /// Write a .png strip by strip without holding the whole image
+    #[arg(long)]
+    streaming: bool,
+    /// Also write each mip level as <name>_mip<n>.png
+    #[arg(long)]
+    mips: bool,
+    /// Also write <name>.json with everything needed to reproduce it. .png outputs
+    /// carry the same in their text chunks either way.
+    #[arg(long)]
+    sidecar: bool,
+    /// Use that format whatever the output's extension, such as svg for the cells as
+    /// filled SVG polygons traced along the pixel grid
+    #[arg(long, value_name = "NAME")]
+    format: Option<OutputFormat>,
+    /// Write the flow field around the feature points, x and y in red and green
+    #[arg(long, value_name = "toward|swirl")]
+    flow: Option<FlowMode>,
+    /// Write each cell's value blended between the K nearest cells by inverse distance
+    /// to the POWER (2 by default), a continuous 0 to 1 field for displacement.
+    /// Grayscale, or raw f32 for a .npy output.
+    #[arg(long, value_name = "K[,POWER]", value_parser = parse_idw)]
+    smooth_value: Option<Idw>,
+    /// Average N x N samples per pixel to smooth cell edges, respecting --color-space
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    supersample: usize,
+    /// Draw N evenly spaced contours of distance / max-dist over the image in white,
+    /// like the lines of a topographic map
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    contours: usize,
+    /// Render once and discard it first, so the timings and --profile leave out
+    /// first-run allocator and cache costs. Prints the cold and warm render times,
+    /// without encoding, except that --streaming encodes as it goes.
+    #[arg(long)]
+    warmup: bool,
+}
+
+#[derive(Debug, ClapArgs)]
+#[command(allow_negative_numbers = true)]
+struct SweepArgs {
+    #[command(flatten)]
+    common: CommonArgs,
+    /// depth, render-depth, growth, cells, max-dist or dist-power
+    #[arg(long, value_name = "NAME")]
+    param: SweepParam,
+    #[arg(long, value_name = "F")]
+    from: f32,
+    #[arg(long, value_name = "F")]
+    to: f32,
+    #[arg(long, value_name = "N", default_value_t = 5)]
+    steps: usize,
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    output_dir: PathBuf,
+}
+
+#[derive(Debug, ClapArgs)]
+#[command(allow_negative_numbers = true)]
+struct AnimateArgs {
+    #[command(flatten)]
+    common: CommonArgs,
+    #[arg(long, value_name = "N", default_value_t = 60)]
+    frames: usize,
+    #[arg(
+        long,
+        value_name = "X[,Y]",
+        value_parser = parse_vec2,
+        default_value = "8,0",
+        allow_hyphen_values = true
+    )]
+    speed: Vec2,
+    /// Instead of panning, circle every feature point once over the frames, RADIUS
+    /// cells out (up to 0.5), so the sequence loops seamlessly
+    #[arg(long, value_name = "RADIUS")]
+    orbit: Option<f32>,
+    /// Write the frames as a looping GIF instead of numbered PNGs
+    #[arg(long, value_name = "PATH")]
+    gif: Option<PathBuf>,
+    /// Also encode the frames into a video with ffmpeg, or print the ffmpeg command
+    /// when it isn't installed
+    #[arg(long, value_name = "PATH", conflicts_with = "gif")]
+    mp4: Option<PathBuf>,
+    /// Frames per second of the GIF or video
+    #[arg(long, value_name = "N", default_value_t = 30)]
+    fps: u32,
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    output_dir: PathBuf,
+}
+
+#[derive(Debug, ClapArgs)]
+#[command(allow_negative_numbers = true)]
+struct LevelsArgs {
+    #[command(flatten)]
+    common: CommonArgs,
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    output_dir: PathBuf,
+}
+
+#[derive(Debug, ClapArgs)]
+#[command(allow_negative_numbers = true)]
+struct SplitArgs {
+    #[command(flatten)]
+    common: CommonArgs,
+    /// Each half shows the same area, black at 0 and white from --max-dist out
+    #[arg(long, value_name = "PATH", default_value = "output.png")]
+    output: PathBuf,
+}
+
+#[derive(Debug, ClapArgs)]
+#[command(allow_negative_numbers = true)]
+struct CompareArgs {
+    #[command(flatten)]
+    common: CommonArgs,
+    #[arg(long, value_name = "PATH", default_value = "output.png")]
+    output: PathBuf,
+    /// Render B as the options plus this change, keyed like --stdin. Repeatable.
+    #[arg(long, value_name = "KEY=VALUE", required_unless_present = "against")]
+    with: Vec<String>,
+    /// Start B from a .json sidecar instead
+    #[arg(long, value_name = "PATH")]
+    against: Option<PathBuf>,
+    /// Scale the difference image
+    #[arg(long, value_name = "F", default_value_t = 8.0)]
+    amplify: f32,
+}
+
+#[derive(Debug, ClapArgs)]
+#[command(allow_negative_numbers = true)]
+struct InfoArgs {
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+// A `--stdin` line, as the one config option it sets
+#[derive(Debug, Parser)]
+#[command(no_binary_name = true, disable_help_flag = true)]
+struct OptionLine {
+    #[command(flatten)]
+    options: ConfigArgs,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum TileSize {
+    Auto,
+    Fixed(usize),
+}
+
+impl FromStr for TileSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(TileSize::Auto),
+            _ => s
+                .parse()
+                .map(TileSize::Fixed)
+                .map_err(|e| format!("expected a size or auto: {e}")),
+        }
+    }
+}
+
+// Parameters shared by every subcommand
+#[derive(Clone, Debug)]
+pub struct Params {
+    pub seed: Option<u64>,
+    // The text `seed` was hashed from, if any
+    pub seed_text: Option<String>,
+    // Everything except the seed, which is resolved once all arguments are parsed
+    pub config: Config,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            seed_text: None,
+            config: Config::default(),
+            width: 5120,
+            height: 1440,
+        }
+    }
+}
+
+impl Params {
+    pub fn config(&self, seed: u64) -> Config {
+        let mut config = self.config.clone();
+        config.noise.seed = seed;
+        config
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SweepParam {
+    Depth,
+    RenderDepth,
+    Growth,
+    Cells,
+    MaxDist,
+    DistPower,
+}
+
+impl SweepParam {
+    pub fn apply(self, params: &mut Params, value: f32) {
+        let config = &mut params.config;
+        match self {
+            SweepParam::Depth => config.noise.depth = value.round() as usize,
+            SweepParam::RenderDepth => config.render_depth = Some(value.round() as usize),
+            SweepParam::Growth => config.noise.growth = Vec2::splat(value),
+            SweepParam::Cells => config.noise.cells = Vec2::splat(value),
+            SweepParam::MaxDist => config.max_dist = config.max_dist.with_value(value),
+            SweepParam::DistPower => config.dist_power = value,
+        }
+    }
+}
+
+impl FromStr for SweepParam {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "depth" => Ok(SweepParam::Depth),
+            "render-depth" => Ok(SweepParam::RenderDepth),
+            "growth" => Ok(SweepParam::Growth),
+            "cells" => Ok(SweepParam::Cells),
+            "max-dist" => Ok(SweepParam::MaxDist),
+            "dist-power" => Ok(SweepParam::DistPower),
+            _ => Err(format!("unknown sweep parameter '{s}'")),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Command {
+    View {
+        window_scale: WindowScale,
+        // Render headlessly when no window can be opened
+        fallback: bool,
+        // Apply parameter updates read from stdin
+        live: bool,
+    },
+    Render {
+        output: PathBuf,
+        region: Option<Region>,
+        // None calibrates the tile size automatically
+        tile_size: Option<usize>,
+        streaming: bool,
+        flow: Option<FlowMode>,
+        mips: bool,
+        sidecar: bool,
+        // Overrides the format the output's extension implies
+        format: Option<OutputFormat>,
+        smooth_value: Option<Idw>,
+        // Samples per pixel along each axis, 1 for none
+        supersample: usize,
+        // Evenly spaced distance contours drawn over the image, 0 for none
+        contours: usize,
+        // Renders once, untimed, before the measured render
+        warmup: bool,
+    },
+    Sweep {
+        param: SweepParam,
+        from: f32,
+        to: f32,
+        steps: usize,
+        output_dir: PathBuf,
+    },
+    Animate {
+        frames: usize,
+        speed: Vec2,
+        // Orbit radius of a looping animation, which replaces panning
+        orbit: Option<f32>,
+        gif: Option<PathBuf>,
+        mp4: Option<PathBuf>,
+        fps: u32,
+        output_dir: PathBuf,
+    },
+    Split {
+        output: PathBuf,
+    },
+    Levels {
+        output_dir: PathBuf,
+    },
+    Compare {
+        output: PathBuf,
+        // `apply_line` changes turning the first config into the second
+        with: Vec<String>,
+        // A sidecar whose config the second starts from
+        against: Option<PathBuf>,
+        amplify: f32,
+    },
+    Info,
+    ListMetrics,
+    ListModes,
+    ListColorings,
+    ListToneMaps,
+    ListPalettes,
+    ListFormats,
+    // Prints the first pixels' cells, distances and colors for comparing builds
+    DumpPixels {
+        count: usize,
+    },
+    // Prints the render parameters embedded in a PNG
+    ReadMetadata {
+        path: PathBuf,
+    },
+    PalettePreview {
+        output: PathBuf,
+    },
+}
+
+impl Command {
+    // Whether it samples the noise, and so needs the config's max_dist and render depth
+    // settled first. The listings, info, the palette preview and metadata don't.
+    pub fn renders(&self) -> bool {
+        !matches!(
+            self,
+            Command::Info
+                | Command::ListMetrics
+                | Command::ListModes
+                | Command::ListColorings
+                | Command::ListToneMaps
+                | Command::ListPalettes
+                | Command::ListFormats
+                | Command::ReadMetadata { .. }
+                | Command::PalettePreview { .. }
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Args {
+    pub command: Command,
+    pub params: Params,
+    // None leaves rayon to pick, one per core
+    pub threads: Option<usize>,
+    // Lowers the render depth until a render is projected to fit this long
+    pub max_render_time: Option<Duration>,
+    // Sets max_dist to this percentile of sampled distances, 0 to 100
+    pub auto_max_dist: Option<f32>,
+    // Where to write the stage timings as folded stacks
+    pub profile: Option<PathBuf>,
+}
+
+impl Args {
+    // Parses the process arguments, exiting with clap's message on bad ones and for
+    // --help
+    pub fn parse() -> Result<Self, String> {
+        let cli = Cli::parse();
+        let (common, command) = match cli.command {
+            None => view_command(cli.view),
+            Some(CliCommand::View(args)) => view_command(args),
+            Some(CliCommand::Render(args)) => {
+                let command = Command::Render {
+                    output: args.output,
+                    region: args
+                        .region
+                        .or(args.common.config.as_ref().and_then(|c| c.region)),
+                    tile_size: match args.tile_size {
+                        TileSize::Auto => None,
+                        TileSize::Fixed(size) => Some(size),
+                    },
+                    streaming: args.streaming,
+                    flow: args.flow,
+                    mips: args.mips,
+                    sidecar: args.sidecar,
+                    format: args.format,
+                    smooth_value: args.smooth_value,
+                    supersample: args.supersample.max(1),
+                    contours: args.contours,
+                    warmup: args.warmup,
+                };
+                (args.common, command)
+            }
+            Some(CliCommand::Sweep(args)) => {
+                let command = Command::Sweep {
+                    param: args.param,
+                    from: args.from,
+                    to: args.to,
+                    steps: args.steps.max(1),
+                    output_dir: args.output_dir,
+                };
+                (args.common, command)
+            }
+            Some(CliCommand::Animate(args)) => {
+                let command = Command::Animate {
+                    frames: args.frames,
+                    speed: args.speed,
+                    orbit: args.orbit,
+                    gif: args.gif,
+                    mp4: args.mp4,
+                    fps: args.fps.max(1),
+                    output_dir: args.output_dir,
+                };
+                (args.common, command)
+            }
+            Some(CliCommand::Levels(args)) => (
+                args.common,
+                Command::Levels {
+                    output_dir: args.output_dir,
+                },
+            ),
+            Some(CliCommand::Split(args)) => (
+                args.common,
+                Command::Split {
+                    output: args.output,
+                },
+            ),
+            Some(CliCommand::Compare(args)) => {
+                let command = Command::Compare {
+                    output: args.output,
+                    with: args.with,
+                    against: args.against,
+                    amplify: args.amplify,
+                };
+                (args.common, command)
+            }
+            Some(CliCommand::Info(args)) => (args.common, Command::Info),
+        };
+        Self::resolve(common, command)
+    }
+
+    // Combines the options every subcommand takes with the subcommand's own, letting
+    // the one-off commands they ask for take its place
+    fn resolve(common: CommonArgs, command: Command) -> Result<Self, String> {
+        if common.profile.is_some() && !profile::ENABLED {
+            return Err("--profile needs a build with --features profile".into());
+        }
+
+        let mut params = Params::default();
+        if let Some(saved) = common.config {
+            params.seed = Some(saved.config.noise.seed);
+            params.config = saved.config;
+            (params.width, params.height) = (saved.width, saved.height);
+        }
+        if let Some(seed) = common.seed {
+            params.seed = Some(seed);
+        }
+        if let Some(text) = common.seed_from_string {
+            params.seed = Some(seed_from_str(&text));
+            params.seed_text = Some(text);
+        }
+        if common.daily {
+            let today = Date::today();
+            params.seed = Some(daily_seed(today));
+            params.seed_text = Some(today.to_string());
+        }
+        set(&mut params.width, common.width);
+        set(&mut params.height, common.height);
+        common.options.apply(&mut params.config);
+        // After the options so it applies to --palette and --config alike
+        if common.invert {
+            params.config.palette = params.config.palette.inverted();
+        }
+        // Turns about the center of the final size
+        if let Some(rotation) = common.rotate {
+            params.config.rotation = rotation;
+            params.config.rotation_center =
+                Vec2::new(params.width as f32, params.height as f32) * 0.5;
+        }
+
+        let command = match command {
+            _ if common.list_metrics => Command::ListMetrics,
+            _ if common.list_modes => Command::ListModes,
+            _ if common.list_colorings => Command::ListColorings,
+            _ if common.list_tonemaps => Command::ListToneMaps,
+            _ if common.list_palettes => Command::ListPalettes,
+            _ if common.list_formats => Command::ListFormats,
+            _ if let Some(output) = common.palette_preview => Command::PalettePreview { output },
+            _ if let Some(count) = common.dump_first_pixels => Command::DumpPixels { count },
+            _ if let Some(path) = common.read_metadata => Command::ReadMetadata { path },
+            command => command,
+        };
+
+        Ok(Self {
+            command,
+            params,
+            threads: if common.deterministic {
+                Some(1)
+            } else {
+                common.threads
+            },
+            max_render_time: common.max_render_time,
+            auto_max_dist: common.auto_max_dist,
+            profile: common.profile,
+        })
+    }
+}
+
+fn view_command(args: ViewArgs) -> (CommonArgs, Command) {
+    let mut window_scale = WindowScale::default();
+    set(&mut window_scale.scale, args.window_scale);
+    window_scale.scale_mode = args.scale_mode;
+    let command = Command::View {
+        window_scale,
+        fallback: !args.no_fallback,
+        live: args.stdin,
+    };
+    (args.common, command)
+}
+
+// Applies one line of the `--stdin` grammar: `key=value`, where the key is a config
+// option without its dashes (`dist-power=1.5`) or `seed`. Blank lines and lines
+// starting with # do nothing. Returns whether the config changed.
+pub fn apply_line(config: &mut Config, line: &str) -> Result<bool, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(false);
+    }
+    let (key, value) = line
+        .split_once('=')
+        .ok_or_else(|| format!("expected key=value, got '{line}'"))?;
+    let (key, value) = (key.trim(), value.trim());
+    if key == "seed" {
+        config.noise.seed = value
+            .parse()
+            .map_err(|e| format!("invalid value '{value}' for seed: {e}"))?;
+        return Ok(true);
+    }
+    // Joined with = so values starting with - aren't taken for flags
+    let parsed = OptionLine::try_parse_from([format!("--{key}={value}")]).map_err(|e| {
+        match e.kind() {
+            ErrorKind::UnknownArgument => format!("unknown key '{key}'"),
+            // Only the first line, without the usage clap appends
+            _ => e
+                .to_string()
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim_start_matches("error: ")
+                .to_string(),
+        }
+    })?;
+    parsed.options.apply(config);
+    Ok(true)
+}
+
+fn load_render_config(path: &str) -> Result<RenderConfig, String> {
+    RenderConfig::load(path).map_err(|e| format!("can't load config '{path}': {e}"))
+}
+
+fn load_dither_texture(path: &str) -> Result<DitherTexture, String> {
+    DitherTexture::load(path).map_err(|e| format!("can't load dither texture '{path}': {e}"))
+}
+
+fn load_palette(value: &str) -> Result<Palette, String> {
+    match BuiltinPalette::from_name(value) {
+        Some(builtin) => Ok(builtin.palette()),
+        None => Palette::load(value).map_err(|e| format!("can't load palette '{value}': {e}")),
+    }
+}
+
+fn parse<T: FromStr>(value: &str) -> Result<T, String>
+where
+    T::Err: std::fmt::Display,
+{
+    value
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid value '{value}': {e}"))
+}
+
+// Parses an image side, which can't be 0
+fn parse_size(value: &str) -> Result<usize, String> {
+    match parse(value)? {
+        0 => Err("must be at least 1".into()),
+        size => Ok(size),
+    }
+}
+
+fn parse_seconds(value: &str) -> Result<Duration, String> {
+    let seconds: f32 = parse(value)?;
+    if !(seconds.is_finite() && seconds > 0.0) {
+        return Err("must be a positive number of seconds".into());
+    }
+    Ok(Duration::from_secs_f32(seconds))
+}
+
+fn parse_percentile(value: &str) -> Result<f32, String> {
+    let percentile: f32 = parse(value)?;
+    if !(percentile > 0.0 && percentile <= 100.0) {
+        return Err("must be a percentile above 0, up to 100".into());
+    }
+    Ok(percentile)
+}
+
+// Parses `X` as a square or `X,Y`
+fn parse_vec2(value: &str) -> Result<Vec2, String> {
+    match value.split_once(',') {
+        Some((x, y)) => Ok(Vec2::new(parse(x)?, parse(y)?)),
+        None => Ok(Vec2::splat(parse(value)?)),
+    }
+}
+
+// Parses `K` or `K,POWER`
+fn parse_idw(value: &str) -> Result<Idw, String> {
+    let (k, power) = value.split_once(',').unwrap_or((value, "2"));
+    let idw = Idw {
+        k: parse(k)?,
+        power: parse(power)?,
+    };
+    if idw.k == 0 || !(idw.power.is_finite() && idw.power > 0.0) {
+        return Err("needs K of at least 1 and a positive POWER".into());
+    }
+    Ok(idw)
+}
+
+// Parses `AMPLITUDE` or `AMPLITUDE,SPEED`
+fn parse_pulse(value: &str) -> Result<Pulse, String> {
+    let (amplitude, speed) = value.split_once(',').unwrap_or((value, "1"));
+    Ok(Pulse {
+        amplitude: parse(amplitude)?,
+        speed: parse(speed)?,
+    })
+}
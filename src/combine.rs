@@ -0,0 +1,32 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::{CellId, WorleyNoise};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CombineOp {
+    #[default]
+    Min,
+    Max,
+    Average,
+    Multiply,
+}
+
+impl CombineOp {
+    pub fn apply(self, a: f32, b: f32) -> f32 {
+        match self {
+            CombineOp::Min => a.min(b),
+            CombineOp::Max => a.max(b),
+            CombineOp::Average => (a + b) * 0.5,
+            CombineOp::Multiply => a * b,
+        }
+    }
+}
+
+// Samples two independent fields and merges their normalized distances. The cell
+// always comes from `a`, so coloring follows the primary layer's layout.
+pub fn combine(a: &WorleyNoise, b: &WorleyNoise, op: CombineOp, pos: Vec2) -> (CellId, f32) {
+    let (cell, dist_a) = a.sample_normalized(pos);
+    let (_, dist_b) = b.sample_normalized(pos);
+    (cell, op.apply(dist_a, dist_b))
+}
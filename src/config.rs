@@ -0,0 +1,416 @@
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use glam::{IVec2, Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ColorSpace, CombineOp, DitherTexture, Named, Palette, ToneMap, WorleyError, WorleyNoise,
+    error::Result, mix64, named::parse_named,
+};
+
+// Mixed into `color_seed`, the fractional bits of the golden ratio
+const COLOR_SEED_SALT: u64 = 0x9e37_79b9_7f4a_7c15;
+
+// Missing fields take their defaults, so files saved before a field existed still load
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub noise: WorleyNoise,
+    // Renders the hierarchy cut off below this level, so scales can be compared without
+    // changing the layout of the coarser levels. None renders the full `noise.depth`.
+    pub render_depth: Option<usize>,
+    // Levels whose cells are smaller than this many pixels across aren't rendered. Each
+    // level only nudges a sample by up to its own cell size, so once cells are far below
+    // a pixel the rest of the hierarchy is invisible work; past a few dozen levels f32
+    // cell sizes underflow entirely. The default keeps every level of typical setups
+    // (the default config's finest cells are 0.04 pixels) while stopping runaway depths;
+    // 1 drops every level finer than a pixel for speed. 0 turns the guard off, short of
+    // cells shrinking to nothing.
+    pub min_cell_size: f32,
+    // Turns the pattern by this many radians about `rotation_center`, clockwise on screen
+    // since y points down, for diagonal patterns without reseeding. Each pixel's world
+    // position, after any pan or zoom, is turned the other way before it's sampled. A
+    // toroidal domain no longer tiles the image once rotated. 0 leaves positions exactly
+    // as they are.
+    pub rotation: f32,
+    // The world position `rotation` turns about, which the CLI puts at the image's center
+    pub rotation_center: Vec2,
+    pub max_dist: MaxDist,
+    pub dist_power: f32,
+    pub palette: Palette,
+    // Picks cell colors from a hash of its own, so colors can be rerolled without moving
+    // any cells. None picks them from the same hash that places the feature points.
+    pub color_seed: Option<u64>,
+    // Color of cells the palette can't color, such as any cell of an empty palette
+    pub fallback_color: Vec3,
+    pub color_mode: ColorMode,
+    // Whether a cell's color is one flat color, grained per pixel or blended with its
+    // neighbors'
+    pub cell_coloring: CellColoring,
+    // Drifts palette picks across the world for large-scale color themes, in palette
+    // lengths per coarse cell along x and y. Each cell's random place along the palette
+    // is squeezed into a window that narrows as the bias grows and slides with the
+    // cell's position, wrapping past the palette's end, so neighbors share a theme and
+    // still vary. Zero keeps every cell's pick independent; from a length of 1 up a
+    // cell's color follows from its position alone. Only `ColorMode::Hash` is biased.
+    pub spatial_color_bias: Vec2,
+    // Number of distance bands for `ColorMode::DistanceBanded`
+    pub bands: usize,
+    // Width of the color transition between neighboring cells as a fraction of the cell
+    // diagonal. 0 keeps hard edges.
+    pub color_blend: f32,
+    // Fades each pixel towards the color of the coarse cell it would switch to across the
+    // nearest wall between finest cells, over this fraction of the finest cells'
+    // diagonal, reaching an even mix on the wall. Colors then change continuously as
+    // walls sweep past in animations or pans instead of popping a pixel at a time, short
+    // of where three coarse cells meet. This needs the F2 work: every finest point within
+    // the fade is followed up through the hierarchy too, each about the cost of a sample,
+    // and the colors are blended the way `color_blend` does. 0 turns it off; up to 1.
+    pub continuous_cells: f32,
+    // Color that distant regions fade towards as the falloff approaches zero
+    pub background: Vec3,
+    // A second, independent field merged into the distances of the first
+    pub overlay: Option<WorleyNoise>,
+    pub combine_op: CombineOp,
+    // Grows and shrinks the cells over time in the viewer
+    pub pulse: Option<Pulse>,
+    // Compresses colors past the display range before quantization
+    pub tonemap: ToneMap,
+    // How colors are encoded, deciding how supersampled pixels are averaged
+    pub color_space: ColorSpace,
+    // Dithers the final 8-bit quantization from a tiling texture. Without one the output
+    // is truncated, leaving only the per-cell color jitter.
+    pub dither: Option<DitherTexture>,
+}
+
+// Scales the cell size by `1 + amplitude * sin(speed * t)`, with `speed` in radians per
+// second. Every length of the field scales together, toroidal domain included, so the
+// layout zooms smoothly about the origin instead of popping to a new arrangement.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Pulse {
+    // Below 1 so the cells never shrink to nothing
+    pub amplitude: f32,
+    pub speed: f32,
+}
+
+impl Pulse {
+    pub fn scale(&self, t: f32) -> f32 {
+        1.0 + self.amplitude * (self.speed * t).sin()
+    }
+}
+
+// The time an animation is shown at, for `Config::at_time`. Pausing holds the time where
+// it is, and resuming carries on from there rather than jumping ahead by the pause.
+#[derive(Clone, Copy, Debug)]
+pub struct AnimationClock {
+    start: Instant,
+    // The time held while paused
+    paused: Option<Duration>,
+}
+
+impl AnimationClock {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            start: now,
+            paused: None,
+        }
+    }
+
+    pub fn time(&self, now: Instant) -> Duration {
+        self.paused
+            .unwrap_or_else(|| now.saturating_duration_since(self.start))
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.is_some()
+    }
+
+    pub fn toggle_pause(&mut self, now: Instant) {
+        match self.paused.take() {
+            Some(held) => self.start = now.checked_sub(held).unwrap_or(now),
+            None => self.paused = Some(self.time(now)),
+        }
+    }
+}
+
+impl Config {
+    pub fn validate(&self) -> Result<()> {
+        self.noise.validate()?;
+        if self.render_depth.is_some_and(|d| d > self.noise.depth) {
+            return Err(WorleyError::invalid(
+                "render_depth",
+                format!("must not exceed depth {}", self.noise.depth),
+            ));
+        }
+        if let Some(overlay) = &self.overlay {
+            overlay.validate()?;
+        }
+        if !(self.min_cell_size.is_finite() && self.min_cell_size >= 0.0) {
+            return Err(WorleyError::invalid(
+                "min_cell_size",
+                "must not be negative",
+            ));
+        }
+        if !(self.rotation.is_finite() && self.rotation_center.is_finite()) {
+            return Err(WorleyError::invalid("rotation", "must be finite"));
+        }
+        let max_dist = self.max_dist.to_world(self.noise.cells);
+        if !(max_dist.is_finite() && max_dist > 0.0) {
+            return Err(WorleyError::invalid("max_dist", "must be positive"));
+        }
+        if !(self.color_blend.is_finite() && self.color_blend >= 0.0) {
+            return Err(WorleyError::invalid("color_blend", "must not be negative"));
+        }
+        if !(0.0..=1.0).contains(&self.continuous_cells) {
+            return Err(WorleyError::invalid("continuous_cells", "must be in 0..=1"));
+        }
+        if !self.spatial_color_bias.is_finite() {
+            return Err(WorleyError::invalid("spatial_color_bias", "must be finite"));
+        }
+        if !self.dist_power.is_finite() {
+            return Err(WorleyError::invalid("dist_power", "must be finite"));
+        }
+        self.palette.validate()?;
+        if let Some(dither) = &self.dither {
+            dither.validate()?;
+        }
+        if let Some(pulse) = self.pulse {
+            if !(0.0..1.0).contains(&pulse.amplitude) {
+                return Err(WorleyError::invalid("pulse amplitude", "must be in 0..1"));
+            }
+            if !pulse.speed.is_finite() {
+                return Err(WorleyError::invalid("pulse speed", "must be finite"));
+            }
+        }
+        Ok(())
+    }
+
+    // This config as it looks `t` seconds into the pulse, or unchanged without one
+    pub fn at_time(&self, t: f32) -> Config {
+        let mut config = self.clone();
+        if let Some(pulse) = self.pulse {
+            let scale = pulse.scale(t);
+            for noise in std::iter::once(&mut config.noise).chain(&mut config.overlay) {
+                noise.cells *= scale;
+                noise.toroidal_domain = noise.toroidal_domain.map(|d| d * scale);
+            }
+        }
+        config
+    }
+
+    // Where the world position `pos` samples the field, turned by `rotation`
+    pub fn sample_pos(&self, pos: Vec2) -> Vec2 {
+        if self.rotation == 0.0 {
+            return pos;
+        }
+        let turn = Vec2::from_angle(-self.rotation);
+        self.rotation_center + turn.rotate(pos - self.rotation_center)
+    }
+
+    // The hash a cell's color is picked from
+    pub fn color_hash(&self, cell: IVec2) -> u64 {
+        match self.color_seed {
+            // Salted so a color seed equal to the seed still differs from the layout
+            Some(seed) => mix64(self.noise.hash.hash(cell, seed ^ COLOR_SEED_SALT)),
+            None => self.noise.cell_hash(cell),
+        }
+    }
+
+    // The depth the primary field renders to: `render_depth`, cut short where cells
+    // shrink below `min_cell_size`
+    pub fn effective_depth(&self) -> usize {
+        let depth = self.render_depth.unwrap_or(self.noise.depth);
+        depth.min(self.noise.depth_for_min_cell_size(self.min_cell_size))
+    }
+
+    // The primary field as rendered, truncated to `effective_depth`
+    pub fn rendered_noise(&self) -> WorleyNoise {
+        WorleyNoise {
+            depth: self.effective_depth(),
+            ..self.noise
+        }
+    }
+
+    // The overlay as rendered, cut short where cells shrink below `min_cell_size`
+    pub fn rendered_overlay(&self) -> Option<WorleyNoise> {
+        self.overlay.map(|overlay| WorleyNoise {
+            depth: overlay.depth_for_min_cell_size(self.min_cell_size),
+            ..overlay
+        })
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            noise: WorleyNoise::default(),
+            render_depth: None,
+            min_cell_size: 0.01,
+            rotation: 0.0,
+            rotation_center: Vec2::ZERO,
+            max_dist: MaxDist::Pixels(70.0),
+            dist_power: 1.5,
+            palette: Palette::default(),
+            color_seed: None,
+            fallback_color: Vec3::new(255.0, 0.0, 255.0),
+            color_mode: ColorMode::default(),
+            cell_coloring: CellColoring::default(),
+            spatial_color_bias: Vec2::ZERO,
+            bands: 4,
+            color_blend: 0.0,
+            continuous_cells: 0.0,
+            background: Vec3::ZERO,
+            overlay: None,
+            combine_op: CombineOp::default(),
+            pulse: None,
+            tonemap: ToneMap::None,
+            color_space: ColorSpace::default(),
+            dither: None,
+        }
+    }
+}
+
+// The distance at which the falloff reaches the background color
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MaxDist {
+    // World pixels, which need retuning whenever the cell size changes
+    Pixels(f32),
+    // A fraction of the coarsest cell's diagonal, so the falloff keeps its look at
+    // any scale. Recommended for new configs.
+    CellFraction(f32),
+}
+
+impl MaxDist {
+    pub fn to_world(self, cells: Vec2) -> f32 {
+        match self {
+            MaxDist::Pixels(dist) => dist,
+            MaxDist::CellFraction(fraction) => fraction * cells.length(),
+        }
+    }
+
+    // Replaces the value while keeping the units
+    pub fn with_value(self, value: f32) -> Self {
+        match self {
+            MaxDist::Pixels(_) => MaxDist::Pixels(value),
+            MaxDist::CellFraction(_) => MaxDist::CellFraction(value),
+        }
+    }
+}
+
+// Parses `70` as pixels or `25%` as a fraction of the cell diagonal
+impl FromStr for MaxDist {
+    type Err = WorleyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |e| WorleyError::invalid("max_dist", format!("'{s}': {e}"));
+        match s.strip_suffix('%') {
+            Some(percent) => Ok(MaxDist::CellFraction(
+                percent.trim().parse::<f32>().map_err(invalid)? / 100.0,
+            )),
+            None => Ok(MaxDist::Pixels(s.trim().parse().map_err(invalid)?)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColorMode {
+    // Each cell picks a palette entry from its hash
+    #[default]
+    Hash,
+    // The distance picks a band of the palette and the hash picks within it, so colors
+    // change from cell centers out to cell edges
+    DistanceBanded,
+    // A distinct hue for the hierarchy level that contributes most to each pixel's
+    // distance, for tuning `depth` and `growth`
+    LevelDebug,
+    // The low 24 bits of each cell's `cell_hash` as red, green and blue, untouched by
+    // the falloff and tonemap. Any visible structure or banding is a hashing problem.
+    CellHash,
+}
+
+impl Named for ColorMode {
+    const KIND: &'static str = "color mode";
+    const ALL: &'static [Self] = &[
+        ColorMode::Hash,
+        ColorMode::DistanceBanded,
+        ColorMode::LevelDebug,
+        ColorMode::CellHash,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            ColorMode::Hash => "hash",
+            ColorMode::DistanceBanded => "banded",
+            ColorMode::LevelDebug => "levels",
+            ColorMode::CellHash => "cellhash",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            ColorMode::Hash => "each cell picks a palette color from its hash",
+            ColorMode::DistanceBanded => "the distance picks a band of the palette (--bands)",
+            ColorMode::LevelDebug => "one hue per hierarchy level, showing which dominates",
+            ColorMode::CellHash => "each cell's raw hash bits as RGB, for spotting patterns",
+        }
+    }
+}
+
+impl FromStr for ColorMode {
+    type Err = WorleyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_named(s)
+    }
+}
+
+// How the color stage spreads a cell's palette color over its pixels
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CellColoring {
+    // Every pixel takes its cell's single color. The cell's hash jitters that color
+    // slightly, once per cell, so cells sharing a palette entry still differ.
+    #[default]
+    Flat,
+    // The same jitter drawn afresh for every pixel instead, graining the cell's exact
+    // palette color
+    Dithered,
+    // The colors of the nearest coarse cells blended by inverse distance, so color
+    // varies smoothly across the image and cell walls only show in the falloff
+    Interpolated,
+}
+
+impl Named for CellColoring {
+    const KIND: &'static str = "cell coloring";
+    const ALL: &'static [Self] = &[
+        CellColoring::Flat,
+        CellColoring::Dithered,
+        CellColoring::Interpolated,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            CellColoring::Flat => "flat",
+            CellColoring::Dithered => "dithered",
+            CellColoring::Interpolated => "interpolated",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            CellColoring::Flat => "one color per cell, jittered per cell",
+            CellColoring::Dithered => "the cell's palette color with per-pixel grain",
+            CellColoring::Interpolated => "neighboring cells' colors blended smoothly",
+        }
+    }
+}
+
+impl FromStr for CellColoring {
+    type Err = WorleyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_named(s)
+    }
+}
@@ -0,0 +1,128 @@
+//! Iso-value contours of a field as polylines, for outlines, topographic renders and
+//! plotter or CNC toolpaths. Marching squares runs over the squares between samples,
+//! each sample standing at its pixel's position, and crossings are placed along the
+//! square's edges by linear interpolation. Segments are then chained through the edges
+//! they share, so every contour comes out as one unbroken polyline.
+
+use std::collections::HashMap;
+
+use glam::{USizeVec2, Vec2};
+
+use crate::Buffer;
+
+// The contours of `field` at each of `levels`, in order of level and then of where
+// each contour is first met scanning row by row. Points are in pixel coordinates.
+// Contours that leave the field run from border to border, and closed ones repeat their
+// first point at the end. Samples above a level are inside it, and NaNs never are.
+pub fn contours(field: &Buffer<f32>, levels: &[f32]) -> Vec<Vec<Vec2>> {
+    levels
+        .iter()
+        .flat_map(|&level| contours_at(field, level))
+        .collect()
+}
+
+// An edge between two neighboring samples: the left or top one, and whether the
+// other is to its right
+type Edge = (USizeVec2, bool);
+
+fn contours_at(field: &Buffer<f32>, level: f32) -> Vec<Vec<Vec2>> {
+    let (width, height) = (field.width, field.height);
+    if width < 2 || height < 2 {
+        return Vec::new();
+    }
+    let value = |pos: USizeVec2| field.buff[pos.x + pos.y * width];
+    // Where the contour crosses an edge with one end inside and one outside
+    let crossing = |(start, right): Edge| {
+        let end = start + if right { USizeVec2::X } else { USizeVec2::Y };
+        let (a, b) = (value(start), value(end));
+        // Halfway when the far end is NaN
+        let t = (level - a) / (b - a);
+        let t = if t.is_nan() { 0.5 } else { t.clamp(0.0, 1.0) };
+        start.as_vec2().lerp(end.as_vec2(), t)
+    };
+
+    // Each crossed edge joins the segments of the one or two squares beside it
+    let mut links: HashMap<Edge, Vec<Edge>> = HashMap::new();
+    let mut order = Vec::new();
+    for y in 0..height - 1 {
+        for x in 0..width - 1 {
+            let corner = USizeVec2::new(x, y);
+            let inside = |offset: USizeVec2| value(corner + offset) > level;
+            let case = inside(USizeVec2::ZERO) as u8
+                | (inside(USizeVec2::X) as u8) << 1
+                | (inside(USizeVec2::ONE) as u8) << 2
+                | (inside(USizeVec2::Y) as u8) << 3;
+            let (top, right) = ((corner, true), (corner + USizeVec2::X, false));
+            let (bottom, left) = ((corner + USizeVec2::Y, true), (corner, false));
+            let segments: &[(Edge, Edge)] = match case {
+                0 | 15 => &[],
+                1 | 14 => &[(left, top)],
+                2 | 13 => &[(top, right)],
+                3 | 12 => &[(left, right)],
+                4 | 11 => &[(right, bottom)],
+                6 | 9 => &[(top, bottom)],
+                7 | 8 => &[(left, bottom)],
+                // Saddles, split by the mean of the corners
+                5 | 10 => {
+                    let mean = [USizeVec2::ZERO, USizeVec2::X, USizeVec2::ONE, USizeVec2::Y]
+                        .map(|offset| value(corner + offset))
+                        .iter()
+                        .sum::<f32>()
+                        / 4.0;
+                    // Whether the middle joins the top left and bottom right corners
+                    if (mean > level) == (case == 5) {
+                        &[(left, bottom), (top, right)]
+                    } else {
+                        &[(left, top), (right, bottom)]
+                    }
+                }
+                _ => unreachable!(),
+            };
+            for &(a, b) in segments {
+                for (from, to) in [(a, b), (b, a)] {
+                    let linked = links.entry(from).or_default();
+                    if linked.is_empty() {
+                        order.push(from);
+                    }
+                    linked.push(to);
+                }
+            }
+        }
+    }
+
+    // Open contours start from the edges only one segment reaches, at the border, and
+    // closed ones from anywhere left over
+    let starts: Vec<Edge> = order
+        .iter()
+        .filter(|edge| links[edge].len() == 1)
+        .chain(&order)
+        .copied()
+        .collect();
+    let mut lines = Vec::new();
+    for start in starts {
+        if links[&start].is_empty() {
+            continue;
+        }
+        let mut line = vec![crossing(start)];
+        let mut edge = start;
+        while let Some(next) = links.get_mut(&edge).and_then(Vec::pop) {
+            let back = links.get_mut(&next).unwrap();
+            back.remove(back.iter().position(|&e| e == edge).unwrap());
+            line.push(crossing(next));
+            edge = next;
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+// Draws the line through `points` one pixel wide, clipped at the buffer's edges
+pub fn draw_polyline<T: Clone>(buffer: &mut Buffer<T>, points: &[Vec2], val: T) {
+    for pair in points.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        let steps = (to - from).abs().max_element().ceil().max(1.0) as usize;
+        for step in 0..=steps {
+            buffer.setf(from.lerp(to, step as f32 / steps as f32), val.clone());
+        }
+    }
+}
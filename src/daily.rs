@@ -0,0 +1,90 @@
+//! Seeds shared by date, so everyone rendering on the same day gets the same image. The
+//! seed is `seed_from_str` of the date written as `YYYY-MM-DD`, so any day's seed can
+//! also be reproduced later with `--seed-from-string`.
+
+use std::{
+    fmt,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{WorleyError, seed_from_str};
+
+// A day of the proleptic Gregorian calendar
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    // The current day in UTC, so the seed doesn't depend on the time zone
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self::from_days((seconds / 86_400) as i64)
+    }
+
+    // The day `days` after 1970-01-01, by Howard Hinnant's `civil_from_days`
+    pub fn from_days(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + (month <= 2) as i64) as i32;
+        Self { year, month, day }
+    }
+
+    fn days_in_month(year: i32, month: u32) -> u32 {
+        let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        match month {
+            2 if leap => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+// Parses `YYYY-MM-DD`, rejecting days the month doesn't have
+impl FromStr for Date {
+    type Err = WorleyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || WorleyError::invalid("date", format!("'{s}' must be YYYY-MM-DD"));
+        let mut parts = s.trim().splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(invalid);
+        let (year, month, day) = (next()?, next()?, next()?);
+        let date = Self {
+            year: year.parse().map_err(|_| invalid())?,
+            month: month.parse().map_err(|_| invalid())?,
+            day: day.parse().map_err(|_| invalid())?,
+        };
+        if !(1..=12).contains(&date.month)
+            || !(1..=Self::days_in_month(date.year, date.month)).contains(&date.day)
+        {
+            return Err(WorleyError::invalid(
+                "date",
+                format!("'{s}' is not a day of the calendar"),
+            ));
+        }
+        Ok(date)
+    }
+}
+
+// The seed everyone shares on `date`
+pub fn daily_seed(date: Date) -> u64 {
+    seed_from_str(&date.to_string())
+}
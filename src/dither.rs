@@ -0,0 +1,76 @@
+//! Ordered dithering from a user supplied tile, typically blue noise. Adding a per-pixel
+//! threshold in 0..1 before truncating to 8 bits turns the banding of smooth falloffs
+//! into fine, evenly spread grain, and the result stays deterministic.
+
+use std::{fmt, path::Path};
+
+use glam::{IVec2, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::{WorleyError, error::Result};
+
+// Serialized whole, so a saved config reproduces the dithering without the source image
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct DitherTexture {
+    pub width: usize,
+    pub height: usize,
+    // Row major thresholds in 0..1
+    pub thresholds: Vec<f32>,
+}
+
+impl DitherTexture {
+    // Loads a tile from any image format, using its luminance
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let image = image::open(path)?.to_luma8();
+        if image.width() == 0 || image.height() == 0 {
+            return Err(WorleyError::invalid("dither texture", "must not be empty"));
+        }
+        Ok(Self {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            // Thresholds stop short of 1 so a full channel never rounds up past 255
+            thresholds: image.pixels().map(|p| p.0[0] as f32 / 256.0).collect(),
+        })
+    }
+
+    // A tile read back from a saved config may have been edited or cut short, and any
+    // mismatch between its size and its thresholds would sample outside them
+    pub fn validate(&self) -> Result<()> {
+        if self.width == 0 || self.height == 0 {
+            return Err(WorleyError::invalid("dither texture", "must not be empty"));
+        }
+        if i32::try_from(self.width).is_err() || i32::try_from(self.height).is_err() {
+            return Err(WorleyError::invalid("dither texture", "is too large"));
+        }
+        if self.width.checked_mul(self.height) != Some(self.thresholds.len()) {
+            return Err(WorleyError::invalid(
+                "dither texture",
+                format!(
+                    "a {}x{} tile needs {} thresholds, not {}",
+                    self.width,
+                    self.height,
+                    self.width.saturating_mul(self.height),
+                    self.thresholds.len()
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    // The threshold for a position, repeating the tile across the plane
+    pub fn threshold(&self, pos: Vec2) -> f32 {
+        let size = IVec2::new(self.width as i32, self.height as i32);
+        let p = pos.floor().as_ivec2().rem_euclid(size);
+        self.thresholds[p.x as usize + p.y as usize * self.width]
+    }
+}
+
+// Thresholds are left out, a tile easily holds thousands of them
+impl fmt::Debug for DitherTexture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DitherTexture")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish_non_exhaustive()
+    }
+}
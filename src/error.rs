@@ -0,0 +1,70 @@
+use image::ImageError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum WorleyError {
+    #[error("invalid {name}: {reason}")]
+    InvalidParameter { name: &'static str, reason: String },
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("unsupported format: {0}")]
+    UnsupportedFormat(String),
+    #[error("image error: {0}")]
+    Image(ImageError),
+    #[error("window error: {0}")]
+    Window(String),
+    #[error("PNG encoding error: {0}")]
+    Png(png::EncodingError),
+    #[error("PNG decoding error: {0}")]
+    PngDecoding(png::DecodingError),
+    #[error("JSON error: {0}")]
+    Json(serde_json::Error),
+}
+
+impl WorleyError {
+    pub fn invalid(name: &'static str, reason: impl Into<String>) -> Self {
+        WorleyError::InvalidParameter {
+            name,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl From<ImageError> for WorleyError {
+    fn from(e: ImageError) -> Self {
+        match e {
+            ImageError::IoError(e) => WorleyError::Io(e),
+            ImageError::Unsupported(e) => WorleyError::UnsupportedFormat(e.to_string()),
+            e => WorleyError::Image(e),
+        }
+    }
+}
+
+impl From<png::EncodingError> for WorleyError {
+    fn from(e: png::EncodingError) -> Self {
+        match e {
+            png::EncodingError::IoError(e) => WorleyError::Io(e),
+            e => WorleyError::Png(e),
+        }
+    }
+}
+
+impl From<png::DecodingError> for WorleyError {
+    fn from(e: png::DecodingError) -> Self {
+        match e {
+            png::DecodingError::IoError(e) => WorleyError::Io(e),
+            e => WorleyError::PngDecoding(e),
+        }
+    }
+}
+
+impl From<serde_json::Error> for WorleyError {
+    fn from(e: serde_json::Error) -> Self {
+        match e.io_error_kind() {
+            Some(_) => WorleyError::Io(e.into()),
+            None => WorleyError::Json(e),
+        }
+    }
+}
+
+pub type Result<T, E = WorleyError> = std::result::Result<T, E>;
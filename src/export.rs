@@ -0,0 +1,177 @@
+//! Batch exports that split a render into several images.
+
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+use glam::U8Vec3;
+use image::{
+    Delay, DynamicImage, Frame,
+    codecs::gif::{GifEncoder, Repeat},
+};
+
+use crate::{Buffer, Config, Named, Orbit, error::Result, render};
+
+// The parameters behind a render as PNG text keywords and values
+pub fn render_metadata(config: &Config) -> Vec<(String, String)> {
+    let noise = &config.noise;
+    [
+        ("seed", noise.seed.to_string()),
+        ("depth", noise.depth.to_string()),
+        (
+            "render_depth",
+            config.render_depth.unwrap_or(noise.depth).to_string(),
+        ),
+        ("min_cell_size", config.min_cell_size.to_string()),
+        ("rotation", config.rotation.to_string()),
+        (
+            "rotation_center",
+            format!("{},{}", config.rotation_center.x, config.rotation_center.y),
+        ),
+        ("growth", format!("{},{}", noise.growth.x, noise.growth.y)),
+        ("cells", format!("{},{}", noise.cells.x, noise.cells.y)),
+        ("relax", noise.relaxation_iterations.to_string()),
+        ("min_spacing", noise.min_spacing.to_string()),
+        (
+            "base_level",
+            noise
+                .base_level
+                .map_or("none".to_string(), |l| l.to_string()),
+        ),
+        (
+            "points_per_level",
+            format!("{:?}", noise.points_per_level.counts()),
+        ),
+        ("metric", noise.metric.name().to_string()),
+        (
+            "metrics_per_level",
+            noise
+                .metrics_per_level
+                .metrics()
+                .iter()
+                .map(|m| m.name())
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        ("anisotropy", noise.anisotropy_strength.to_string()),
+        (
+            "color_seed",
+            config
+                .color_seed
+                .map_or("none".to_string(), |s| s.to_string()),
+        ),
+        ("color_mode", config.color_mode.name().to_string()),
+        ("cell_coloring", config.cell_coloring.name().to_string()),
+        ("continuous_cells", config.continuous_cells.to_string()),
+        (
+            "spatial_color_bias",
+            format!(
+                "{},{}",
+                config.spatial_color_bias.x, config.spatial_color_bias.y
+            ),
+        ),
+        ("max_dist", format!("{:?}", config.max_dist)),
+        ("dist_power", config.dist_power.to_string()),
+    ]
+    .into_iter()
+    .map(|(key, value)| (format!("worley:{key}"), value))
+    .collect()
+}
+
+// Writes `level_0.png` through `level_<depth>.png` into `dir`. Level 0 is the full
+// hierarchy and each following level drops the finest remaining one through
+// `render_depth`, so the last shows the coarsest cells alone. Every file records its
+// parameters and level in PNG text chunks.
+pub fn export_levels(
+    config: &Config,
+    width: usize,
+    height: usize,
+    dir: impl AsRef<Path>,
+) -> Result<Vec<PathBuf>> {
+    let depth = config.noise.depth;
+    let mut buffer = Buffer::new(width, height, U8Vec3::ZERO);
+    let mut paths = Vec::with_capacity(depth + 1);
+    for level in 0..=depth {
+        let config = Config {
+            render_depth: Some(depth - level),
+            ..config.clone()
+        };
+        render(&mut buffer, &config);
+
+        let mut text = render_metadata(&config);
+        text.push(("worley:level".to_string(), level.to_string()));
+        let path = dir.as_ref().join(format!("level_{level}.png"));
+        buffer.save_png_with_text(&path, &text)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+// Renders a seamless loop of `frames` frames in which every feature point orbits its
+// resting place once, `radius` cells out, passing each frame to `on_frame` in order.
+// Another frame past the end would be identical to the first.
+pub fn render_orbit(
+    config: &Config,
+    width: usize,
+    height: usize,
+    frames: usize,
+    radius: f32,
+    mut on_frame: impl FnMut(usize, &Buffer<U8Vec3>) -> Result<()>,
+) -> Result<()> {
+    let mut buffer = Buffer::new(width, height, U8Vec3::ZERO);
+    for frame in 0..frames {
+        let mut config = config.clone();
+        config.noise.orbit = Some(Orbit::at_frame(radius, frame, frames));
+        config.validate()?;
+        render(&mut buffer, &config);
+        on_frame(frame, &buffer)?;
+    }
+    Ok(())
+}
+
+// The file name of frame `frame` of a `frames` long sequence, numbered from 0 and
+// padded to at least 4 digits so every frame sorts in order by name
+pub fn frame_file_name(frame: usize, frames: usize) -> String {
+    format!("frame_{frame:0width$}.png", width = frame_digits(frames))
+}
+
+fn frame_digits(frames: usize) -> usize {
+    frames.saturating_sub(1).to_string().len().max(4)
+}
+
+// Arguments to ffmpeg that encode the `frames` frames written to `dir` under
+// `frame_file_name` into an H.264 `video` at `fps` frames per second. Odd sizes are
+// padded by a pixel, since the widely playable yuv420p needs even ones.
+pub fn ffmpeg_args(dir: &Path, frames: usize, fps: u32, video: &Path) -> Vec<String> {
+    let pattern = dir.join(format!("frame_%0{}d.png", frame_digits(frames)));
+    [
+        "-y",
+        "-framerate",
+        &fps.to_string(),
+        "-i",
+        &pattern.to_string_lossy(),
+        "-vf",
+        "pad=ceil(iw/2)*2:ceil(ih/2)*2",
+        "-c:v",
+        "libx264",
+        "-pix_fmt",
+        "yuv420p",
+        &video.to_string_lossy(),
+    ]
+    .map(String::from)
+    .into()
+}
+
+// Writes `frames` as an endlessly repeating GIF, showing each for `frame_ms`
+pub fn save_gif(path: impl AsRef<Path>, frames: &[Buffer<U8Vec3>], frame_ms: u32) -> Result<()> {
+    let mut encoder = GifEncoder::new(BufWriter::new(File::create(path)?));
+    encoder.set_repeat(Repeat::Infinite)?;
+    for buffer in frames {
+        let rgba = DynamicImage::ImageRgb8(buffer.to_image()).into_rgba8();
+        let delay = Delay::from_numer_denom_ms(frame_ms, 1);
+        encoder.encode_frame(Frame::from_parts(rgba, 0, 0, delay))?;
+    }
+    Ok(())
+}
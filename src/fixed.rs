@@ -0,0 +1,238 @@
+//! Fixed-point sampling for targets without fast floating point, such as small
+//! microcontrollers or shader languages where integer math is cheaper. Positions, cell
+//! sizes and distances are 16.16 `Fixed` values, and the search, distances and blend
+//! run entirely on integers. The cell hashes are shared with the float path, which stays
+//! the default and the reference: every cell and feature point is the same, and the
+//! results differ only by rounding.
+//!
+//! The fixed path covers the plain layout with a single metric. Relaxation, spacing,
+//! orbits, extra points per level, per-level metrics, anisotropy and toroidal domains
+//! are ignored here.
+
+use core::ops::{Add, Div, Mul, Sub};
+
+use glam::{IVec2, Vec2};
+
+use crate::worley::{CellId, DistanceMetric, WorleyNoise, center_bits};
+
+// How far a fixed-point distance strays from `WorleyNoise::sample`, in pixels, for
+// positions within a few thousand pixels of the origin and finest cells of at least a
+// pixel. Each level rounds its feature points and cell sizes to 1/65536, and the error
+// grows with the number of finest cells between the origin and the sample; typical
+// errors are around a thousandth of a pixel. Where two feature points are nearly
+// equidistant the rounding can pick the other one, so a few samples in a thousand along
+// cell walls at any level fall outside it.
+pub const TOLERANCE: f32 = 1.0 / 256.0;
+
+// A signed 16.16 fixed-point number, covering -32768 to just under 32768 in steps of
+// 1/65536
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed(pub i32);
+
+impl Fixed {
+    pub const FRAC_BITS: u32 = 16;
+    pub const ZERO: Fixed = Fixed(0);
+    pub const ONE: Fixed = Fixed(1 << Self::FRAC_BITS);
+    pub const MAX: Fixed = Fixed(i32::MAX);
+
+    pub fn from_int(n: i32) -> Self {
+        Fixed(n << Self::FRAC_BITS)
+    }
+
+    // Rounds to the nearest step, saturating outside the range
+    pub fn from_f32(x: f32) -> Self {
+        // `as` truncates towards zero, and `f32::round` needs std
+        let scaled = x * Self::ONE.0 as f32;
+        Fixed(if scaled < 0.0 {
+            scaled - 0.5
+        } else {
+            scaled + 0.5
+        } as i32)
+    }
+
+    pub fn to_f32(self) -> f32 {
+        self.0 as f32 / Self::ONE.0 as f32
+    }
+
+    // Rounds towards negative infinity, like `f32::floor`
+    pub fn floor_int(self) -> i32 {
+        self.0 >> Self::FRAC_BITS
+    }
+
+    pub fn abs(self) -> Self {
+        Fixed(self.0.wrapping_abs())
+    }
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+
+    fn add(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0.wrapping_add(rhs.0))
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+
+    fn sub(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0.wrapping_sub(rhs.0))
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Fixed;
+
+    fn mul(self, rhs: Fixed) -> Fixed {
+        Fixed(((self.0 as i64 * rhs.0 as i64) >> Self::FRAC_BITS) as i32)
+    }
+}
+
+// Rounds towards zero
+impl Div for Fixed {
+    type Output = Fixed;
+
+    fn div(self, rhs: Fixed) -> Fixed {
+        Fixed((((self.0 as i64) << Self::FRAC_BITS) / rhs.0 as i64) as i32)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FixedVec2 {
+    pub x: Fixed,
+    pub y: Fixed,
+}
+
+impl FixedVec2 {
+    pub fn new(x: Fixed, y: Fixed) -> Self {
+        Self { x, y }
+    }
+
+    pub fn from_vec2(v: Vec2) -> Self {
+        Self::new(Fixed::from_f32(v.x), Fixed::from_f32(v.y))
+    }
+
+    pub fn to_vec2(self) -> Vec2 {
+        Vec2::new(self.x.to_f32(), self.y.to_f32())
+    }
+}
+
+// The integer square root of `n`, rounded down
+fn isqrt(n: u64) -> u64 {
+    let (mut rem, mut root) = (n, 0u64);
+    let mut bit = 1u64 << 62;
+    while bit > n {
+        bit >>= 2;
+    }
+    while bit != 0 {
+        if rem >= root + bit {
+            rem -= root + bit;
+            root = (root >> 1) + bit;
+        } else {
+            root >>= 1;
+        }
+        bit >>= 2;
+    }
+    root
+}
+
+impl DistanceMetric {
+    // `distance` on a 16.16 delta. Squares are summed in 32.32, so Euclidean distances
+    // stay exact to the last step until they pass 32768.
+    pub fn distance_fixed(self, delta: FixedVec2) -> Fixed {
+        let (x, y) = (
+            delta.x.0.unsigned_abs() as u64,
+            delta.y.0.unsigned_abs() as u64,
+        );
+        let dist = match self {
+            DistanceMetric::Euclidean => isqrt((x * x).saturating_add(y * y)),
+            DistanceMetric::Manhattan => x + y,
+            DistanceMetric::Chebyshev => x.max(y),
+        };
+        Fixed(dist.min(i32::MAX as u64) as i32)
+    }
+}
+
+impl WorleyNoise {
+    // `sample` in fixed point. The coarsest cell size and the growth are converted once
+    // per call; everything after that is integer math.
+    pub fn sample_fixed(&self, pos: FixedVec2) -> (CellId, Fixed) {
+        let cell_size = FixedVec2::from_vec2(self.cells);
+        let growth = FixedVec2::from_vec2(self.growth);
+        self.sample_level_fixed(pos, cell_size, growth, self.depth)
+    }
+
+    fn sample_level_fixed(
+        &self,
+        sample_pos: FixedVec2,
+        cell_size: FixedVec2,
+        growth: FixedVec2,
+        depth: usize,
+    ) -> (CellId, Fixed) {
+        let level = self.depth.saturating_sub(depth);
+        if depth == 0 {
+            let (cell, dist) = self.worley_fixed(sample_pos, cell_size);
+            return (cell.into(), self.blend_fixed(level, dist, Fixed::ZERO));
+        }
+
+        let finer_cell_size = FixedVec2::new(cell_size.x / growth.x, cell_size.y / growth.y);
+        let (cell, dist) = self.sample_level_fixed(sample_pos, finer_cell_size, growth, depth - 1);
+
+        let new_sample_pos = FixedVec2::new(
+            Fixed(cell.0.x.wrapping_mul(finer_cell_size.x.0)),
+            Fixed(cell.0.y.wrapping_mul(finer_cell_size.y.0)),
+        );
+        let (cell_o, dist_o) = self.worley_fixed(new_sample_pos, cell_size);
+
+        (cell_o.into(), self.blend_fixed(level, dist_o, dist))
+    }
+
+    // `blend` with the quarters taken as a shift
+    fn blend_fixed(&self, level: usize, dist: Fixed, finer: Fixed) -> Fixed {
+        if self.base_level == Some(level) {
+            dist
+        } else if level >= self.depth {
+            Fixed::ZERO
+        } else {
+            Fixed(((dist.0 as i64 + 3 * finer.0 as i64) >> 2) as i32)
+        }
+    }
+
+    // `worley` in fixed point: the nearest feature point on a single grid
+    pub fn worley_fixed(&self, sample_pos: FixedVec2, cell_size: FixedVec2) -> (IVec2, Fixed) {
+        let base_cell = IVec2::new(
+            sample_pos.x.0.div_euclid(cell_size.x.0),
+            sample_pos.y.0.div_euclid(cell_size.y.0),
+        );
+
+        let mut best_cell = base_cell;
+        let mut best_dist = Fixed::MAX;
+        for xo in -1..=1 {
+            for yo in -1..=1 {
+                let cell = base_cell + IVec2::new(xo, yo);
+                let center = self.center_fixed(cell);
+                let point = FixedVec2::new(
+                    (Fixed::from_int(cell.x) + center.x) * cell_size.x,
+                    (Fixed::from_int(cell.y) + center.y) * cell_size.y,
+                );
+                let delta = FixedVec2::new(point.x - sample_pos.x, point.y - sample_pos.y);
+                let dist = self.metric.distance_fixed(delta);
+
+                if best_dist > dist {
+                    best_cell = cell;
+                    best_dist = dist;
+                }
+            }
+        }
+
+        (best_cell, best_dist)
+    }
+
+    // The unrelaxed feature point of a cell, ZERO to ONE, from the same hash bits as
+    // `center`
+    pub fn center_fixed(&self, cell: IVec2) -> FixedVec2 {
+        let (x, y) = center_bits(self.cell_hash(cell));
+        let round = |bits: u32| Fixed(((bits as i64 + (1 << 15)) >> 16) as i32);
+        FixedVec2::new(round(x), round(y))
+    }
+}
@@ -0,0 +1,95 @@
+//! A tiny 3x5 bitmap font for labeling diagnostic images without a font dependency.
+//! It covers digits, capital letters, '-' and space; lowercase letters are drawn as
+//! capitals and anything else is skipped as a blank.
+
+use glam::{U8Vec3, USizeVec2};
+
+use crate::Buffer;
+
+// Width of a glyph plus the gap after it, in unscaled pixels
+pub const GLYPH_ADVANCE: usize = 4;
+pub const GLYPH_HEIGHT: usize = 5;
+
+// One row of three bits per line from the top, the leftmost pixel in the high bit
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+const LETTERS: [[u8; 5]; 26] = [
+    [0b010, 0b101, 0b111, 0b101, 0b101],
+    [0b110, 0b101, 0b110, 0b101, 0b110],
+    [0b011, 0b100, 0b100, 0b100, 0b011],
+    [0b110, 0b101, 0b101, 0b101, 0b110],
+    [0b111, 0b100, 0b110, 0b100, 0b111],
+    [0b111, 0b100, 0b110, 0b100, 0b100],
+    [0b011, 0b100, 0b101, 0b101, 0b011],
+    [0b101, 0b101, 0b111, 0b101, 0b101],
+    [0b111, 0b010, 0b010, 0b010, 0b111],
+    [0b001, 0b001, 0b001, 0b101, 0b010],
+    [0b101, 0b101, 0b110, 0b101, 0b101],
+    [0b100, 0b100, 0b100, 0b100, 0b111],
+    [0b101, 0b111, 0b111, 0b101, 0b101],
+    [0b110, 0b101, 0b101, 0b101, 0b101],
+    [0b010, 0b101, 0b101, 0b101, 0b010],
+    [0b110, 0b101, 0b110, 0b100, 0b100],
+    [0b010, 0b101, 0b101, 0b110, 0b011],
+    [0b110, 0b101, 0b110, 0b101, 0b101],
+    [0b011, 0b100, 0b010, 0b001, 0b110],
+    [0b111, 0b010, 0b010, 0b010, 0b010],
+    [0b101, 0b101, 0b101, 0b101, 0b111],
+    [0b101, 0b101, 0b101, 0b101, 0b010],
+    [0b101, 0b101, 0b111, 0b111, 0b101],
+    [0b101, 0b101, 0b010, 0b101, 0b101],
+    [0b101, 0b101, 0b010, 0b010, 0b010],
+    [0b111, 0b001, 0b010, 0b100, 0b111],
+];
+
+pub fn glyph(c: char) -> [u8; 5] {
+    match c.to_ascii_uppercase() {
+        c @ '0'..='9' => DIGITS[c as usize - '0' as usize],
+        c @ 'A'..='Z' => LETTERS[c as usize - 'A' as usize],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        _ => [0; 5],
+    }
+}
+
+// The size of `text` drawn at `scale`, without the gap after the last glyph
+pub fn text_size(text: &str, scale: usize) -> USizeVec2 {
+    let glyphs = text.chars().count();
+    USizeVec2::new((glyphs * GLYPH_ADVANCE).saturating_sub(1), GLYPH_HEIGHT) * scale
+}
+
+// Draws `text` on one line with its top left corner at `corner`, each font pixel a
+// `scale` pixels square. Anything off the buffer is clipped.
+pub fn draw_text(
+    buffer: &mut Buffer<U8Vec3>,
+    corner: USizeVec2,
+    text: &str,
+    color: U8Vec3,
+    scale: usize,
+) {
+    for (n, c) in text.chars().enumerate() {
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) == 0 {
+                    continue;
+                }
+                let pixel = USizeVec2::new(n * GLYPH_ADVANCE + col, row) * scale;
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        buffer.set(corner + pixel + USizeVec2::new(dx, dy), color);
+                    }
+                }
+            }
+        }
+    }
+}
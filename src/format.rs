@@ -0,0 +1,100 @@
+//! The file formats renders can be written as, chosen from the output's extension or
+//! named with `--format`. Raster images go through the `image` crate's encoders; NumPy
+//! and SVG outputs hold distances and cell outlines rather than colors.
+
+use std::{path::Path, str::FromStr};
+
+use image::ImageFormat;
+
+use crate::{Named, WorleyError, error::Result, named::parse_named};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OutputFormat {
+    Png,
+    Jpeg,
+    Bmp,
+    Tiff,
+    // 32-bit float channels, each 0 to 1
+    Exr,
+    Npy,
+    Svg,
+}
+
+impl OutputFormat {
+    // The format `path`'s extension names, in any case. `.jpeg` and `.tif` work as well.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .ok_or_else(|| {
+                WorleyError::UnsupportedFormat(format!(
+                    "'{}' has no extension to pick a format from, such as .png",
+                    path.display()
+                ))
+            })?
+            .to_ascii_lowercase();
+        match extension.as_str() {
+            "jpeg" => Ok(OutputFormat::Jpeg),
+            "tif" => Ok(OutputFormat::Tiff),
+            _ => extension.parse(),
+        }
+    }
+
+    // The encoder behind a raster format, or None for the formats written by hand
+    pub fn image_format(self) -> Option<ImageFormat> {
+        match self {
+            OutputFormat::Png => Some(ImageFormat::Png),
+            OutputFormat::Jpeg => Some(ImageFormat::Jpeg),
+            OutputFormat::Bmp => Some(ImageFormat::Bmp),
+            OutputFormat::Tiff => Some(ImageFormat::Tiff),
+            OutputFormat::Exr => Some(ImageFormat::OpenExr),
+            OutputFormat::Npy | OutputFormat::Svg => None,
+        }
+    }
+}
+
+impl Named for OutputFormat {
+    const KIND: &'static str = "output format";
+    const ALL: &'static [Self] = &[
+        OutputFormat::Png,
+        OutputFormat::Jpeg,
+        OutputFormat::Bmp,
+        OutputFormat::Tiff,
+        OutputFormat::Exr,
+        OutputFormat::Npy,
+        OutputFormat::Svg,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Jpeg => "jpg",
+            OutputFormat::Bmp => "bmp",
+            OutputFormat::Tiff => "tiff",
+            OutputFormat::Exr => "exr",
+            OutputFormat::Npy => "npy",
+            OutputFormat::Svg => "svg",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            OutputFormat::Png => "lossless, carrying the render parameters in text chunks",
+            OutputFormat::Jpeg => "lossy, small files",
+            OutputFormat::Bmp => "uncompressed",
+            OutputFormat::Tiff => "lossless, for print and image editors",
+            OutputFormat::Exr => "OpenEXR with float channels from 0 to 1",
+            OutputFormat::Npy => "the raw f32 distances as a NumPy array",
+            OutputFormat::Svg => "the cells as filled vector polygons",
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = WorleyError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        parse_named(s)
+    }
+}
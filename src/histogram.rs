@@ -0,0 +1,136 @@
+//! The distribution of sampled distances, for tuning `max_dist` and `dist_power`.
+//! Distances are normalized by `max_dist`, so everything at 1 or above has faded fully
+//! into the background. A histogram bunched up near 0 means `max_dist` is too large and
+//! most of the falloff range is never used. `estimate_max_dist` picks one from a
+//! percentile of the distances instead.
+
+use glam::{U8Vec3, USizeVec2, Vec2};
+
+use crate::{
+    Buffer, Config, PixelGeometry,
+    render::{Fields, sample_with},
+};
+
+// Samples per side of the grid `estimate_max_dist` samples
+const ESTIMATE_GRID: usize = 64;
+
+// Panel colors, chosen to stay readable over any palette
+const PANEL_BACKGROUND: U8Vec3 = U8Vec3::new(16, 16, 16);
+const PANEL_BAR: U8Vec3 = U8Vec3::new(230, 230, 230);
+// Bars past max_dist, where pixels are pure background
+const PANEL_OVERFLOW: U8Vec3 = U8Vec3::new(230, 80, 80);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+    // Counts of normalized distances in even steps over 0..range
+    pub bins: Vec<u32>,
+    // The normalized distance the last bin ends at. Above 1 so the overflow past
+    // `max_dist` shows up too.
+    pub range: f32,
+    // Distances beyond `range`, which are not in any bin
+    pub clipped: u32,
+}
+
+impl Histogram {
+    pub fn new(bins: usize, range: f32) -> Self {
+        Self {
+            bins: vec![0; bins.max(1)],
+            range,
+            clipped: 0,
+        }
+    }
+
+    // Bins the distances of a field from `compute_field` or a `RenderCache`, so no
+    // sampling is needed
+    pub fn from_field(field: &Buffer<PixelGeometry>, config: &Config, bins: usize) -> Self {
+        let max_dist = config.max_dist.to_world(config.noise.cells);
+        let mut histogram = Self::new(bins, 1.5);
+        for geometry in &field.buff {
+            histogram.add(geometry.dist / max_dist);
+        }
+        histogram
+    }
+
+    pub fn add(&mut self, value: f32) {
+        let bin = (value.max(0.0) / self.range * self.bins.len() as f32) as usize;
+        match self.bins.get_mut(bin) {
+            Some(count) => *count += 1,
+            None => self.clipped += 1,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.bins.iter().sum::<u32>() + self.clipped
+    }
+
+    // The normalized distance below which a fraction `q` of the values fall, to the
+    // resolution of a bin. Values past `range` count as `range`.
+    pub fn quantile(&self, q: f32) -> f32 {
+        let target = (q.clamp(0.0, 1.0) * self.total() as f32).ceil() as u32;
+        let bin_width = self.range / self.bins.len() as f32;
+        let mut seen = 0;
+        for (i, &count) in self.bins.iter().enumerate() {
+            seen += count;
+            if seen >= target.max(1) {
+                return (i + 1) as f32 * bin_width;
+            }
+        }
+        self.range
+    }
+
+    // Values in bins starting at or after `value`, plus those past `range`
+    pub fn count_from(&self, value: f32) -> u32 {
+        let bin_width = self.range / self.bins.len() as f32;
+        let first = (value / bin_width).ceil().max(0.0) as usize;
+        self.bins.iter().skip(first).sum::<u32>() + self.clipped
+    }
+
+    // Draws the histogram as a panel of bars with its top left corner at `corner`,
+    // clipped to the buffer. Bins past normalized distance 1 are drawn in red.
+    pub fn draw(&self, buffer: &mut Buffer<U8Vec3>, corner: USizeVec2, size: USizeVec2) {
+        let tallest = self.bins.iter().copied().max().unwrap_or(0).max(1);
+        let bins = self.bins.len();
+        buffer.par_for_each(|pos, pixel| {
+            if pos.x < corner.x || pos.y < corner.y {
+                return;
+            }
+            let local = pos - corner;
+            if local.x >= size.x || local.y >= size.y {
+                return;
+            }
+            let bin = local.x * bins / size.x;
+            let height = (self.bins[bin] as u64 * size.y as u64 / tallest as u64) as usize;
+            *pixel = if size.y - local.y > height {
+                PANEL_BACKGROUND
+            } else if (bin as f32 + 0.5) / bins as f32 * self.range > 1.0 {
+                PANEL_OVERFLOW
+            } else {
+                PANEL_BAR
+            };
+        });
+    }
+}
+
+// The distance in world pixels that a fraction `percentile` of a `width` x `height`
+// image's pixels fall within, from a coarse grid of samples spread evenly over it. As
+// `max_dist` it fades that fraction of the image in and puts the rest in the background,
+// so the falloff uses the full range whatever the cell sizes.
+pub fn estimate_max_dist(config: &Config, width: usize, height: usize, percentile: f32) -> f32 {
+    let size = Vec2::new(width as f32, height as f32);
+    let steps = USizeVec2::new(width, height).min(USizeVec2::splat(ESTIMATE_GRID));
+    let fields = Fields::of(config);
+    let mut dists = Vec::with_capacity(steps.x * steps.y);
+    for y in 0..steps.y {
+        for x in 0..steps.x {
+            // The centers of an even grid of blocks, rounded to pixels
+            let pos = (Vec2::new(x as f32, y as f32) + 0.5) / steps.as_vec2() * size;
+            dists.push(sample_with(pos.floor(), config, &fields).1);
+        }
+    }
+    if dists.is_empty() {
+        return 0.0;
+    }
+    dists.sort_by(f32::total_cmp);
+    let rank = (percentile.clamp(0.0, 1.0) * dists.len() as f32).ceil() as usize;
+    dists[rank.clamp(1, dists.len()) - 1]
+}
@@ -0,0 +1,126 @@
+//! Hierarchical worley noise.
+//!
+//! The sampling core (`worley`, `worley3` and `combine`) only needs `glam` and works
+//! without `std`: build with `--no-default-features --features libm` for embedded or
+//! shader-transpile use, adding `fixed` for integer-only sampling on targets without fast
+//! floats. Everything that renders, colors or writes images lives behind the default
+//! `std` feature.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod combine;
+#[cfg(feature = "fixed")]
+pub mod fixed;
+pub mod named;
+pub mod profile;
+pub mod worley;
+pub mod worley3;
+
+#[cfg(feature = "std")]
+pub mod alpha;
+#[cfg(feature = "std")]
+pub mod buffer;
+#[cfg(feature = "std")]
+pub mod cache;
+#[cfg(feature = "std")]
+pub mod config;
+#[cfg(feature = "std")]
+pub mod contour;
+#[cfg(feature = "std")]
+pub mod daily;
+#[cfg(feature = "std")]
+pub mod dither;
+#[cfg(feature = "std")]
+pub mod error;
+#[cfg(feature = "std")]
+pub mod export;
+#[cfg(feature = "std")]
+pub mod font;
+#[cfg(feature = "std")]
+pub mod format;
+#[cfg(feature = "std")]
+pub mod histogram;
+#[cfg(feature = "std")]
+pub mod mip;
+#[cfg(feature = "std")]
+pub mod npy;
+#[cfg(feature = "std")]
+pub mod palette;
+#[cfg(feature = "std")]
+pub mod render;
+#[cfg(feature = "std")]
+pub mod ruler;
+#[cfg(feature = "std")]
+pub mod sidecar;
+#[cfg(feature = "std")]
+pub mod stream;
+#[cfg(feature = "std")]
+pub mod svg;
+#[cfg(feature = "std")]
+pub mod tiled;
+#[cfg(feature = "std")]
+pub mod tonemap;
+
+pub use combine::{CombineOp, combine};
+#[cfg(feature = "fixed")]
+pub use fixed::{Fixed, FixedVec2};
+pub use named::Named;
+pub use worley::{
+    CellId, CellInfo, DistanceMetric, FlowMode, HashMixer, Idw, KNearest, MetricsPerLevel, Orbit,
+    PointsPerLevel, Worley, WorleyNoise, cell_hash, cell_info, edge_distance, hierarchical_worley,
+    k_nearest, mix64, seed_from_str, worley, worley_center,
+};
+pub use worley3::{WorleyNoise3, cell_hash3, worley_center3};
+
+#[cfg(feature = "std")]
+pub use alpha::{AlphaSource, CHECKER_SIZE, checkerboard, composite_checkerboard, render_alpha};
+#[cfg(feature = "std")]
+pub use buffer::{
+    Buffer, DiffStats, hash_distance, rgb_from_u8, rgb_from_vec, rgba_from_u8, rgba_from_vec,
+    unpack_rgb, unpack_rgba,
+};
+#[cfg(feature = "std")]
+pub use cache::{RenderCache, colorize, compute_field};
+#[cfg(feature = "std")]
+pub use config::{AnimationClock, CellColoring, ColorMode, Config, MaxDist, Pulse};
+#[cfg(feature = "std")]
+pub use contour::{contours, draw_polyline};
+#[cfg(feature = "std")]
+pub use daily::{Date, daily_seed};
+#[cfg(feature = "std")]
+pub use dither::DitherTexture;
+#[cfg(feature = "std")]
+pub use error::WorleyError;
+#[cfg(feature = "std")]
+pub use export::{
+    export_levels, ffmpeg_args, frame_file_name, render_metadata, render_orbit, save_gif,
+};
+#[cfg(feature = "std")]
+pub use font::{draw_text, text_size};
+#[cfg(feature = "std")]
+pub use format::OutputFormat;
+#[cfg(feature = "std")]
+pub use histogram::{Histogram, estimate_max_dist};
+#[cfg(feature = "std")]
+pub use mip::{mip_chain, save_mips};
+#[cfg(feature = "std")]
+pub use palette::{BuiltinPalette, Palette, PaletteEntry};
+#[cfg(feature = "std")]
+pub use render::{
+    PixelGeometry, Region, dump_pixels, quantize, quantize_pixel, render, render_cells,
+    render_distances, render_float, render_flow, render_into, render_pixel, render_region,
+    render_region_to_tile, render_scaled, render_smooth_values, render_split, render_supersampled,
+    render_with_shader, sample, sample_geometry, shade, shade_float,
+};
+#[cfg(feature = "std")]
+pub use ruler::{RULER_SIZE, draw_ruler, tick_spacing};
+#[cfg(feature = "std")]
+pub use sidecar::{PNG_CONFIG_KEY, RenderConfig, read_png_text, sidecar_path};
+#[cfg(feature = "std")]
+pub use stream::{save_png_streaming, write_png_streaming};
+#[cfg(feature = "std")]
+pub use svg::{CellPolygon, cell_ids, render_svg, save_svg, trace_cells};
+#[cfg(feature = "std")]
+pub use tiled::{auto_tile_size, fit_render_depth, render_tiled};
+#[cfg(feature = "std")]
+pub use tonemap::{ColorSpace, ToneMap, linear_to_srgb, srgb_to_linear};
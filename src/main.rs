@@ -1,262 +1,444 @@
-use std::{
-    f32::consts::PI,
-    hash::{DefaultHasher, Hash, Hasher},
-    time::Instant,
+mod cli;
+mod viewer;
+
+use std::time::Instant;
+
+use cli::{Args, Command};
+use glam::{U8Vec3, Vec2};
+use layered_worley::{
+    Buffer, BuiltinPalette, CellColoring, ColorMode, DistanceMetric, MaxDist, Named, OutputFormat,
+    PNG_CONFIG_KEY, Region, RenderCache, RenderConfig, ToneMap, WorleyError, auto_tile_size,
+    contours, draw_polyline, dump_pixels, estimate_max_dist, export_levels, ffmpeg_args,
+    fit_render_depth, frame_file_name, hash_distance, mip_chain, profile, read_png_text, render,
+    render_distances, render_flow, render_orbit, render_region, render_smooth_values, render_split,
+    render_supersampled, render_svg, render_tiled, save_gif, save_mips, save_png_streaming,
+    sidecar_path, write_png_streaming,
 };
+use rand::random;
 
-use glam::{I8Vec2, IVec2, U8Vec2, U8Vec3, USizeVec2, Vec2, Vec3};
-use image::{Rgb, RgbImage};
-use minifb::{Key, Window, WindowOptions};
-use rand::{
-    Rng, SeedableRng, random,
-    rngs::{SmallRng, ThreadRng},
-    seq::IndexedRandom,
-};
-use rand_distr::{Binomial, Distribution};
-use rayon::prelude::*;
+const CONTOUR_COLOR: U8Vec3 = U8Vec3::splat(255);
 
-const WIDTH: usize = 5120;
-const HEIGHT: usize = 1440;
+fn main() {
+    let args = Args::parse().unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(2);
+    });
 
-#[derive(Clone, Debug)]
-pub struct Buffer<T> {
-    pub buff: Vec<T>,
-    pub width: usize,
-    pub height: usize,
+    let profile = args.profile.clone();
+    let started = match run(args) {
+        Ok(started) => started,
+        Err(e) => {
+            eprintln!("error: {e}");
+            std::process::exit(1);
+        }
+    };
+    if let Some(path) = profile {
+        let profile = profile::take();
+        eprintln!(
+            "{:.3}s in total\n{profile}",
+            started.elapsed().as_secs_f32()
+        );
+        if let Err(e) = std::fs::write(&path, profile.folded()) {
+            eprintln!("error: writing {}: {e}", path.display());
+            std::process::exit(1);
+        }
+    }
 }
 
-impl<T: Clone> Buffer<T> {
-    pub fn set(&mut self, pos: USizeVec2, val: T) {
-        self.buff
-            .get_mut(pos.x % self.width + self.width * pos.y)
-            .map(|c| *c = val);
+// Returns when the measured work started, after any warmup render
+fn run(args: Args) -> Result<Instant, WorleyError> {
+    let mut started = Instant::now();
+    if let Some(threads) = args.threads {
+        #[cfg(feature = "rayon")]
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .map_err(|e| WorleyError::invalid("threads", e.to_string()))?;
+        // Without rayon everything already runs on this one thread
+        #[cfg(not(feature = "rayon"))]
+        if threads > 1 {
+            return Err(WorleyError::invalid(
+                "threads",
+                "more than 1 needs a build with the rayon feature",
+            ));
+        }
     }
-
-    pub fn setf(&mut self, pos: Vec2, val: T) {
-        self.set(pos.round().as_usizevec2(), val);
+    let mut params = args.params;
+    let seed = params.seed.unwrap_or_else(random);
+    if let Some(text) = &params.seed_text {
+        // The number reproduces the render without the text
+        eprintln!("seed {text:?} is --seed {seed}");
     }
-
-    pub fn get(&mut self, pos: USizeVec2) -> T {
-        self.buff
-            .get(pos.x % self.width + self.width * pos.y)
-            .cloned()
-            .unwrap()
+    let mut config = params.config(seed);
+    config.validate()?;
+    let (width, height) = (params.width, params.height);
+    let toroidal = std::iter::once(&config.noise)
+        .chain(&config.overlay)
+        .any(|noise| noise.toroidal_domain.is_some());
+    if config.rotation != 0.0 && toroidal {
+        eprintln!("warning: --rotate turns the toroidal domain too, so the image won't tile");
     }
-
-    pub fn reset(&mut self, val: T) {
-        self.buff = vec![val; self.width * self.height];
+    let requested = config.render_depth.unwrap_or(config.noise.depth);
+    if config.effective_depth() < requested {
+        eprintln!(
+            "rendering to depth {} of {requested}: finer cells are under --min-cell-size {}",
+            config.effective_depth(),
+            config.min_cell_size
+        );
     }
-}
-
-pub fn rgb_from_u8(r: u8, g: u8, b: u8) -> u32 {
-    let (r, g, b) = (r as u32, g as u32, b as u32);
-    r << 16 | g << 8 | b
-}
-
-pub fn rgb_from_vec(rgb: U8Vec3) -> u32 {
-    let (r, g, b) = (rgb.x as u32, rgb.y as u32, rgb.z as u32);
-    r << 16 | g << 8 | b
-}
-
-fn main() {
-    let mut buffer = Buffer {
-        width: WIDTH,
-        height: HEIGHT,
-        buff: vec![U8Vec3::ZERO; WIDTH * HEIGHT],
-    };
-
-    let mut window = Window::new(
-        "Test - ESC to exit",
-        WIDTH,
-        HEIGHT,
-        WindowOptions::default(),
-    )
-    .unwrap_or_else(|e| {
-        panic!("{}", e);
-    });
-
-    window.set_target_fps(240);
-    let mut time = Instant::now();
-    let mut refresh = Instant::now();
-
-    let mut seed: u64 = random();
-    let mut depth = 8;
-    let mut growth = 3.0;
-    let mut cells = Vec2::new(256.0, 256.0);
-    let mut max_dist = 70.0;
-    let mut dist_power = 1.5;
-    while window.is_open() && !window.is_key_down(Key::Escape) {
-        if refresh.elapsed().as_millis() < 1000 {
-            // refresh = Instant::now();
-            let t = time.elapsed().as_millis() as f32 / 1000.0;
-            buffer.reset(U8Vec3::ZERO);
-
-            buffer
-                .buff
-                .par_iter_mut()
-                .enumerate()
-                .for_each(|(i, pixel)| {
-                    let x = i % buffer.width;
-                    let y = i / buffer.width;
-
-                    let (cell, dist) = hierarchical_worley(
-                        (x as f32, y as f32).into(),
-                        cells,
-                        seed,
-                        depth,
-                        growth,
-                    );
-
-                    let hash = cell_hash(cell, seed);
-                    let mut rng = SmallRng::seed_from_u64(hash);
-
-                    let rgb: Vec3 = [
-                        (255., 167., 0.).into(),
-                        (245., 187., 0.).into(),
-                        (225., 200., 0.).into(),
-                        (255., 85., 85.).into(),
-                        (255., 85., 85.).into(),
-                        (255., 85., 85.).into(),
-                        (49., 0., 62.).into(),
-                        (49., 0., 62.).into(),
-                        (49., 0., 62.).into(),
-                        (49., 0., 62.).into(),
-                        (49., 0., 62.).into(),
-                        (49., 0., 62.).into(),
-                        (82., 7., 130.).into(),
-                        (82., 7., 130.).into(),
-                        (82., 7., 130.).into(),
-                        (82., 7., 130.).into(),
-                        (82., 7., 130.).into(),
-                        (143., 26., 132.).into(),
-                        (143., 26., 132.).into(),
-                        (143., 26., 132.).into(),
-                        (143., 26., 132.).into(),
-                        (143., 26., 132.).into(),
-                        (26., 5., 64.).into(),
-                        (26., 5., 64.).into(),
-                        (26., 5., 64.).into(),
-                        (26., 5., 64.).into(),
-                        (26., 5., 64.).into(),
-                        (80., 250., 123.).into(),
-                        (80., 250., 80.).into(),
-                        (90., 250., 90.).into(),
-                        (80., 250., 60.).into(),
-                        (90., 250., 70.).into(),
-                        (80., 250., 100.).into(),
-                        (98., 114., 164.).into(),
-                        // (139., 233., 253.).into(),
-                        // (255., 184., 108.).into(),
-                        // (255., 121., 198.).into(),
-                        // (189., 147., 249.).into(),
-                        // (248., 248., 242.).into(),
-                        // (40., 42., 54.).into(),
-                        // (68., 72., 90.).into(),
-                    ]
-                    .choose(&mut rng)
-                    .cloned()
-                    .unwrap();
-                    let bin_r = Binomial::new(255, rgb.x as f64 / 255.0).unwrap();
-                    let bin_g = Binomial::new(255, rgb.y as f64 / 255.0).unwrap();
-                    let bin_b = Binomial::new(255, rgb.z as f64 / 255.0).unwrap();
-                    let rgb: U8Vec3 = (
-                        bin_r.sample(&mut rng) as u8,
-                        bin_g.sample(&mut rng) as u8,
-                        bin_b.sample(&mut rng) as u8,
-                    )
-                        .into();
-                    let rgb =
-                        (rgb.as_vec3() * (1.0 - dist / max_dist).powf(dist_power)).as_u8vec3();
-
-                    *pixel = rgb;
-                });
+    // Both sample the noise, which only matters to commands that render it
+    let renders = args.command.renders();
+    if let Some(percentile) = args.auto_max_dist.filter(|_| renders) {
+        // Rounded so the printed value reproduces the render exactly
+        let estimate = estimate_max_dist(&config, width, height, percentile / 100.0);
+        let max_dist = (estimate * 100.0).round() / 100.0;
+        if max_dist <= 0.0 {
+            return Err(WorleyError::invalid(
+                "auto_max_dist",
+                "every sampled distance is 0, so there is no falloff to fit",
+            ));
         }
-
-        window
-            .update_with_buffer(
-                &buffer
-                    .buff
-                    .iter()
-                    .map(|x| rgb_from_vec(*x))
-                    .collect::<Vec<_>>(),
-                WIDTH,
-                HEIGHT,
-            )
-            .unwrap();
+        eprintln!("--max-dist {max_dist}, the {percentile}th percentile of sampled distances");
+        config.max_dist = MaxDist::Pixels(max_dist);
+        params.config.max_dist = MaxDist::Pixels(max_dist);
     }
-
-    let mut img = RgbImage::new(WIDTH as u32, HEIGHT as u32);
-    for (i, pixel) in buffer.buff.iter().enumerate() {
-        let x = (i % WIDTH) as u32;
-        let y = (i / WIDTH) as u32;
-        img.put_pixel(x, y, Rgb([pixel.x, pixel.y, pixel.z]));
+    if let Some(budget) = args.max_render_time.filter(|_| renders) {
+        let supersample = match args.command {
+            Command::Render { supersample, .. } => supersample,
+            _ => 1,
+        };
+        let (render_depth, projected) =
+            fit_render_depth(&config, width, height, supersample, budget);
+        eprintln!(
+            "rendering to depth {render_depth} of {} to fit {:.2}s, about {:.2}s per image",
+            config.noise.depth,
+            budget.as_secs_f32(),
+            projected.as_secs_f32()
+        );
+        config.render_depth = Some(render_depth);
+        params.config.render_depth = Some(render_depth);
     }
+    match args.command {
+        Command::View {
+            window_scale,
+            fallback,
+            live,
+        } => viewer::view(&config, width, height, window_scale, fallback, live)?,
+        Command::Render {
+            output,
+            region,
+            tile_size,
+            streaming,
+            flow,
+            mips,
+            sidecar,
+            format,
+            smooth_value,
+            supersample,
+            contours: contour_count,
+            warmup,
+        } => {
+            let record = RenderConfig::new(&config, width, height).with_region(region);
+            let region = region.unwrap_or(Region {
+                x: 0,
+                y: 0,
+                width,
+                height,
+            });
+            let tile_size = || tile_size.unwrap_or_else(|| auto_tile_size(&config));
+            let format = match format {
+                Some(format) => format,
+                None => OutputFormat::from_path(&output)?,
+            };
+            if streaming && format != OutputFormat::Png {
+                return Err(WorleyError::UnsupportedFormat(
+                    "--streaming only writes .png".to_string(),
+                ));
+            }
+            // Everything up to encoding, except that a streamed render is encoded and
+            // written strip by strip as it goes, into nothing when `discard` is set
+            let render = |discard: bool| -> Result<Rendered, WorleyError> {
+                Ok(if let Some(mode) = flow {
+                    let mut field = Buffer::new(region.width, region.height, Vec2::ZERO);
+                    render_flow(&mut field, &config, region.origin(), mode);
+                    Rendered::Flow(field)
+                } else if let Some(idw) = smooth_value {
+                    let mut field = Buffer::new(region.width, region.height, 0.0);
+                    render_smooth_values(&mut field, &config, region.origin(), idw);
+                    Rendered::SmoothValues(field)
+                } else if streaming {
+                    if discard {
+                        write_png_streaming(std::io::sink(), &config, region, tile_size())?;
+                    } else {
+                        save_png_streaming(&output, &config, region, tile_size())?;
+                    }
+                    Rendered::Streamed
+                } else if format == OutputFormat::Svg {
+                    Rendered::Svg(render_svg(&config, region))
+                } else if format == OutputFormat::Npy {
+                    // .npy outputs get the raw distances rather than colors
+                    let mut field = Buffer::new(region.width, region.height, 0.0);
+                    render_distances(&mut field, &config, region.origin());
+                    Rendered::Distances(field)
+                } else {
+                    let mut buffer = Buffer::new(region.width, region.height, U8Vec3::ZERO);
+                    if supersample > 1 {
+                        render_supersampled(&mut buffer, &config, region.origin(), supersample);
+                    } else {
+                        render_tiled(&mut buffer, &config, region.origin(), tile_size());
+                    }
+                    if contour_count > 0 {
+                        let mut field = Buffer::new(region.width, region.height, 0.0);
+                        render_distances(&mut field, &config, region.origin());
+                        let max_dist = config.max_dist.to_world(config.noise.cells);
+                        field.par_for_each(|_, dist| *dist /= max_dist);
+                        let levels: Vec<_> = (1..=contour_count)
+                            .map(|i| i as f32 / (contour_count + 1) as f32)
+                            .collect();
+                        for line in contours(&field, &levels) {
+                            draw_polyline(&mut buffer, &line, CONTOUR_COLOR);
+                        }
+                    }
+                    Rendered::Image(buffer)
+                })
+            };
+
+            let cold = if warmup {
+                // Fills the allocator, page tables and relaxation caches and calibrates
+                // the tile size, then drops its timings
+                let warmup = Instant::now();
+                render(true)?;
+                let cold = warmup.elapsed();
+                profile::take();
+                started = Instant::now();
+                Some(cold)
+            } else {
+                None
+            };
+            let rendering = Instant::now();
+            let rendered = render(false)?;
+            if let Some(cold) = cold {
+                eprintln!(
+                    "{:.3}s cold for the discarded warmup render, {:.3}s warm",
+                    cold.as_secs_f32(),
+                    rendering.elapsed().as_secs_f32()
+                );
+            }
 
-    img.save("output.png").expect("Failed to save image");
+            match rendered {
+                Rendered::Flow(field) => field.save_flow(&output)?,
+                Rendered::SmoothValues(field) if format == OutputFormat::Npy => {
+                    field.save_npy(&output)?
+                }
+                Rendered::SmoothValues(field) => {
+                    let mut gray = Buffer::new(region.width, region.height, U8Vec3::ZERO);
+                    gray.par_for_each(|pos, pixel| {
+                        let value = field.buff[pos.x + pos.y * region.width];
+                        *pixel = U8Vec3::splat((value * 255.0).round() as u8);
+                    });
+                    gray.save_as(&output, format)?;
+                }
+                Rendered::Streamed => {}
+                Rendered::Svg(svg) => std::fs::write(&output, svg)?,
+                Rendered::Distances(field) => field.save_npy(&output)?,
+                Rendered::Image(buffer) => {
+                    if mips {
+                        // A toroidal render tiles, so its mips should too
+                        let wrap = config.noise.toroidal_domain.is_some();
+                        save_mips(&mip_chain(&buffer, wrap), &output)?;
+                    } else if format == OutputFormat::Png {
+                        buffer.save_png_with_text(&output, &record.png_text()?)?;
+                    } else {
+                        buffer.save_as(&output, format)?;
+                    }
+                }
+            }
+            if sidecar {
+                record.save(sidecar_path(&output))?;
+            }
+        }
+        Command::Sweep {
+            param,
+            from,
+            to,
+            steps,
+            output_dir,
+        } => {
+            let mut buffer = Buffer::new(width, height, U8Vec3::ZERO);
+            // Sweeping a color parameter only resamples the cells once
+            let mut cache = RenderCache::new();
+            let mut hashes = Vec::with_capacity(steps);
+            for step in 0..steps {
+                let t = if steps == 1 {
+                    0.0
+                } else {
+                    step as f32 / (steps - 1) as f32
+                };
+                let value = from + (to - from) * t;
+                let mut params = params.clone();
+                param.apply(&mut params, value);
+                let config = params.config(seed);
+                config.validate()?;
+                cache.render(&mut buffer, &config);
+                let path = output_dir.join(format!("sweep_{step:03}.png"));
+                // Flag steps where the parameter made no visible difference
+                let hash = buffer.average_hash();
+                let duplicate = match hashes.iter().position(|&h| hash_distance(h, hash) == 0) {
+                    Some(earlier) => format!(", looks like step {earlier}"),
+                    None => String::new(),
+                };
+                hashes.push(hash);
+                println!("{} ({param:?} = {value}{duplicate})", path.display());
+                buffer.save(&path)?;
+            }
+        }
+        Command::Animate {
+            frames,
+            speed,
+            orbit,
+            gif,
+            mp4,
+            fps,
+            output_dir,
+        } => {
+            let mut gif_frames = Vec::new();
+            let mut emit = |frame: usize, buffer: &Buffer<U8Vec3>| {
+                if gif.is_some() {
+                    gif_frames.push(buffer.clone());
+                    Ok(())
+                } else {
+                    buffer.save(output_dir.join(frame_file_name(frame, frames)))
+                }
+            };
+            if let Some(radius) = orbit {
+                render_orbit(&config, width, height, frames, radius, emit)?;
+            } else {
+                let mut buffer = Buffer::new(width, height, U8Vec3::ZERO);
+                for frame in 0..frames {
+                    let origin = (speed * frame as f32).round().as_ivec2();
+                    render_region(&mut buffer, &config, origin);
+                    emit(frame, &buffer)?;
+                }
+            }
+            if let Some(path) = gif {
+                save_gif(path, &gif_frames, 1000 / fps)?;
+            }
+            if let Some(video) = mp4 {
+                encode_video(&ffmpeg_args(&output_dir, frames, fps, &video))?;
+            }
+        }
+        Command::Split { output } => {
+            let mut buffer = Buffer::new(width, height, U8Vec3::ZERO);
+            render_split(&mut buffer, &config);
+            buffer.save(&output)?;
+        }
+        Command::Levels { output_dir } => {
+            for path in export_levels(&config, width, height, &output_dir)? {
+                println!("{}", path.display());
+            }
+        }
+        Command::Compare {
+            output,
+            with,
+            against,
+            amplify,
+        } => {
+            let mut other = match against {
+                Some(path) => RenderConfig::load(path)?.config,
+                None => config.clone(),
+            };
+            for line in &with {
+                cli::apply_line(&mut other, line)
+                    .map_err(|e| WorleyError::invalid("--with", format!("'{line}': {e}")))?;
+            }
+            other.validate()?;
+            let mut a = Buffer::new(width, height, U8Vec3::ZERO);
+            let mut b = Buffer::new(width, height, U8Vec3::ZERO);
+            render(&mut a, &config);
+            render(&mut b, &other);
+            let (diff, stats) = a.diff(&b, amplify);
+            diff.save(&output)?;
+            println!(
+                "mean difference {:.3}, max {}, {} of {} pixels changed",
+                stats.mean,
+                stats.max,
+                stats.changed,
+                width * height
+            );
+        }
+        Command::PalettePreview { output } => {
+            config.palette.preview(width, height).save(&output)?;
+        }
+        Command::ListMetrics => print_named::<DistanceMetric>(),
+        Command::ListModes => print_named::<ColorMode>(),
+        Command::ListColorings => print_named::<CellColoring>(),
+        Command::ListToneMaps => print_named::<ToneMap>(),
+        Command::ListPalettes => print_named::<BuiltinPalette>(),
+        Command::ListFormats => print_named::<OutputFormat>(),
+        Command::DumpPixels { count } => print!("{}", dump_pixels(&config, width, count)),
+        Command::ReadMetadata { path } => {
+            let text = read_png_text(&path)?;
+            for (keyword, value) in &text {
+                if keyword != PNG_CONFIG_KEY {
+                    println!("{keyword}: {value}");
+                }
+            }
+            // Checks the full record reads back before suggesting it
+            match RenderConfig::from_png_text(&text) {
+                Ok(_) => println!("reproduce with --config {}", path.display()),
+                Err(e) => println!("can't reproduce: {e}"),
+            }
+        }
+        Command::Info => {
+            println!("seed: {seed}");
+            println!("size: {width}x{height}");
+            println!("{config:#?}");
+        }
+    }
+    Ok(started)
 }
 
-// Hashes the seed + cell coordinate
-fn cell_hash(cell: IVec2, seed: u64) -> u64 {
-    let mut x = (cell.x as i64 as u64).wrapping_mul(0xa0761d6478bd642f);
-    let mut y = (cell.y as i64 as u64).wrapping_mul(0xe7037ed1a0b428db);
-    let mut s = seed.wrapping_mul(0x8ebc6af09c88c6e3);
-    x ^= y.rotate_left(25);
-    y ^= s.rotate_left(47);
-    s ^= x.rotate_left(17);
-    s ^ y
+// A render command's output before it's encoded
+enum Rendered {
+    Flow(Buffer<Vec2>),
+    SmoothValues(Buffer<f32>),
+    Distances(Buffer<f32>),
+    Svg(String),
+    Image(Buffer<U8Vec3>),
+    // Already written strip by strip
+    Streamed,
 }
 
-// Get the center of a worley cell, ZERO to ONE
-fn worley_center(cell: IVec2, seed: u64) -> Vec2 {
-    let hash = cell_hash(cell, seed);
-    let bits1 = (hash >> 12) as u32;
-    let bits2 = (hash >> 32) as u32;
-    let x = (bits1 as f32) / (u32::MAX as f32);
-    let y = (bits2 as f32) / (u32::MAX as f32);
-    (x, y).into()
+fn print_named<T: Named>() {
+    let width = T::ALL.iter().map(|v| v.name().len()).max().unwrap_or(0);
+    for v in T::ALL {
+        println!("{:width$}  {}", v.name(), v.description());
+    }
 }
 
-fn worley(sample_pos: Vec2, cell_size: Vec2, seed: u64) -> (IVec2, f32) {
-    let pos_in_cells = sample_pos / cell_size;
-    let base_cell = pos_in_cells.floor().as_ivec2();
-
-    let mut best_cell = None;
-    let mut best_dist = None;
-
-    for xo in -1..=1 {
-        for yo in -1..=1 {
-            let neighbor = base_cell + IVec2::new(xo, yo);
-            let center = worley_center(neighbor, seed);
-            let world_center = neighbor.as_vec2() * cell_size + center * cell_size;
-            let dist = (world_center - sample_pos).length();
-
-            if best_dist.is_none() || best_dist.unwrap() > dist {
-                best_cell = Some(neighbor);
-                best_dist = Some(dist);
+// Runs ffmpeg with `args`, or prints the command to run once it's installed
+fn encode_video(args: &[String]) -> Result<(), WorleyError> {
+    let quoted: Vec<_> = args
+        .iter()
+        .map(|arg| {
+            if arg.contains([' ', '(', ')', '*']) {
+                format!("'{arg}'")
+            } else {
+                arg.clone()
             }
+        })
+        .collect();
+    let command = format!("ffmpeg {}", quoted.join(" "));
+    match std::process::Command::new("ffmpeg").args(args).status() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(WorleyError::invalid(
+            "--mp4",
+            format!("{command} exited with {status}"),
+        )),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("ffmpeg not found, encode the frames with:\n{command}");
+            Ok(())
         }
+        Err(e) => Err(e.into()),
     }
-
-    (best_cell.unwrap(), best_dist.unwrap())
-}
-
-fn hierarchical_worley(
-    sample_pos: Vec2,
-    cell_size: Vec2,
-    seed: u64,
-    depth: usize,
-    growth: f32,
-) -> (IVec2, f32) {
-    if depth == 0 {
-        let (cell, dist) = worley(sample_pos, cell_size, seed);
-        return (cell, 0.0);
-    }
-
-    let finer_cell_size = cell_size / growth;
-    let (cell, dist) = hierarchical_worley(sample_pos, finer_cell_size, seed, depth - 1, growth);
-
-    let new_sample_pos = cell.as_vec2() * finer_cell_size;
-    let (cell_o, dist_o) = worley(new_sample_pos, cell_size, seed);
-
-    (cell_o, dist_o * 0.25 + dist * 0.75)
 }
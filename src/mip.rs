@@ -0,0 +1,74 @@
+//! Mipmap chains for using a render as a texture. Each level box-filters the one above
+//! it to half the size, averaging in linear light so that the smaller levels don't
+//! darken. Filters hanging off an odd sized edge clamp to it, or wrap around to the far
+//! side for tileable (toroidal) renders so no seam appears at the border.
+
+use std::path::Path;
+
+use glam::{U8Vec3, Vec3};
+
+use crate::{Buffer, error::Result, linear_to_srgb, srgb_to_linear};
+
+// The chain from `buffer` itself down to a single pixel
+pub fn mip_chain(buffer: &Buffer<U8Vec3>, wrap: bool) -> Vec<Buffer<U8Vec3>> {
+    let mut levels = vec![buffer.clone()];
+    let mut linear = Buffer {
+        buff: buffer.buff.iter().map(|&rgb| decode(rgb)).collect(),
+        width: buffer.width,
+        height: buffer.height,
+    };
+    while linear.width > 1 || linear.height > 1 {
+        linear = downsample(&linear, wrap);
+        levels.push(Buffer {
+            buff: linear.buff.iter().map(|&rgb| encode(rgb)).collect(),
+            width: linear.width,
+            height: linear.height,
+        });
+    }
+    levels
+}
+
+// Halves each side, rounding up, by averaging 2x2 blocks. On an odd side the last block
+// hangs over the edge by one pixel.
+fn downsample(src: &Buffer<Vec3>, wrap: bool) -> Buffer<Vec3> {
+    let (width, height) = (src.width.div_ceil(2), src.height.div_ceil(2));
+    let index = |i: usize, len: usize| if wrap { i % len } else { i.min(len - 1) };
+    let mut dst = Buffer::new(width, height, Vec3::ZERO);
+    dst.par_for_each(|pos, pixel| {
+        let (x, y) = (pos.x * 2, pos.y * 2);
+        let mut sum = Vec3::ZERO;
+        for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            let sx = index(x + dx, src.width);
+            let sy = index(y + dy, src.height);
+            sum += src.buff[sx + sy * src.width];
+        }
+        *pixel = sum / 4.0;
+    });
+    dst
+}
+
+fn decode(rgb: U8Vec3) -> Vec3 {
+    srgb_to_linear(rgb.as_vec3() / 255.0)
+}
+
+fn encode(linear: Vec3) -> U8Vec3 {
+    (linear_to_srgb(linear) * 255.0).round().as_u8vec3()
+}
+
+// Writes level n beside `path` as `<stem>_mip<n>.<ext>`, level 0 being `path` itself
+pub fn save_mips(levels: &[Buffer<U8Vec3>], path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    for (level, buffer) in levels.iter().enumerate() {
+        if level == 0 {
+            buffer.save(path)?;
+            continue;
+        }
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let name = match path.extension() {
+            Some(ext) => format!("{stem}_mip{level}.{}", ext.to_string_lossy()),
+            None => format!("{stem}_mip{level}"),
+        };
+        buffer.save(path.with_file_name(name))?;
+    }
+    Ok(())
+}
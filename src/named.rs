@@ -0,0 +1,38 @@
+//! Enums that can be chosen by name, such as from the command line. Each type lists its
+//! variants with a name and description in one place, which drives both parsing and
+//! the CLI's `--list-*` flags.
+
+#[cfg(feature = "std")]
+use crate::{WorleyError, error::Result};
+
+pub trait Named: Copy + 'static {
+    // What a value of this type is called in messages, such as "color mode"
+    const KIND: &'static str;
+    const ALL: &'static [Self];
+
+    fn name(self) -> &'static str;
+    fn description(self) -> &'static str;
+
+    // Case-insensitive lookup by `name`
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|v| v.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+// Backs the `FromStr` impls, listing the valid names when `name` isn't one of them
+#[cfg(feature = "std")]
+pub fn parse_named<T: Named>(name: &str) -> Result<T> {
+    T::from_name(name).ok_or_else(|| {
+        let names: Vec<_> = T::ALL.iter().map(|v| v.name()).collect();
+        WorleyError::invalid(
+            T::KIND,
+            format!(
+                "unknown name '{name}', expected one of {}",
+                names.join(", ")
+            ),
+        )
+    })
+}
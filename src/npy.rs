@@ -0,0 +1,48 @@
+//! Minimal writer for NumPy's `.npy` format, enough to hand raw distance fields to
+//! Python tooling without a dependency.
+//!
+//! A `Buffer<f32>` is written as a C-ordered `<f4` (little-endian f32) array of shape
+//! `(height, width)`, so `numpy.load(path)[y, x]` is the distance at pixel (x, y).
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::{Buffer, error::Result};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+pub fn write_npy(writer: &mut impl Write, buffer: &Buffer<f32>) -> Result<()> {
+    let mut header = format!(
+        "{{'descr': '<f4', 'fortran_order': False, 'shape': ({}, {}), }}",
+        buffer.height, buffer.width
+    );
+    // Magic, version and length come to 10 bytes, and the whole preamble is padded
+    // with spaces to a multiple of 64 bytes, ending in a newline
+    let unpadded = MAGIC.len() + 4 + header.len() + 1;
+    header.extend(std::iter::repeat_n(
+        ' ',
+        unpadded.next_multiple_of(64) - unpadded,
+    ));
+    header.push('\n');
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[1, 0])?;
+    writer.write_all(&(header.len() as u16).to_le_bytes())?;
+    writer.write_all(header.as_bytes())?;
+    for value in &buffer.buff {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+impl Buffer<f32> {
+    pub fn save_npy(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        write_npy(&mut writer, self)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
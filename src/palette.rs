@@ -0,0 +1,453 @@
+use std::{fmt::Write, path::Path};
+
+use glam::{U8Vec3, Vec3};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{Buffer, Named, WorleyError, error::Result, named::parse_named};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PaletteEntry {
+    // 0 to 255 per channel
+    pub color: Vec3,
+    // Relative likelihood of a cell picking this color
+    pub weight: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Palette {
+    pub entries: Vec<PaletteEntry>,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::dracula()
+    }
+}
+
+impl Palette {
+    pub fn new(entries: impl IntoIterator<Item = (Vec3, u32)>) -> Self {
+        Self {
+            entries: entries
+                .into_iter()
+                .map(|(color, weight)| PaletteEntry { color, weight })
+                .collect(),
+        }
+    }
+
+    pub fn dracula() -> Self {
+        Self::new([
+            (Vec3::new(255., 167., 0.), 1),
+            (Vec3::new(245., 187., 0.), 1),
+            (Vec3::new(225., 200., 0.), 1),
+            (Vec3::new(255., 85., 85.), 3),
+            (Vec3::new(49., 0., 62.), 6),
+            (Vec3::new(82., 7., 130.), 5),
+            (Vec3::new(143., 26., 132.), 5),
+            (Vec3::new(26., 5., 64.), 5),
+            (Vec3::new(80., 250., 123.), 1),
+            (Vec3::new(80., 250., 80.), 1),
+            (Vec3::new(90., 250., 90.), 1),
+            (Vec3::new(80., 250., 60.), 1),
+            (Vec3::new(90., 250., 70.), 1),
+            (Vec3::new(80., 250., 100.), 1),
+            (Vec3::new(98., 114., 164.), 1),
+            // (Vec3::new(139., 233., 253.), 1),
+            // (Vec3::new(255., 184., 108.), 1),
+            // (Vec3::new(255., 121., 198.), 1),
+            // (Vec3::new(189., 147., 249.), 1),
+            // (Vec3::new(248., 248., 242.), 1),
+            // (Vec3::new(40., 42., 54.), 1),
+            // (Vec3::new(68., 72., 90.), 1),
+        ])
+    }
+
+    // Eight evenly weighted stops of matplotlib's perceptually uniform viridis map
+    pub fn viridis() -> Self {
+        Self::from_hex_stops(&[
+            0x440154, 0x46327e, 0x365c8d, 0x277f8e, 0x1fa187, 0x4ac16d, 0xa0da39, 0xfde725,
+        ])
+    }
+
+    // Eight evenly weighted stops of matplotlib's magma map, black through purple to cream
+    pub fn magma() -> Self {
+        Self::from_hex_stops(&[
+            0x000004, 0x1c1044, 0x4f127b, 0x812581, 0xb5367a, 0xe55064, 0xfb8761, 0xfcfdbf,
+        ])
+    }
+
+    // Eight even steps from near black to near white
+    pub fn grayscale() -> Self {
+        Self::new((0..8).map(|i| (Vec3::splat(24.0 + i as f32 * 208.0 / 7.0), 1)))
+    }
+
+    fn from_hex_stops(stops: &[u32]) -> Self {
+        Self::new(stops.iter().map(|&rgb| {
+            let channel = |shift: u32| ((rgb >> shift) & 0xff) as f32;
+            (Vec3::new(channel(16), channel(8), channel(0)), 1)
+        }))
+    }
+
+    // Loads a palette file, picking the format from the extension: `.gpl` for GIMP,
+    // `.act` for Adobe color tables and `.hex` or `.txt` for hex lists
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        match extension.to_ascii_lowercase().as_str() {
+            "gpl" => Self::from_gpl(&std::fs::read_to_string(path)?),
+            "act" => Self::from_act(&std::fs::read(path)?),
+            "hex" | "txt" => Self::from_hex_list(&std::fs::read_to_string(path)?),
+            _ => Err(WorleyError::UnsupportedFormat(format!(
+                "palette '{}', expected .gpl, .act, .hex or .txt",
+                path.display()
+            ))),
+        }
+    }
+
+    // A GIMP palette: a `GIMP Palette` line, optional `Name:` and `Columns:` headers, then
+    // one `R G B [name]` line per color. Names are ignored and every weight is 1.
+    pub fn from_gpl(text: &str) -> Result<Self> {
+        let mut lines = text.lines().enumerate();
+        if lines.next().map(|(_, l)| l.trim()) != Some("GIMP Palette") {
+            return Err(invalid_line(1, "expected 'GIMP Palette'"));
+        }
+        let mut colors = Vec::new();
+        for (i, line) in lines {
+            let line = line.trim();
+            if line.is_empty()
+                || line.starts_with('#')
+                || line.starts_with("Name:")
+                || line.starts_with("Columns:")
+            {
+                continue;
+            }
+            let channels: Vec<_> = line.split_whitespace().take(3).collect();
+            let color = match channels[..] {
+                [r, g, b] => [r, g, b].map(|c| c.parse::<u8>().ok()),
+                _ => [None; 3],
+            };
+            match color {
+                [Some(r), Some(g), Some(b)] => colors.push((U8Vec3::new(r, g, b).as_vec3(), 1)),
+                _ => return Err(invalid_line(i + 1, "expected R G B in 0..=255")),
+            }
+        }
+        Self::non_empty(colors)
+    }
+
+    // An Adobe color table: 256 RGB triples, optionally followed by a big endian color
+    // count and transparent index, which is ignored
+    pub fn from_act(bytes: &[u8]) -> Result<Self> {
+        let count = match bytes.len() {
+            768 => 256,
+            772 => match u16::from_be_bytes([bytes[768], bytes[769]]) as usize {
+                0 => 256,
+                count => count.min(256),
+            },
+            len => {
+                return Err(WorleyError::invalid(
+                    "palette",
+                    format!("an .act file is 768 or 772 bytes, not {len}"),
+                ));
+            }
+        };
+        let colors = bytes[..count * 3]
+            .chunks_exact(3)
+            .map(|c| (U8Vec3::new(c[0], c[1], c[2]).as_vec3(), 1));
+        Self::non_empty(colors.collect())
+    }
+
+    // One `RRGGBB` color per line, with an optional leading # and an optional weight
+    // after it. Blank lines and lines starting with ; are skipped. The weights must add
+    // up to something above 0 that still fits a u32, or no cell could pick a color.
+    pub fn from_hex_list(text: &str) -> Result<Self> {
+        let mut colors = Vec::new();
+        let mut total: u32 = 0;
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let hex = parts.next().unwrap_or("");
+            let hex = hex.strip_prefix('#').unwrap_or(hex);
+            let color = match (hex.len(), u32::from_str_radix(hex, 16)) {
+                (6, Ok(rgb)) => rgb.to_be_bytes(),
+                _ => return Err(invalid_line(i + 1, format!("'{hex}' isn't RRGGBB hex"))),
+            };
+            let weight = match parts.next() {
+                Some(w) => w
+                    .parse()
+                    .map_err(|_| invalid_line(i + 1, format!("'{w}' isn't a weight")))?,
+                None => 1,
+            };
+            if parts.next().is_some() {
+                return Err(invalid_line(i + 1, "expected RRGGBB [weight]"));
+            }
+            total = total.checked_add(weight).ok_or_else(|| {
+                invalid_line(i + 1, format!("weights add up to more than {}", u32::MAX))
+            })?;
+            colors.push((U8Vec3::new(color[1], color[2], color[3]).as_vec3(), weight));
+        }
+        if !colors.is_empty() && total == 0 {
+            return Err(WorleyError::invalid("palette", "every weight is 0"));
+        }
+        Self::non_empty(colors)
+    }
+
+    fn non_empty(colors: Vec<(Vec3, u32)>) -> Result<Self> {
+        if colors.is_empty() {
+            return Err(WorleyError::invalid("palette", "has no colors"));
+        }
+        Ok(Self::new(colors))
+    }
+
+    // Writes the palette as a GIMP palette, dropping the weights
+    pub fn to_gpl(&self, name: &str) -> String {
+        let mut text = format!("GIMP Palette\nName: {name}\n#\n");
+        for entry in &self.entries {
+            let c = entry.color.round().as_u8vec3();
+            writeln!(text, "{:3} {:3} {:3}", c.x, c.y, c.z).unwrap();
+        }
+        text
+    }
+
+    // Writes the palette as a hex list, keeping weights other than 1
+    pub fn to_hex_list(&self) -> String {
+        let mut text = String::new();
+        for entry in &self.entries {
+            let c = entry.color.round().as_u8vec3();
+            write!(text, "{:02x}{:02x}{:02x}", c.x, c.y, c.z).unwrap();
+            match entry.weight {
+                1 => text.push('\n'),
+                weight => writeln!(text, " {weight}").unwrap(),
+            }
+        }
+        text
+    }
+
+    // The same palette with each channel flipped, turning dark palettes light and back,
+    // keeping the weights. Inverting twice gives back whole-number channels exactly, as
+    // loaded palettes have, and fractional ones to within float rounding.
+    pub fn inverted(&self) -> Self {
+        self.map_colors(|color| Vec3::splat(255.0) - color)
+    }
+
+    // The same palette with each channel passed through its own curve, red, green and
+    // then blue, indexed by the channel's rounded value
+    pub fn remap(&self, lut: &[[u8; 256]; 3]) -> Self {
+        self.map_colors(|color| {
+            let c = color
+                .round()
+                .clamp(Vec3::ZERO, Vec3::splat(255.0))
+                .as_u8vec3();
+            Vec3::new(
+                lut[0][c.x as usize] as f32,
+                lut[1][c.y as usize] as f32,
+                lut[2][c.z as usize] as f32,
+            )
+        })
+    }
+
+    fn map_colors(&self, f: impl Fn(Vec3) -> Vec3) -> Self {
+        Self {
+            entries: self
+                .entries
+                .iter()
+                .map(|e| PaletteEntry {
+                    color: f(e.color),
+                    weight: e.weight,
+                })
+                .collect(),
+        }
+    }
+
+    // Summed wide, so any number of entries can't overflow it
+    pub fn total_weight(&self) -> u64 {
+        total_weight(&self.entries)
+    }
+
+    // Whether its weights can be picked by: a total within a u32, as the importers
+    // allow, and above 0 unless it has no entries at all, whose cells all fall back
+    pub fn validate(&self) -> Result<()> {
+        match self.total_weight() {
+            0 if !self.entries.is_empty() => {
+                Err(WorleyError::invalid("palette", "every weight is 0"))
+            }
+            total if total > u32::MAX as u64 => Err(WorleyError::invalid(
+                "palette",
+                format!("weights add up to more than {}", u32::MAX),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    // Draws a single index in 0..total_weight, so integer weights behave exactly like
+    // repeating each color weight times in a flat list
+    pub fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Vec3> {
+        choose_from(&self.entries, rng)
+    }
+
+    // Splits the entries into `bands` contiguous groups and chooses by weight within
+    // group `band`. Bands left empty by a short palette use the whole palette instead.
+    pub fn choose_in_band<R: Rng + ?Sized>(
+        &self,
+        band: usize,
+        bands: usize,
+        rng: &mut R,
+    ) -> Option<Vec3> {
+        choose_from(self.band(band, bands), rng).or_else(|| self.choose(rng))
+    }
+
+    // Like `choose`, but picks the weighted index straight from hash bits rather than
+    // a random number generator
+    pub fn pick(&self, hash: u64) -> Option<Vec3> {
+        pick_from(&self.entries, hash)
+    }
+
+    // Like `pick`, with the hash's place along the palette, 0 to 1, squeezed into a
+    // window `spread` long that starts at `shift` and wraps past the end. A spread of 1
+    // and shift of 0 picks exactly as `pick`.
+    pub fn pick_in_window(&self, hash: u64, spread: f32, shift: f32) -> Option<Vec3> {
+        let total = self.total_weight();
+        if total == 0 {
+            return None;
+        }
+        // The index `pick` takes, refined by higher bits into a place within it
+        let index = (hash % total) as f64 + (hash >> 32) as f64 / (1u64 << 32) as f64;
+        let place = (index / total as f64 * spread as f64 + shift as f64).rem_euclid(1.0);
+        let index = ((place * total as f64) as u64).min(total - 1);
+        entry_at(&self.entries, index).map(|e| e.color)
+    }
+
+    // Like `choose_in_band`, picking from hash bits
+    pub fn pick_in_band(&self, band: usize, bands: usize, hash: u64) -> Option<Vec3> {
+        pick_from(self.band(band, bands), hash).or_else(|| self.pick(hash))
+    }
+
+    fn band(&self, band: usize, bands: usize) -> &[PaletteEntry] {
+        let bands = bands.max(1);
+        let band = band.min(bands - 1);
+        let len = self.entries.len();
+        &self.entries[band * len / bands..(band + 1) * len / bands]
+    }
+
+    // Horizontal swatches, one band per color with widths proportional to weights
+    pub fn preview(&self, width: usize, height: usize) -> Buffer<U8Vec3> {
+        let mut buffer = Buffer::new(width, height, U8Vec3::ZERO);
+        let total = self.total_weight();
+        if total == 0 {
+            return buffer;
+        }
+        buffer.par_for_each(|pos, pixel| {
+            let index = pos.x as u64 * total / width as u64;
+            if let Some(entry) = entry_at(&self.entries, index) {
+                *pixel = entry.color.as_u8vec3();
+            }
+        });
+        buffer
+    }
+}
+
+fn invalid_line(line: usize, reason: impl std::fmt::Display) -> WorleyError {
+    WorleyError::invalid("palette", format!("line {line}: {reason}"))
+}
+
+fn choose_from<R: Rng + ?Sized>(entries: &[PaletteEntry], rng: &mut R) -> Option<Vec3> {
+    let total = total_weight(entries);
+    if total == 0 {
+        return None;
+    }
+    entry_at(entries, rng.random_range(..total)).map(|e| e.color)
+}
+
+fn pick_from(entries: &[PaletteEntry], hash: u64) -> Option<Vec3> {
+    let total = total_weight(entries);
+    if total == 0 {
+        return None;
+    }
+    entry_at(entries, hash % total).map(|e| e.color)
+}
+
+fn total_weight(entries: &[PaletteEntry]) -> u64 {
+    entries.iter().map(|e| e.weight as u64).sum()
+}
+
+// The entry covering `index` when each entry spans `weight` consecutive indices
+fn entry_at(entries: &[PaletteEntry], mut index: u64) -> Option<&PaletteEntry> {
+    entries.iter().find(|e| {
+        if index < e.weight as u64 {
+            true
+        } else {
+            index -= e.weight as u64;
+            false
+        }
+    })
+}
+
+// The palettes that ship with the crate, selectable by name
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BuiltinPalette {
+    #[default]
+    Dracula,
+    Viridis,
+    Magma,
+    Grayscale,
+}
+
+impl BuiltinPalette {
+    pub fn palette(self) -> Palette {
+        match self {
+            BuiltinPalette::Dracula => Palette::dracula(),
+            BuiltinPalette::Viridis => Palette::viridis(),
+            BuiltinPalette::Magma => Palette::magma(),
+            BuiltinPalette::Grayscale => Palette::grayscale(),
+        }
+    }
+
+    // The palette after this one, wrapping around to the first
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&p| p == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    // The built-in palette `palette` is exactly, if any
+    pub fn find(palette: &Palette) -> Option<Self> {
+        Self::ALL.iter().copied().find(|p| p.palette() == *palette)
+    }
+}
+
+impl Named for BuiltinPalette {
+    const KIND: &'static str = "palette";
+    const ALL: &'static [Self] = &[
+        BuiltinPalette::Dracula,
+        BuiltinPalette::Viridis,
+        BuiltinPalette::Magma,
+        BuiltinPalette::Grayscale,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            BuiltinPalette::Dracula => "dracula",
+            BuiltinPalette::Viridis => "viridis",
+            BuiltinPalette::Magma => "magma",
+            BuiltinPalette::Grayscale => "grayscale",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            BuiltinPalette::Dracula => "purples with orange and green accents, the default",
+            BuiltinPalette::Viridis => "perceptually uniform blue through green to yellow",
+            BuiltinPalette::Magma => "perceptually uniform black through purple to cream",
+            BuiltinPalette::Grayscale => "eight even grays",
+        }
+    }
+}
+
+impl std::str::FromStr for BuiltinPalette {
+    type Err = WorleyError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        parse_named(s)
+    }
+}
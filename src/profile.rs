@@ -0,0 +1,255 @@
+//! Per-stage timings of a render, for finding what to optimize. Stages are timed by
+//! scoped guards from `scope`, nested however the calls nest, so hashing shows up both
+//! under the neighbor search and under color selection. Without the `profile` feature
+//! the guards are empty and compile away to nothing.
+//!
+//! Timers cost tens of nanoseconds each, which inflates the finest stages (hashing
+//! above all), so compare stages against each other rather than against a normal build.
+
+// A stage of rendering a pixel
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Stage {
+    // Sampling the hierarchy for a pixel's geometry
+    Sample,
+    // Finding the nearest feature points on one grid
+    NeighborSearch,
+    // Hashing a cell, for its feature point or its color
+    Hash,
+    // Choosing and shading a pixel's color
+    Color,
+    // The per-cell color jitter and texture dithering
+    Dither,
+    // Packing colors into an image or framebuffer
+    Pack,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 6] = [
+        Stage::Sample,
+        Stage::NeighborSearch,
+        Stage::Hash,
+        Stage::Color,
+        Stage::Dither,
+        Stage::Pack,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Stage::Sample => "sample",
+            Stage::NeighborSearch => "neighbor_search",
+            Stage::Hash => "hash",
+            Stage::Color => "color",
+            Stage::Dither => "dither",
+            Stage::Pack => "pack",
+        }
+    }
+}
+
+// Times a stage from now until the guard drops. Bind it to a named variable such as
+// `_scope`; `_` alone drops it immediately.
+#[inline(always)]
+pub fn scope(stage: Stage) -> Scope {
+    imp::scope(stage)
+}
+
+pub use imp::Scope;
+
+// Whether this build records timings
+pub const ENABLED: bool = cfg!(feature = "profile");
+
+// Time spent in one chain of nested stages, summed over every thread
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct StageTime {
+    // Outermost stage first
+    pub path: Vec<Stage>,
+    // Including the nested stages
+    pub total_nanos: u64,
+    // Excluding the nested stages
+    pub self_nanos: u64,
+    pub calls: u64,
+}
+
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Profile {
+    // Sorted by path, so every stage directly follows its parent
+    pub stages: Vec<StageTime>,
+}
+
+#[cfg(feature = "std")]
+impl Profile {
+    // One line per path of `outer;inner microseconds` of self time, the folded stack
+    // format that flamegraph.pl and inferno read
+    pub fn folded(&self) -> String {
+        let mut out = String::new();
+        for stage in &self.stages {
+            let path: Vec<_> = stage.path.iter().map(|s| s.name()).collect();
+            out += &format!("{} {}\n", path.join(";"), stage.self_nanos / 1000);
+        }
+        out
+    }
+}
+
+// A table of the stages indented by nesting, with total and self time in ms
+#[cfg(feature = "std")]
+impl std::fmt::Display for Profile {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:<32} {:>12} {:>12} {:>14}",
+            "stage", "total ms", "self ms", "calls"
+        )?;
+        for stage in &self.stages {
+            let name = format!(
+                "{:indent$}{}",
+                "",
+                stage.path.last().map_or("", |s| s.name()),
+                indent = 2 * (stage.path.len() - 1)
+            );
+            writeln!(
+                f,
+                "{name:<32} {:>12.1} {:>12.1} {:>14}",
+                stage.total_nanos as f64 / 1e6,
+                stage.self_nanos as f64 / 1e6,
+                stage.calls
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Collects the timings recorded on every thread since the last `take`, and clears them.
+// Always empty without the `profile` feature.
+#[cfg(feature = "std")]
+pub fn take() -> Profile {
+    imp::take()
+}
+
+#[cfg(feature = "profile")]
+mod imp {
+    use std::{
+        cell::RefCell,
+        collections::HashMap,
+        sync::{Mutex, PoisonError},
+        time::Instant,
+    };
+
+    use super::{Profile, Stage, StageTime};
+
+    // Open stages packed four bits each, innermost in the low bits, one more than the
+    // stage's index so the empty path is 0
+    type Path = u64;
+
+    #[derive(Default)]
+    struct Timings {
+        path: Path,
+        // Total nanoseconds and calls per path
+        totals: HashMap<Path, (u64, u64)>,
+    }
+
+    thread_local! {
+        static LOCAL: RefCell<Timings> = RefCell::new(Timings::default());
+    }
+
+    static MERGED: Mutex<Option<HashMap<Path, (u64, u64)>>> = Mutex::new(None);
+
+    pub struct Scope {
+        start: Instant,
+        parent: Path,
+    }
+
+    pub fn scope(stage: Stage) -> Scope {
+        let parent = LOCAL.with_borrow_mut(|local| {
+            let parent = local.path;
+            local.path = parent << 4 | (stage as u64 + 1);
+            parent
+        });
+        Scope {
+            start: Instant::now(),
+            parent,
+        }
+    }
+
+    impl Drop for Scope {
+        fn drop(&mut self) {
+            let nanos = self.start.elapsed().as_nanos() as u64;
+            LOCAL.with_borrow_mut(|local| {
+                let entry = local.totals.entry(local.path).or_default();
+                entry.0 += nanos;
+                entry.1 += 1;
+                local.path = self.parent;
+            });
+        }
+    }
+
+    fn flush() {
+        let totals = LOCAL.with_borrow_mut(|local| std::mem::take(&mut local.totals));
+        let mut merged = MERGED.lock().unwrap_or_else(PoisonError::into_inner);
+        let merged = merged.get_or_insert_with(HashMap::new);
+        for (path, (nanos, calls)) in totals {
+            let entry = merged.entry(path).or_default();
+            entry.0 += nanos;
+            entry.1 += calls;
+        }
+    }
+
+    fn unpack(mut path: Path) -> Vec<Stage> {
+        let mut stages = Vec::new();
+        while path != 0 {
+            stages.push(Stage::ALL[(path & 0xf) as usize - 1]);
+            path >>= 4;
+        }
+        stages.reverse();
+        stages
+    }
+
+    pub fn take() -> Profile {
+        // Every rayon worker holds its own timings
+        #[cfg(feature = "rayon")]
+        rayon::broadcast(|_| flush());
+        flush();
+        let merged = MERGED
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+            .unwrap_or_default();
+
+        let mut stages: Vec<StageTime> = merged
+            .iter()
+            .map(|(&path, &(total_nanos, calls))| {
+                let children: u64 = merged
+                    .iter()
+                    .filter(|&(&child, _)| child >> 4 == path)
+                    .map(|(_, &(nanos, _))| nanos)
+                    .sum();
+                StageTime {
+                    path: unpack(path),
+                    total_nanos,
+                    self_nanos: total_nanos.saturating_sub(children),
+                    calls,
+                }
+            })
+            .collect();
+        stages.sort_by(|a, b| a.path.cmp(&b.path));
+        Profile { stages }
+    }
+}
+
+#[cfg(not(feature = "profile"))]
+mod imp {
+    use super::Stage;
+
+    // Nothing to record, and no `Drop` for the optimizer to keep
+    pub struct Scope;
+
+    #[inline(always)]
+    pub fn scope(_stage: Stage) -> Scope {
+        Scope
+    }
+
+    #[cfg(feature = "std")]
+    pub fn take() -> super::Profile {
+        super::Profile::default()
+    }
+}
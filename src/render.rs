@@ -0,0 +1,682 @@
+use std::{fmt::Write, str::FromStr};
+
+use glam::{IVec2, U8Vec3, USizeVec2, Vec2, Vec3};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Buffer, CellColoring, CellId, ColorMode, Config, FlowMode, Idw, WorleyError, WorleyNoise,
+    combine, draw_text,
+    error::Result,
+    mix64,
+    profile::{self, Stage},
+    rgb_from_vec, text_size,
+};
+
+// Cells blended by `CellColoring::Interpolated`
+const INTERPOLATED_CELLS: usize = 3;
+// Line between the halves of `render_split`
+const SPLIT_DIVIDER: U8Vec3 = U8Vec3::new(255, 64, 64);
+
+// A rectangle of output pixels, in the coordinates of the full image
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Region {
+    pub x: i32,
+    pub y: i32,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Region {
+    pub fn origin(&self) -> IVec2 {
+        IVec2::new(self.x, self.y)
+    }
+
+    // Pixels are addressed as i32 from the corner, so the far edges must fit one too
+    pub fn validate(&self) -> Result<()> {
+        if self.width == 0 || self.height == 0 {
+            return Err(WorleyError::invalid("region", "must have a positive size"));
+        }
+        let fits = |corner: i32, size: usize| {
+            i32::try_from(size)
+                .ok()
+                .and_then(|size| corner.checked_add(size))
+                .is_some()
+        };
+        if !(fits(self.x, self.width) && fits(self.y, self.height)) {
+            return Err(WorleyError::invalid(
+                "region",
+                format!("must end by pixel {} on each axis", i32::MAX),
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Parses `x,y,w,h`. The corner may be negative, to render the world left of or above
+// the image.
+impl FromStr for Region {
+    type Err = WorleyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s
+            .split(',')
+            .map(|p| p.trim().parse::<i32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| WorleyError::invalid("region", format!("'{s}': {e}")))?;
+        match parts[..] {
+            [x, y, width, height] if width > 0 && height > 0 => {
+                let region = Self {
+                    x,
+                    y,
+                    width: width as usize,
+                    height: height as usize,
+                };
+                region.validate()?;
+                Ok(region)
+            }
+            [_, _, _, _] => Err(WorleyError::invalid(
+                "region",
+                format!("'{s}' must have a positive size"),
+            )),
+            _ => Err(WorleyError::invalid(
+                "region",
+                format!("'{s}' must be x,y,w,h"),
+            )),
+        }
+    }
+}
+
+pub fn render(buffer: &mut Buffer<U8Vec3>, config: &Config) {
+    render_region(buffer, config, IVec2::ZERO);
+}
+
+// Renders the window of the full image whose top left pixel is `origin`. Sampling
+// uses absolute pixel coordinates, so the result matches the same area of a full render.
+pub fn render_region(buffer: &mut Buffer<U8Vec3>, config: &Config, origin: IVec2) {
+    let fields = Fields::of(config);
+    buffer.par_for_each(|pos, pixel| {
+        *pixel = render_pixel_with((origin + pos.as_ivec2()).as_vec2(), config, &fields)
+    });
+}
+
+// Renders the full image straight into memory the caller owns, such as a GUI
+// framebuffer, packed as 0x00RRGGBB like `Buffer::to_minifb_buffer`. `out` must hold
+// exactly `width * height` pixels in row-major order.
+pub fn render_into(out: &mut [u32], width: usize, height: usize, config: &Config) -> Result<()> {
+    if width.checked_mul(height) != Some(out.len()) {
+        return Err(WorleyError::invalid(
+            "output",
+            format!(
+                "a {width}x{height} render needs {} pixels, but the slice holds {}",
+                width.saturating_mul(height),
+                out.len()
+            ),
+        ));
+    }
+    let fields = Fields::of(config);
+    let render = |(i, pixel): (usize, &mut u32)| {
+        let pos = USizeVec2::new(i % width, i / width);
+        *pixel = rgb_from_vec(render_pixel_with(pos.as_vec2(), config, &fields));
+    };
+    #[cfg(feature = "rayon")]
+    out.par_iter_mut().enumerate().for_each(render);
+    #[cfg(not(feature = "rayon"))]
+    out.iter_mut().enumerate().for_each(render);
+    Ok(())
+}
+
+// Renders like `render_region`, but stops before quantizing, leaving 0 to 255 floats for
+// further processing such as supersampling or HDR output. `quantize` finishes the job.
+pub fn render_float(buffer: &mut Buffer<Vec3>, config: &Config, origin: IVec2) {
+    let (fields, needs) = (Fields::of(config), geometry_needs(config));
+    buffer.par_for_each(|pos, pixel| {
+        let pos = (origin + pos.as_ivec2()).as_vec2();
+        let geometry = sample_geometry_for(pos, config, &fields, needs);
+        *pixel = shade_float_with(&geometry, config, &fields);
+    });
+}
+
+// Quantizes a `render_float` buffer rendered at the same `origin` to 8 bits, giving
+// exactly what `render_region` would have
+pub fn quantize(buffer: &Buffer<Vec3>, config: &Config, origin: IVec2) -> Buffer<U8Vec3> {
+    let width = buffer.width;
+    let mut out = Buffer::new(width, buffer.height, U8Vec3::ZERO);
+    out.par_for_each(|pos, pixel| {
+        let rgb = buffer.buff[pos.x + pos.y * width];
+        *pixel = quantize_pixel(rgb, (origin + pos.as_ivec2()).as_vec2(), config);
+    });
+    out
+}
+
+// Renders like `render_region` with `factor` by `factor` samples per pixel, spread
+// evenly around the pixel's own sample and averaged by `config.color_space` before
+// quantizing. A factor of 1 is exactly `render_region`.
+pub fn render_supersampled(
+    buffer: &mut Buffer<U8Vec3>,
+    config: &Config,
+    origin: IVec2,
+    factor: usize,
+) {
+    if factor <= 1 {
+        return render_region(buffer, config, origin);
+    }
+    let (fields, needs) = (Fields::of(config), geometry_needs(config));
+    buffer.par_for_each(|pos, pixel| {
+        let center = (origin + pos.as_ivec2()).as_vec2();
+        let samples = (0..factor * factor).map(|i| {
+            let sub = Vec2::new((i % factor) as f32, (i / factor) as f32);
+            let pos = center + (sub + 0.5) / factor as f32 - 0.5;
+            let geometry = sample_geometry_for(pos, config, &fields, needs);
+            shade_float_with(&geometry, config, &fields)
+        });
+        *pixel = quantize_pixel(config.color_space.average(samples), center, config);
+    });
+}
+
+// Renders one tile of a larger image into a new buffer. Every pixel depends only on the
+// config and its absolute position, so tiles can be rendered on separate machines and
+// pasted back together into exactly the image a single render would produce.
+pub fn render_region_to_tile(config: &Config, region: Region) -> Buffer<U8Vec3> {
+    let mut tile = Buffer::new(region.width, region.height, U8Vec3::ZERO);
+    render_region(&mut tile, config, region.origin());
+    tile
+}
+
+// Renders the full image at `scale` times its resolution, so pixel (x, y) samples the
+// world position (x, y) / scale. Used by the viewer to trade resolution for speed.
+pub fn render_scaled(buffer: &mut Buffer<U8Vec3>, config: &Config, scale: f32) {
+    let fields = Fields::of(config);
+    buffer.par_for_each(|pos, pixel| {
+        *pixel = render_pixel_with(pos.as_vec2() / scale, config, &fields)
+    });
+}
+
+// Fills `buffer` with the raw world-space distances behind the colors of a render
+pub fn render_distances(buffer: &mut Buffer<f32>, config: &Config, origin: IVec2) {
+    let fields = Fields::of(config);
+    buffer.par_for_each(|pos, dist| {
+        *dist = sample_with((origin + pos.as_ivec2()).as_vec2(), config, &fields).1
+    });
+}
+
+// Fills `field` with the cell and raw distance of each pixel of the window whose top
+// left is `origin`, for coloring with `render_with_shader`
+pub fn render_cells(field: &mut Buffer<(IVec2, f32)>, config: &Config, origin: IVec2) {
+    let fields = Fields::of(config);
+    field.par_for_each(|pos, sample_at| {
+        let (cell, dist) = sample_with((origin + pos.as_ivec2()).as_vec2(), config, &fields);
+        *sample_at = (cell.into(), dist);
+    });
+}
+
+// Colors a field from `render_cells` with a function of your own, called in parallel
+// with each pixel's position within the field, its cell and its distance. The shader
+// must give the same color for the same arguments, or renders stop being reproducible.
+// `shade` with a `PixelGeometry` of just the cell and distance reproduces the built-in
+// coloring, short of the blends that need more geometry.
+pub fn render_with_shader(
+    field: &Buffer<(IVec2, f32)>,
+    shader: impl Fn(USizeVec2, IVec2, f32) -> U8Vec3 + Sync,
+) -> Buffer<U8Vec3> {
+    let mut buffer = Buffer::new(field.width, field.height, U8Vec3::ZERO);
+    let width = field.width;
+    buffer.par_for_each(|pos, pixel| {
+        let (cell, dist) = field.buff[pos.x + pos.y * width];
+        *pixel = shader(pos, cell, dist);
+    });
+    buffer
+}
+
+impl WorleyNoise {
+    // The field as an image: a `width` x `height` grid of `sample_normalized` distances,
+    // 0 to 1, sampled in parallel. Pixel (x, y) samples `origin + (x, y) * step`, so
+    // panning moves the origin and zooming scales the step.
+    pub fn sample_batch_grid(
+        &self,
+        width: usize,
+        height: usize,
+        origin: Vec2,
+        step: Vec2,
+    ) -> Buffer<f32> {
+        let mut buffer = Buffer::new(width, height, 0.0);
+        buffer.par_for_each(|pos, dist| {
+            *dist = self.sample_normalized(origin + pos.as_vec2() * step).1;
+        });
+        buffer
+    }
+}
+
+// Fills `buffer` with the coarsest cells' values smoothly interpolated between them
+pub fn render_smooth_values(buffer: &mut Buffer<f32>, config: &Config, origin: IVec2, idw: Idw) {
+    buffer.par_for_each(|pos, value| {
+        let pos = config.sample_pos((origin + pos.as_ivec2()).as_vec2());
+        *value = config.noise.sample_smooth_value(pos, idw);
+    });
+}
+
+// A side by side of the distance field without and with the hierarchy, labeled, for
+// seeing what the finer levels add. The left half is the coarsest grid's plain `worley`
+// distance and the right the hierarchical distance `sample` blends, both turned and
+// layered as a render would, over the same area of the world and on the same
+// grayscale, black at 0 and white from `max_dist` out.
+pub fn render_split(buffer: &mut Buffer<U8Vec3>, config: &Config) {
+    let max_dist = config.max_dist.to_world(config.noise.cells);
+    // Each layer anchored at its coarsest level, which then weighs in alone
+    let coarsest = |noise: WorleyNoise| WorleyNoise {
+        depth: 0,
+        base_level: Some(0),
+        ..noise
+    };
+    let flat = Config {
+        noise: coarsest(config.noise),
+        overlay: config.overlay.map(coarsest),
+        render_depth: None,
+        ..config.clone()
+    };
+    let (flat_fields, fields) = (Fields::of(&flat), Fields::of(config));
+    let half = buffer.width.div_ceil(2);
+    buffer.par_for_each(|pos, pixel| {
+        if pos.x == half {
+            *pixel = SPLIT_DIVIDER;
+            return;
+        }
+        let dist = if pos.x < half {
+            sample_with(pos.as_vec2(), &flat, &flat_fields).1
+        } else {
+            sample_with((pos - USizeVec2::X * (half + 1)).as_vec2(), config, &fields).1
+        };
+        *pixel = U8Vec3::splat((dist / max_dist * 255.0).clamp(0.0, 255.0) as u8);
+    });
+    for (x, label) in [(0, "worley"), (half + 1, "hierarchical")] {
+        let size = text_size(label, 2) + USizeVec2::splat(8);
+        let corner = USizeVec2::new(x + 4, 4);
+        for y in corner.y..corner.y + size.y {
+            for x in corner.x..corner.x + size.x {
+                buffer.set(USizeVec2::new(x, y), U8Vec3::ZERO);
+            }
+        }
+        draw_text(buffer, corner + 4, label, U8Vec3::splat(255), 2);
+    }
+}
+
+// Fills `buffer` with unit flow vectors around the coarsest grid's feature points, for
+// advecting particles
+pub fn render_flow(buffer: &mut Buffer<Vec2>, config: &Config, origin: IVec2, mode: FlowMode) {
+    // Flows point across the rotated field, so they turn with it
+    let turn = Vec2::from_angle(config.rotation);
+    buffer.par_for_each(|pos, flow| {
+        let pos = config.sample_pos((origin + pos.as_ivec2()).as_vec2());
+        *flow = turn.rotate(config.noise.sample_flow(pos, mode));
+    });
+}
+
+// The fields a config renders, its `rendered_noise` and `rendered_overlay`. Resolving
+// them walks the hierarchy for `min_cell_size`, so renders do it once up front rather
+// than for every pixel.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Fields {
+    pub noise: WorleyNoise,
+    pub overlay: Option<WorleyNoise>,
+}
+
+impl Fields {
+    pub fn of(config: &Config) -> Self {
+        Self {
+            noise: config.rendered_noise(),
+            overlay: config.rendered_overlay(),
+        }
+    }
+}
+
+// The cell and distance that a pixel's color is derived from
+pub fn sample(pos: Vec2, config: &Config) -> (CellId, f32) {
+    sample_with(pos, config, &Fields::of(config))
+}
+
+// `sample` with the fields of `config` already resolved
+pub(crate) fn sample_with(pos: Vec2, config: &Config, fields: &Fields) -> (CellId, f32) {
+    sample_at(config.sample_pos(pos), config, fields)
+}
+
+// `sample` at a position already turned by the config's rotation
+fn sample_at(pos: Vec2, config: &Config, fields: &Fields) -> (CellId, f32) {
+    let noise = &fields.noise;
+    match &fields.overlay {
+        // Scale the combined value back into the primary layer's units for max_dist
+        Some(overlay) => {
+            let (cell, combined) = combine(noise, overlay, config.combine_op, pos);
+            (cell, combined * noise.cells.length())
+        }
+        None => noise.sample(pos),
+    }
+}
+
+pub fn render_pixel(pos: Vec2, config: &Config) -> U8Vec3 {
+    render_pixel_with(pos, config, &Fields::of(config))
+}
+
+// `render_pixel` with the fields of `config` already resolved
+pub(crate) fn render_pixel_with(pos: Vec2, config: &Config, fields: &Fields) -> U8Vec3 {
+    let geometry = sample_geometry_for(pos, config, fields, geometry_needs(config));
+    quantize_pixel(shade_float_with(&geometry, config, fields), pos, config)
+}
+
+// The first `count` pixels of a `width` wide render, row by row, one line each with the
+// cell, the distance, the color before quantizing and the final color. Floats print in
+// full, so diffing the dumps of two machines or builds finds the first pixel and stage
+// where rounding diverges.
+pub fn dump_pixels(config: &Config, width: usize, count: usize) -> String {
+    let width = width.max(1);
+    let (fields, needs) = (Fields::of(config), geometry_needs(config));
+    let mut text = String::new();
+    for i in 0..count {
+        let pos = USizeVec2::new(i % width, i / width).as_vec2();
+        let geometry = sample_geometry_for(pos, config, &fields, needs);
+        let float = shade_float_with(&geometry, config, &fields);
+        let color = quantize_pixel(float, pos, config);
+        writeln!(
+            text,
+            "{},{} cell {},{} dist {:?} float {:?},{:?},{:?} color #{:06x}",
+            pos.x,
+            pos.y,
+            geometry.cell.0.x,
+            geometry.cell.0.y,
+            geometry.dist,
+            float.x,
+            float.y,
+            float.z,
+            rgb_from_vec(color)
+        )
+        .unwrap();
+    }
+    text
+}
+
+// Everything a pixel's color is derived from that depends on the layout of the cells.
+// Colors can be recomputed from this alone while only color parameters change.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PixelGeometry {
+    pub cell: CellId,
+    pub dist: f32,
+    // The second nearest cell and F2 - F1, only sampled when `color_blend` is on
+    pub second: Option<(CellId, f32)>,
+    // The level deciding the pixel, only sampled for `ColorMode::LevelDebug`
+    pub level: usize,
+    // The nearest coarse cells and their distances, closest first, only sampled for
+    // `CellColoring::Interpolated`
+    pub nearest: Option<[(CellId, f32); INTERPOLATED_CELLS + 1]>,
+    // The nearest coarse cell across a wall between finest cells and the finest
+    // F2 - F1, only sampled for `continuous_cells` and only within its fade
+    pub across: Option<(CellId, f32)>,
+}
+
+impl Default for PixelGeometry {
+    fn default() -> Self {
+        Self {
+            cell: CellId(IVec2::ZERO),
+            dist: 0.0,
+            second: None,
+            level: 0,
+            nearest: None,
+            across: None,
+        }
+    }
+}
+
+// Whether the color stage of `config` needs `PixelGeometry::second`, `level` and
+// `nearest`, and the fade `across` is sampled for, 0 for none
+pub(crate) fn geometry_needs(config: &Config) -> (bool, bool, bool, f32) {
+    let level_debug = config.color_mode == ColorMode::LevelDebug;
+    let diagnostic = level_debug || config.color_mode == ColorMode::CellHash;
+    (
+        config.color_blend > 0.0 && !diagnostic,
+        level_debug,
+        config.cell_coloring == CellColoring::Interpolated && !diagnostic,
+        if diagnostic {
+            0.0
+        } else {
+            config.continuous_cells
+        },
+    )
+}
+
+// The geometry of a pixel, sampling only what `config` colors with
+pub fn sample_geometry(pos: Vec2, config: &Config) -> PixelGeometry {
+    sample_geometry_for(pos, config, &Fields::of(config), geometry_needs(config))
+}
+
+// Like `sample_geometry` with the fields of `config` already resolved, choosing whether
+// to sample `second`, `level`, `nearest` and `across`
+pub(crate) fn sample_geometry_for(
+    pos: Vec2,
+    config: &Config,
+    fields: &Fields,
+    (blend, level_debug, interpolate, continuous): (bool, bool, bool, f32),
+) -> PixelGeometry {
+    let _scope = profile::scope(Stage::Sample);
+    let noise = &fields.noise;
+    let pos = config.sample_pos(pos);
+    let (cell, dist) = sample_at(pos, config, fields);
+    PixelGeometry {
+        cell,
+        dist,
+        second: blend.then(|| {
+            let [(_, f1), (second, f2)] = noise.sample_f2(pos);
+            (second, f2 - f1)
+        }),
+        level: if level_debug {
+            noise.dominant_level(pos)
+        } else {
+            0
+        },
+        nearest: interpolate.then(|| {
+            let near = noise.k_nearest(pos, noise.cells, INTERPOLATED_CELLS + 1);
+            // A small toroidal domain can run out of cells, so the last one repeats
+            // infinitely far away, where it weighs nothing
+            std::array::from_fn(|i| match near.get(i) {
+                Some(&(cell, dist)) => (CellId(cell), dist),
+                None => (CellId(near[near.len() - 1].0), f32::INFINITY),
+            })
+        }),
+        across: if continuous > 0.0 {
+            let max_gap = continuous * noise.finest_cell_size().length();
+            match noise.sample_across(pos, max_gap) {
+                // An overlay can pick a cell of its own, with walls of its own
+                (first, across) if first == cell => across,
+                _ => None,
+            }
+        } else {
+            None
+        },
+    }
+}
+
+// The color stage of a render, from a pixel's geometry
+pub fn shade(geometry: &PixelGeometry, pos: Vec2, config: &Config) -> U8Vec3 {
+    shade_with(geometry, pos, config, &Fields::of(config))
+}
+
+// `shade` with the fields of `config` already resolved
+pub(crate) fn shade_with(
+    geometry: &PixelGeometry,
+    pos: Vec2,
+    config: &Config,
+    fields: &Fields,
+) -> U8Vec3 {
+    quantize_pixel(shade_float_with(geometry, config, fields), pos, config)
+}
+
+// The color stage without the final 8-bit quantization, 0 to 255 per channel
+pub fn shade_float(geometry: &PixelGeometry, config: &Config) -> Vec3 {
+    shade_float_with(geometry, config, &Fields::of(config))
+}
+
+// `shade_float` with the fields of `config` already resolved
+pub(crate) fn shade_float_with(geometry: &PixelGeometry, config: &Config, fields: &Fields) -> Vec3 {
+    let _scope = profile::scope(Stage::Color);
+    let noise = &fields.noise;
+    let PixelGeometry { cell, dist, .. } = *geometry;
+    let max_dist = config.max_dist.to_world(noise.cells);
+    if config.color_mode == ColorMode::CellHash {
+        return hash_color(noise.cell_hash(cell.into()));
+    }
+
+    let nearest = geometry.nearest.filter(|_| geometry_needs(config).2);
+    let mut rgb = match (config.color_mode, nearest) {
+        (ColorMode::LevelDebug, _) => level_color(geometry.level, noise.depth),
+        (_, Some(nearest)) => interpolated_color(&nearest, dist, config),
+        _ => cell_color(cell, dist, config),
+    };
+    // Geometry sampled for other configs may carry a second cell this one doesn't blend
+    if let Some((second, gap)) = geometry.second.filter(|_| geometry_needs(config).0) {
+        // Mix towards the second nearest cell as F2 - F1 shrinks, reaching an even
+        // split exactly on the boundary
+        let width = config.color_blend * noise.cells.length();
+        let t = (gap / width).clamp(0.0, 1.0);
+        rgb = rgb.lerp(cell_color(second, dist, config), 0.5 * (1.0 - t));
+    }
+    if let Some((across, gap)) = geometry.across.filter(|_| geometry_needs(config).3 > 0.0) {
+        // Fades towards the cell across the wall, reaching an even mix on it
+        let width = config.continuous_cells * noise.finest_cell_size().length();
+        let t = (gap / width).clamp(0.0, 1.0);
+        rgb = rgb.lerp(cell_color(across, dist, config), 0.5 * (1.0 - t));
+    }
+
+    let falloff = (1.0 - dist / max_dist).max(0.0).powf(config.dist_power);
+    config
+        .tonemap
+        .apply(rgb.lerp(config.background, 1.0 - falloff))
+}
+
+// Rounds a float color to 8 bits, dithered by the config's texture if it has one.
+// Colors with a NaN or infinite channel, from degenerate parameters upstream, come out
+// as the fallback color rather than whatever the cast makes of them.
+pub fn quantize_pixel(rgb: Vec3, pos: Vec2, config: &Config) -> U8Vec3 {
+    // Raw hash colors stay exact
+    let dithered = config.color_mode != ColorMode::CellHash;
+    let rgb = match config.cell_coloring {
+        CellColoring::Dithered if dithered => grain(rgb, pos, config),
+        _ => rgb,
+    };
+    let rgb = match config.dither.as_ref().filter(|_| dithered) {
+        Some(texture) => {
+            let _scope = profile::scope(Stage::Dither);
+            rgb + texture.threshold(pos)
+        }
+        None => rgb,
+    };
+    let rgb = if rgb.is_finite() {
+        rgb
+    } else if config.fallback_color.is_finite() {
+        config.fallback_color
+    } else {
+        Vec3::ZERO
+    };
+    rgb.clamp(Vec3::ZERO, Vec3::splat(255.0)).as_u8vec3()
+}
+
+// The palette color of a cell, 0 to 255 per channel. Every decision comes from the
+// cell's color hash and position: its low bits pick the palette entry, drifted by any
+// spatial bias and, for `CellColoring::Flat`,
+// a copy remixed by `mix64` supplies 16 bits of dither noise per channel. Cells the
+// palette can't color get the undithered fallback color.
+fn cell_color(cell: CellId, dist: f32, config: &Config) -> Vec3 {
+    let noise = &config.noise;
+    let hash = config.color_hash(cell.into());
+
+    let max_dist = config.max_dist.to_world(noise.cells);
+    let picked = match config.color_mode {
+        ColorMode::Hash if config.spatial_color_bias != Vec2::ZERO => {
+            let bias = config.spatial_color_bias;
+            let spread = 1.0 - bias.length().min(1.0);
+            let shift = bias.dot(IVec2::from(cell).as_vec2() + 0.5);
+            config.palette.pick_in_window(hash, spread, shift)
+        }
+        ColorMode::Hash | ColorMode::LevelDebug | ColorMode::CellHash => config.palette.pick(hash),
+        ColorMode::DistanceBanded => {
+            let band = (dist / max_dist * config.bands as f32).max(0.0) as usize;
+            config.palette.pick_in_band(band, config.bands, hash)
+        }
+    };
+    let Some(rgb) = picked else {
+        return config.fallback_color;
+    };
+
+    match config.cell_coloring {
+        CellColoring::Flat => jitter(rgb, mix64(hash)),
+        // Grained per pixel at quantization, or blended smooth
+        CellColoring::Dithered | CellColoring::Interpolated => rgb,
+    }
+}
+
+// Inverse distance weighting of the nearest cells' colors. Each weight has the next
+// farther cell's weight subtracted, so a cell fades out exactly as it leaves the
+// nearest and the colors never jump.
+fn interpolated_color(
+    nearest: &[(CellId, f32); INTERPOLATED_CELLS + 1],
+    dist: f32,
+    config: &Config,
+) -> Vec3 {
+    let far = nearest[INTERPOLATED_CELLS].1;
+    let (mut total, mut weights) = (Vec3::ZERO, 0.0);
+    for &(cell, near) in &nearest[..INTERPOLATED_CELLS] {
+        if near <= 0.0 {
+            return cell_color(cell, dist, config);
+        }
+        let inverse = Vec2::new(near, far).powf(-2.0);
+        let weight = (inverse.x - inverse.y).max(0.0);
+        total += weight * cell_color(cell, dist, config);
+        weights += weight;
+    }
+    if weights > 0.0 {
+        total / weights
+    } else {
+        cell_color(nearest[0].0, dist, config)
+    }
+}
+
+// The per-pixel dither of `CellColoring::Dithered`, from a hash of the position
+fn grain(rgb: Vec3, pos: Vec2, config: &Config) -> Vec3 {
+    let bits = (pos.x.to_bits() as u64) << 32 | pos.y.to_bits() as u64;
+    jitter(rgb, mix64(bits ^ config.noise.seed))
+}
+
+// Dithers each channel with 16 of the bits
+fn jitter(rgb: Vec3, bits: u64) -> Vec3 {
+    let _scope = profile::scope(Stage::Dither);
+    let channel = |value: f32, shift: u32| dither(value, (bits >> shift) as u16);
+    Vec3::new(channel(rgb.x, 0), channel(rgb.y, 16), channel(rgb.z, 32))
+}
+
+// Jitters a 0 to 255 channel with the spread of a Binomial(255, value / 255) draw, the
+// dithering this replaced. Two bytes summed give a triangular offset with standard
+// deviation 1 / sqrt(6), which is rescaled to the binomial's.
+fn dither(value: f32, bits: u16) -> f32 {
+    let p = (value / 255.0).clamp(0.0, 1.0);
+    let sigma = (255.0 * p * (1.0 - p)).sqrt();
+    let offset = ((bits & 0xff) as f32 + (bits >> 8) as f32 - 255.0) / 255.0;
+    (value + offset * sigma * 6f32.sqrt())
+        .round()
+        .clamp(0.0, 255.0)
+}
+
+// Bits 16 to 23 of a hash as red, 8 to 15 as green and 0 to 7 as blue
+fn hash_color(hash: u64) -> Vec3 {
+    Vec3::new(
+        (hash >> 16 & 0xff) as f32,
+        (hash >> 8 & 0xff) as f32,
+        (hash & 0xff) as f32,
+    )
+}
+
+// Spreads the levels 0..=depth around the hue wheel, 0 to 255 per channel
+fn level_color(level: usize, depth: usize) -> Vec3 {
+    let hue = level as f32 / (depth + 1) as f32 * 6.0;
+    let channel = |offset: f32| {
+        let k = (hue + offset) % 6.0;
+        1.0 - (k.min(4.0 - k).clamp(0.0, 1.0))
+    };
+    Vec3::new(channel(5.0), channel(3.0), channel(1.0)) * 255.0
+}
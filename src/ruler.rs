@@ -0,0 +1,108 @@
+//! Rulers along the top and left edges of an image for lining the noise up with other
+//! assets. Ticks mark world coordinates at a round spacing that keeps them apart on
+//! screen, with every fifth tick longer and labeled, and separate marks show where the
+//! coarsest cells' grid lines meet the edge.
+
+use glam::{U8Vec3, USizeVec2, Vec2};
+
+use crate::{Buffer, draw_text};
+
+const RULER_BACKGROUND: U8Vec3 = U8Vec3::new(16, 16, 16);
+const RULER_INK: U8Vec3 = U8Vec3::new(230, 230, 230);
+const CELL_MARK: U8Vec3 = U8Vec3::new(255, 184, 108);
+
+// Depth of each ruler strip in pixels: cell marks, then room for five digits across
+// the left ruler, with the minor ticks alongside the labels
+pub const RULER_SIZE: usize = 24;
+// Closest two minor ticks may be on screen
+const MIN_TICK_SPACING: f32 = 8.0;
+const MAJOR_EVERY: i64 = 5;
+
+// The smallest 1, 2 or 5 times a power of ten that is at least `min`
+pub fn tick_spacing(min: f32) -> f32 {
+    let mut step = 1.0;
+    while step < min {
+        step *= 10.0;
+    }
+    while step / 10.0 >= min {
+        step /= 10.0;
+    }
+    for candidate in [step / 5.0, step / 2.0] {
+        if candidate >= min {
+            return candidate;
+        }
+    }
+    step
+}
+
+// Draws the rulers over `buffer`, whose pixel (0, 0) shows world position `origin` and
+// each pixel spans `scale` world units. `cell_size` places the coarse cell marks.
+pub fn draw_ruler(buffer: &mut Buffer<U8Vec3>, origin: Vec2, scale: f32, cell_size: Vec2) {
+    let (width, height) = (buffer.width, buffer.height);
+    for y in 0..height.min(RULER_SIZE) {
+        for x in 0..width {
+            buffer.set(USizeVec2::new(x, y), RULER_BACKGROUND);
+        }
+    }
+    for y in RULER_SIZE.min(height)..height {
+        for x in 0..width.min(RULER_SIZE) {
+            buffer.set(USizeVec2::new(x, y), RULER_BACKGROUND);
+        }
+    }
+
+    // Whole world units at the finest, so every label is an integer
+    let step = tick_spacing((MIN_TICK_SPACING * scale).max(1.0));
+    // Along the top ruler x is the axis, along the left ruler y is
+    for axis in 0..2 {
+        let length = if axis == 0 { width } else { height };
+        let start = origin[axis];
+        let end = start + length as f32 * scale;
+        let draw = |buffer: &mut Buffer<U8Vec3>, along: usize, across: usize, color| {
+            let pos = match axis {
+                0 => USizeVec2::new(along, across),
+                _ => USizeVec2::new(across, along),
+            };
+            // Keep the corner where the rulers meet clear
+            if along >= RULER_SIZE {
+                buffer.set(pos, color);
+            }
+        };
+
+        // Coarse cell grid lines, as short marks on the outer edge
+        let first_cell = (start / cell_size[axis]).ceil() as i64;
+        for i in first_cell.. {
+            let world = i as f32 * cell_size[axis];
+            if world >= end {
+                break;
+            }
+            let along = ((world - start) / scale).round() as usize;
+            for across in 0..3 {
+                draw(buffer, along, across, CELL_MARK);
+            }
+        }
+
+        let first = (start / step).ceil() as i64;
+        for i in first.. {
+            let world = i as f32 * step;
+            if world >= end {
+                break;
+            }
+            let along = ((world - start) / scale).round() as usize;
+            // Ticks grow from the inner edge, major ones reaching past the labels
+            let major = i % MAJOR_EVERY == 0;
+            let length = if major { RULER_SIZE - 4 } else { 8 };
+            for across in RULER_SIZE - length..RULER_SIZE {
+                draw(buffer, along, across, RULER_INK);
+            }
+            // Labels sit just past their tick, unless it's hidden under the corner
+            if major && along >= RULER_SIZE {
+                let label = (world.round() as i64).to_string();
+                let corner = match axis {
+                    0 => USizeVec2::new(along + 2, 4),
+                    _ => USizeVec2::new(4, along + 2),
+                };
+                draw_text(buffer, corner, &label, RULER_INK, 1);
+            }
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! JSON sidecars recording everything behind a render, so any saved image can be
+//! reproduced exactly with `--config`. PNGs can carry the same record in their text
+//! chunks, keeping it attached to the image itself.
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Config, Region, WorleyError, error::Result, render_metadata};
+
+// The PNG text keyword holding the whole record as JSON
+pub const PNG_CONFIG_KEY: &str = "worley:config";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RenderConfig {
+    // The crate version that wrote the file. Layouts can change between versions even
+    // with identical parameters.
+    pub version: String,
+    pub width: usize,
+    pub height: usize,
+    // The window of the `width` by `height` image actually rendered, None for all of it
+    #[serde(default)]
+    pub region: Option<Region>,
+    // Includes the seed, as `config.noise.seed`
+    pub config: Config,
+}
+
+impl RenderConfig {
+    pub fn new(config: &Config, width: usize, height: usize) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            width,
+            height,
+            region: None,
+            config: config.clone(),
+        }
+    }
+
+    // The same record for a render cropped to `region`
+    pub fn with_region(self, region: Option<Region>) -> Self {
+        Self { region, ..self }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        Ok(serde_json::to_writer_pretty(writer, self)?)
+    }
+
+    // Loads a JSON sidecar, or the record embedded in a .png by `png_text`, rejecting
+    // records edited into something that can't be rendered
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let record: Self = if path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("png"))
+        {
+            Self::from_png_text(&read_png_text(path)?)?
+        } else {
+            serde_json::from_reader(BufReader::new(File::open(path)?))?
+        };
+        record.validate()?;
+        Ok(record)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.config.validate()?;
+        match &self.region {
+            Some(region) => region.validate(),
+            None => Ok(()),
+        }
+    }
+
+    // PNG text for `Buffer::save_png_with_text`: the readable keys of `render_metadata`
+    // and the size, for anyone inspecting the file, and the whole record as JSON under
+    // `PNG_CONFIG_KEY` to reproduce it from
+    pub fn png_text(&self) -> Result<Vec<(String, String)>> {
+        let mut text = render_metadata(&self.config);
+        text.push(("worley:width".to_string(), self.width.to_string()));
+        text.push(("worley:height".to_string(), self.height.to_string()));
+        if let Some(Region {
+            x,
+            y,
+            width,
+            height,
+        }) = self.region
+        {
+            text.push((
+                "worley:region".to_string(),
+                format!("{x},{y},{width},{height}"),
+            ));
+        }
+        text.push((PNG_CONFIG_KEY.to_string(), serde_json::to_string(self)?));
+        Ok(text)
+    }
+
+    // The record in text read back from a PNG
+    pub fn from_png_text(text: &[(String, String)]) -> Result<Self> {
+        let (_, json) = text
+            .iter()
+            .find(|(keyword, _)| keyword == PNG_CONFIG_KEY)
+            .ok_or_else(|| WorleyError::invalid("png metadata", format!("no {PNG_CONFIG_KEY}")))?;
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+// Every text chunk before a PNG's image data, as keyword and value in file order by
+// kind: tEXt, then zTXt, then iTXt
+pub fn read_png_text(path: impl AsRef<Path>) -> Result<Vec<(String, String)>> {
+    let decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+    let reader = decoder.read_info()?;
+    let info = reader.info();
+    let mut text: Vec<_> = info
+        .uncompressed_latin1_text
+        .iter()
+        .map(|chunk| (chunk.keyword.clone(), chunk.text.clone()))
+        .collect();
+    for chunk in &info.compressed_latin1_text {
+        text.push((chunk.keyword.clone(), chunk.get_text()?));
+    }
+    for chunk in &info.utf8_text {
+        text.push((chunk.keyword.clone(), chunk.get_text()?));
+    }
+    Ok(text)
+}
+
+// Where the sidecar of an image goes: beside it, with a .json extension
+pub fn sidecar_path(image: impl AsRef<Path>) -> PathBuf {
+    image.as_ref().with_extension("json")
+}
@@ -0,0 +1,66 @@
+//! Streaming PNG export for images too large to hold in memory. The image is rendered
+//! one strip of rows at a time with the tiled renderer and each strip is compressed
+//! straight into the PNG before the next is rendered, so memory use depends on the
+//! width rather than the full image size.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use glam::{IVec2, U8Vec3};
+
+use crate::{Buffer, Config, Region, error::Result, render_tiled};
+
+// Rows rendered per strip. Large enough to keep every thread busy, small enough that a
+// strip of a very wide image still fits comfortably in memory.
+pub const STRIP_ROWS: usize = 256;
+
+// Writes `region` of the image as an RGB PNG, rendering it strip by strip
+pub fn write_png_streaming(
+    writer: impl Write,
+    config: &Config,
+    region: Region,
+    tile_size: usize,
+) -> Result<()> {
+    let mut encoder = png::Encoder::new(writer, region.width as u32, region.height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut png = encoder.write_header()?;
+    let mut stream = png.stream_writer()?;
+
+    let mut strip = Buffer::new(region.width, STRIP_ROWS, U8Vec3::ZERO);
+    let mut bytes = Vec::with_capacity(region.width * STRIP_ROWS * 3);
+    for y in (0..region.height).step_by(STRIP_ROWS) {
+        let rows = STRIP_ROWS.min(region.height - y);
+        if rows != strip.height {
+            strip = Buffer::new(region.width, rows, U8Vec3::ZERO);
+        }
+        render_tiled(
+            &mut strip,
+            config,
+            region.origin() + IVec2::new(0, y as i32),
+            tile_size,
+        );
+
+        bytes.clear();
+        bytes.extend(strip.buff.iter().flat_map(|rgb| rgb.to_array()));
+        stream.write_all(&bytes)?;
+    }
+    stream.finish()?;
+    png.finish()?;
+    Ok(())
+}
+
+pub fn save_png_streaming(
+    path: impl AsRef<Path>,
+    config: &Config,
+    region: Region,
+    tile_size: usize,
+) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_png_streaming(&mut writer, config, region, tile_size)?;
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,160 @@
+//! Vector export of the cells as SVG polygons. Cells are traced from a raster of cell
+//! ids along the pixel edges between differing ids, so neighboring polygons share their
+//! boundaries exactly and leave no gaps, at the cost of a staircase wherever a wall
+//! isn't horizontal or vertical. Straight runs are merged into single segments.
+
+use std::{collections::HashMap, fmt::Write as _, path::Path};
+
+use glam::{IVec2, USizeVec2, Vec3};
+
+use crate::{
+    Buffer, CellId, Config, Region,
+    error::Result,
+    render::{Fields, sample_with},
+};
+
+// One cell's outline. Outer boundaries run clockwise on screen and holes
+// counterclockwise, so the nonzero fill rule fills exactly the cell.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellPolygon {
+    pub cell: CellId,
+    // Closed loops of pixel corners, without repeating the first corner
+    pub loops: Vec<Vec<USizeVec2>>,
+}
+
+// The coarsest cell of every pixel in `region`, as `render_region` samples them
+pub fn cell_ids(config: &Config, region: Region) -> Buffer<CellId> {
+    let mut ids = Buffer::new(region.width, region.height, CellId(IVec2::ZERO));
+    let origin = region.origin();
+    let fields = Fields::of(config);
+    ids.par_for_each(|pos, id| {
+        *id = sample_with((origin + pos.as_ivec2()).as_vec2(), config, &fields).0
+    });
+    ids
+}
+
+// Traces every cell in `ids`, in the order each first appears scanning row by row
+pub fn trace_cells(ids: &Buffer<CellId>) -> Vec<CellPolygon> {
+    let (width, height) = (ids.width, ids.height);
+    let id = |x: usize, y: usize| ids.buff[x + y * width];
+    let mut order = Vec::new();
+    // Directed edges between pixel corners, keyed by their start
+    let mut edges: HashMap<CellId, HashMap<USizeVec2, Vec<USizeVec2>>> = HashMap::new();
+
+    for y in 0..height {
+        for x in 0..width {
+            let cell = id(x, y);
+            let cell_edges = edges.entry(cell).or_insert_with(|| {
+                order.push(cell);
+                HashMap::new()
+            });
+            let corner = |dx: usize, dy: usize| USizeVec2::new(x + dx, y + dy);
+            // Each pixel's sides walked clockwise, kept where the neighbor differs
+            let sides = [
+                (y == 0 || id(x, y - 1) != cell, corner(0, 0), corner(1, 0)),
+                (
+                    x + 1 == width || id(x + 1, y) != cell,
+                    corner(1, 0),
+                    corner(1, 1),
+                ),
+                (
+                    y + 1 == height || id(x, y + 1) != cell,
+                    corner(1, 1),
+                    corner(0, 1),
+                ),
+                (x == 0 || id(x - 1, y) != cell, corner(0, 1), corner(0, 0)),
+            ];
+            for (boundary, from, to) in sides {
+                if boundary {
+                    cell_edges.entry(from).or_default().push(to);
+                }
+            }
+        }
+    }
+
+    order
+        .into_iter()
+        .map(|cell| CellPolygon {
+            cell,
+            loops: link_loops(edges.remove(&cell).unwrap_or_default()),
+        })
+        .collect()
+}
+
+// Follows edges end to start until each loop closes, dropping corners along straight runs
+fn link_loops(mut edges: HashMap<USizeVec2, Vec<USizeVec2>>) -> Vec<Vec<USizeVec2>> {
+    let mut starts: Vec<USizeVec2> = edges.keys().copied().collect();
+    // Sorted so the output doesn't depend on hash map order
+    starts.sort_by_key(|c| (c.y, c.x));
+    let mut loops = Vec::new();
+    for start in starts {
+        while let Some(mut to) = edges.get_mut(&start).and_then(Vec::pop) {
+            let mut corners = vec![start];
+            while to != start {
+                corners.push(to);
+                let Some(next) = edges.get_mut(&to).and_then(Vec::pop) else {
+                    break;
+                };
+                to = next;
+            }
+            loops.push(simplify(corners));
+        }
+    }
+    loops
+}
+
+fn simplify(corners: Vec<USizeVec2>) -> Vec<USizeVec2> {
+    let n = corners.len();
+    let direction = |a: USizeVec2, b: USizeVec2| b.as_ivec2() - a.as_ivec2();
+    (0..n)
+        .filter(|&i| {
+            let (prev, here, next) = (corners[(i + n - 1) % n], corners[i], corners[(i + 1) % n]);
+            direction(prev, here).signum() != direction(here, next).signum()
+        })
+        .map(|i| corners[i])
+        .collect()
+}
+
+// The flat palette color of a cell, without dithering or distance falloff
+fn fill(cell: CellId, config: &Config) -> Vec3 {
+    config
+        .palette
+        .pick(config.color_hash(cell.into()))
+        .unwrap_or(config.fallback_color)
+}
+
+// An SVG document with one filled path per cell, sized to the region in pixels
+pub fn render_svg(config: &Config, region: Region) -> String {
+    let polygons = trace_cells(&cell_ids(config, region));
+    let (width, height) = (region.width, region.height);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\">\n"
+    );
+    for polygon in polygons {
+        let rgb = fill(polygon.cell, config)
+            .clamp(Vec3::ZERO, Vec3::splat(255.0))
+            .round()
+            .as_u8vec3();
+        let mut path = String::new();
+        for corners in &polygon.loops {
+            for (i, c) in corners.iter().enumerate() {
+                let command = if i == 0 { 'M' } else { 'L' };
+                let _ = write!(path, "{command}{} {}", c.x, c.y);
+            }
+            path.push('Z');
+        }
+        let _ = writeln!(
+            svg,
+            "<path fill=\"#{:02x}{:02x}{:02x}\" d=\"{path}\"/>",
+            rgb.x, rgb.y, rgb.z
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+pub fn save_svg(path: impl AsRef<Path>, config: &Config, region: Region) -> Result<()> {
+    std::fs::write(path, render_svg(config, region))?;
+    Ok(())
+}
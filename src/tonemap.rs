@@ -0,0 +1,151 @@
+//! Tone mapping for colors that leave the displayable range. Colors are decoded from
+//! sRGB to linear light, compressed into 0..1 by the chosen curve, then encoded back, so
+//! bright values roll off smoothly instead of clipping to white.
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::Named;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ToneMap {
+    // Clip at the display range, matching output from before tone mapping existed
+    #[default]
+    None,
+    Reinhard,
+    Aces,
+}
+
+// Linear value that extended Reinhard maps to full white
+const REINHARD_WHITE: f32 = 4.0;
+
+impl ToneMap {
+    // Maps a color of 0 to 255 per channel, which may exceed 255, into 0 to 255
+    pub fn apply(self, rgb: Vec3) -> Vec3 {
+        let curve: fn(f32) -> f32 = match self {
+            ToneMap::None => return rgb,
+            ToneMap::Reinhard => |x| x * (1.0 + x / (REINHARD_WHITE * REINHARD_WHITE)) / (1.0 + x),
+            // Narkowicz's fit of the ACES filmic curve
+            ToneMap::Aces => |x| (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14),
+        };
+        let linear = srgb_to_linear(rgb / 255.0);
+        linear_to_srgb(linear.map(curve).clamp(Vec3::ZERO, Vec3::ONE)) * 255.0
+    }
+}
+
+impl Named for ToneMap {
+    const KIND: &'static str = "tone map";
+    const ALL: &'static [Self] = &[ToneMap::None, ToneMap::Reinhard, ToneMap::Aces];
+
+    fn name(self) -> &'static str {
+        match self {
+            ToneMap::None => "none",
+            ToneMap::Reinhard => "reinhard",
+            ToneMap::Aces => "aces",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            ToneMap::None => "clip out of range colors",
+            ToneMap::Reinhard => "gentle roll off that keeps hues, reaching white at 4x",
+            ToneMap::Aces => "filmic contrast curve with a soft shoulder",
+        }
+    }
+}
+
+impl std::str::FromStr for ToneMap {
+    type Err = crate::WorleyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::named::parse_named(s)
+    }
+}
+
+// The sRGB transfer functions, per channel in 0..1. Values past 1 follow the curve out.
+pub fn srgb_to_linear(srgb: Vec3) -> Vec3 {
+    srgb.map(|c| {
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    })
+}
+
+pub fn linear_to_srgb(linear: Vec3) -> Vec3 {
+    linear.map(|c| {
+        if c <= 0.0031308 {
+            c * 12.92
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        }
+    })
+}
+
+// The encoding of the 0 to 255 colors a render produces, which decides how samples are
+// averaged when supersampling. Light adds linearly, so sRGB samples are decoded before
+// averaging and encoded after; averaging the encoded values darkens every edge.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColorSpace {
+    // Palette colors are sRGB, as image files and color pickers give them
+    #[default]
+    Srgb,
+    // Colors are already linear, so samples average as they are
+    Linear,
+}
+
+impl ColorSpace {
+    // The mean of 0 to 255 colors, as light. Empty input averages to black, and samples
+    // all of one color give back exactly that color, free of round trip error that
+    // would otherwise quantize white down a step.
+    pub fn average(self, samples: impl IntoIterator<Item = Vec3>) -> Vec3 {
+        let mut samples = samples.into_iter();
+        let Some(first) = samples.next() else {
+            return Vec3::ZERO;
+        };
+        let decode = |rgb: Vec3| match self {
+            ColorSpace::Srgb => srgb_to_linear(rgb / 255.0),
+            ColorSpace::Linear => rgb,
+        };
+        let (mut total, mut count, mut uniform) = (decode(first), 1, true);
+        for rgb in samples {
+            total += decode(rgb);
+            count += 1;
+            uniform &= rgb == first;
+        }
+        let mean = total / count as f32;
+        match self {
+            _ if uniform => first,
+            ColorSpace::Srgb => linear_to_srgb(mean) * 255.0,
+            ColorSpace::Linear => mean,
+        }
+    }
+}
+
+impl Named for ColorSpace {
+    const KIND: &'static str = "color space";
+    const ALL: &'static [Self] = &[ColorSpace::Srgb, ColorSpace::Linear];
+
+    fn name(self) -> &'static str {
+        match self {
+            ColorSpace::Srgb => "srgb",
+            ColorSpace::Linear => "linear",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            ColorSpace::Srgb => "colors are sRGB, averaged in linear light",
+            ColorSpace::Linear => "colors are linear, averaged as they are",
+        }
+    }
+}
+
+impl std::str::FromStr for ColorSpace {
+    type Err = crate::WorleyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::named::parse_named(s)
+    }
+}
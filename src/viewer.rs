@@ -0,0 +1,460 @@
+use std::{
+    io::BufRead,
+    path::Path,
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::{Duration, Instant},
+};
+
+use glam::{IVec2, U8Vec3, USizeVec2, Vec2};
+use layered_worley::{
+    AlphaSource, AnimationClock, Buffer, BuiltinPalette, ColorMode, Config, Histogram, Named,
+    RULER_SIZE, RenderCache, WorleyError, draw_ruler, draw_text, render, render_alpha,
+    render_scaled, text_size,
+};
+use minifb::{Key, KeyRepeat, Scale, ScaleMode, Window, WindowOptions};
+use rand::random;
+
+use crate::cli::apply_line;
+
+// Frame time the adaptive resolution aims to hold while the view is changing
+const TARGET_FRAME_TIME: Duration = Duration::from_millis(33);
+const MIN_RENDER_SCALE: f32 = 0.1;
+const MAX_RENDER_SCALE: f32 = 1.0;
+
+// Bins in the distance histogram panel, toggled with H
+const HISTOGRAM_BINS: usize = 60;
+const HASH_VIEW_LABEL: &str = "CELL HASH";
+const ROTATED_RULER_LABEL: &str = "NO RULERS WHILE ROTATED";
+
+// Fraction of the window resolution to render at, adjusted from the last frame time
+pub struct AdaptiveScale {
+    pub render_scale: f32,
+}
+
+impl AdaptiveScale {
+    pub fn new() -> Self {
+        Self {
+            render_scale: MAX_RENDER_SCALE,
+        }
+    }
+
+    pub fn update(&mut self, frame_time: Duration) {
+        // Render cost follows the pixel count, which is the square of the scale. The step
+        // is limited so a single slow frame can't collapse the resolution.
+        let ratio = TARGET_FRAME_TIME.as_secs_f32() / frame_time.as_secs_f32().max(1e-6);
+        let step = ratio.sqrt().clamp(0.5, 2.0);
+        self.render_scale = (self.render_scale * step).clamp(MIN_RENDER_SCALE, MAX_RENDER_SCALE);
+    }
+
+    pub fn size(&self, width: usize, height: usize) -> (usize, usize) {
+        let scaled = |n: usize| ((n as f32 * self.render_scale).round() as usize).max(1);
+        (scaled(width), scaled(height))
+    }
+}
+
+// How the rendered buffer is shown in the window
+#[derive(Clone, Copy, Debug)]
+pub struct WindowScale {
+    pub scale: Scale,
+    // Only set when asked for, which also makes the window resizable
+    pub scale_mode: Option<ScaleMode>,
+}
+
+impl Default for WindowScale {
+    fn default() -> Self {
+        Self {
+            scale: Scale::X1,
+            scale_mode: None,
+        }
+    }
+}
+
+impl WindowScale {
+    fn options(self) -> WindowOptions {
+        WindowOptions {
+            scale: self.scale,
+            scale_mode: self.scale_mode.unwrap_or(ScaleMode::UpperLeft),
+            resize: self.scale_mode.is_some(),
+            ..WindowOptions::default()
+        }
+    }
+}
+
+// Parses 1, 2, 4, 8, 16, 32 or fit
+pub fn parse_scale(value: &str) -> Result<Scale, String> {
+    match value {
+        "1" => Ok(Scale::X1),
+        "2" => Ok(Scale::X2),
+        "4" => Ok(Scale::X4),
+        "8" => Ok(Scale::X8),
+        "16" => Ok(Scale::X16),
+        "32" => Ok(Scale::X32),
+        "fit" => Ok(Scale::FitScreen),
+        _ => Err(format!("unknown window scale '{value}'")),
+    }
+}
+
+// Parses stretch, aspect, center or upper-left
+pub fn parse_scale_mode(value: &str) -> Result<ScaleMode, String> {
+    match value {
+        "stretch" => Ok(ScaleMode::Stretch),
+        "aspect" => Ok(ScaleMode::AspectRatioStretch),
+        "center" => Ok(ScaleMode::Center),
+        "upper-left" => Ok(ScaleMode::UpperLeft),
+        _ => Err(format!("unknown scale mode '{value}'")),
+    }
+}
+
+// Saves from the viewer, reporting failures without losing the render
+fn try_save(buffer: &Buffer<U8Vec3>, path: &Path) -> bool {
+    match buffer.save(path) {
+        Ok(()) => {
+            println!("Saved {}", path.display());
+            true
+        }
+        Err(e) => {
+            eprintln!("Failed to save {}: {e}", path.display());
+            false
+        }
+    }
+}
+
+// Renders straight to output.png, for when there's no display to open a window on
+fn render_headless(config: &Config, width: usize, height: usize) -> Result<(), WorleyError> {
+    let mut buffer = Buffer::new(width, height, U8Vec3::ZERO);
+    render(&mut buffer, config);
+    buffer.save("output.png")?;
+    println!("Saved output.png");
+    Ok(())
+}
+
+// Forwards stdin line by line from a background thread, so reading never blocks a frame
+fn read_stdin_lines() -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in std::io::stdin().lock().lines().map_while(Result::ok) {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+// Applies every line that has arrived since the last frame, keeping the config valid.
+// Returns whether anything changed.
+fn apply_stdin(config: &mut Config, lines: &Receiver<String>) -> bool {
+    let mut changed = false;
+    for line in lines.try_iter() {
+        let mut updated = config.clone();
+        match apply_line(&mut updated, &line).and_then(|changed| {
+            updated.validate().map_err(|e| e.to_string())?;
+            Ok(changed)
+        }) {
+            Ok(true) => {
+                *config = updated;
+                changed = true;
+            }
+            Ok(false) => {}
+            Err(e) => eprintln!("ignoring '{line}': {e}"),
+        }
+    }
+    changed
+}
+
+// The window title, naming the palette so cycling through them shows which is which
+fn title(config: &Config, paused: bool) -> String {
+    let palette = BuiltinPalette::find(&config.palette).map_or("custom", |p| p.name());
+    let paused = if paused { " - paused" } else { "" };
+    format!(
+        "Test - palette {palette}{paused} - Space to pause, S to save, C for new colors, \
+         P for next palette, H for histogram, R for rulers, X for cell hashes, A for alpha, \
+         ESC to exit"
+    )
+}
+
+// Summarizes where the distances fall relative to max_dist
+fn print_histogram(histogram: Option<&Histogram>) {
+    let Some(histogram) = histogram else {
+        println!("no histogram until the view settles");
+        return;
+    };
+    let [low, median, high] = [0.1, 0.5, 0.9].map(|q| histogram.quantile(q));
+    let faded = histogram.count_from(1.0);
+    println!(
+        "distance / max_dist: 10% below {low:.2}, median {median:.2}, 90% below {high:.2}, \
+         {faded} pixels past max_dist"
+    );
+}
+
+// Whether the view is still changing and drawn at low resolution. A pulsing config
+// changes every frame, so it never settles unless the animation is paused.
+fn refreshing(config: &Config, refresh: Instant, clock: &AnimationClock) -> bool {
+    (config.pulse.is_some() && !clock.is_paused()) || refresh.elapsed().as_millis() < 1000
+}
+
+struct Rendered {
+    cache: RenderCache,
+    buffer: Buffer<U8Vec3>,
+}
+
+// A full resolution render on a background thread, which can take seconds for a large
+// window, so the event loop keeps running and the window stays responsive meanwhile
+struct FullRender {
+    config: Config,
+    receiver: Receiver<Rendered>,
+}
+
+impl FullRender {
+    fn spawn(mut cache: RenderCache, config: &Config, width: usize, height: usize) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let job_config = config.clone();
+        thread::spawn(move || {
+            let mut buffer = Buffer::new(width, height, U8Vec3::ZERO);
+            cache.render(&mut buffer, &job_config);
+            let _ = sender.send(Rendered { cache, buffer });
+        });
+        Self {
+            config: config.clone(),
+            receiver,
+        }
+    }
+
+    // The config rendered and the result, once the render is done
+    fn finished(&self) -> Option<(Config, Rendered)> {
+        let rendered = self.receiver.try_recv().ok()?;
+        Some((self.config.clone(), rendered))
+    }
+
+    fn wait(self) -> Option<(Config, Rendered)> {
+        let rendered = self.receiver.recv().ok()?;
+        Some((self.config, rendered))
+    }
+}
+
+// Opens the interactive viewer. If the window can't be created, such as over SSH or in
+// a container, this warns and renders headlessly instead unless `fallback` is false.
+pub fn view(
+    config: &Config,
+    width: usize,
+    height: usize,
+    window_scale: WindowScale,
+    fallback: bool,
+    live: bool,
+) -> Result<(), WorleyError> {
+    let mut window = match Window::new(&title(config, false), width, height, window_scale.options())
+    {
+        Ok(window) => window,
+        Err(e) if fallback => {
+            eprintln!("warning: can't open a window ({e}), rendering headlessly");
+            return render_headless(config, width, height);
+        }
+        Err(e) => return Err(WorleyError::Window(e.to_string())),
+    };
+
+    let mut config = config.clone();
+    let lines = live.then(read_stdin_lines);
+    let mut buffer = Buffer::new(width, height, U8Vec3::ZERO);
+    let mut scale = AdaptiveScale::new();
+    // Set once the view stops changing and a full resolution frame has been rendered
+    let mut settled = false;
+    // The window's copy of `buffer`, only repacked when a render has changed it
+    let mut packed = Vec::with_capacity(width * height);
+    let mut dirty = true;
+
+    window.set_target_fps(240);
+    let mut clock = AnimationClock::new(Instant::now());
+    let mut refresh = Instant::now();
+
+    // Settled renders keep their geometry, which the histogram is binned from
+    let mut cache = RenderCache::new();
+    // The full resolution render in progress, which holds the cache until it finishes
+    let mut job: Option<FullRender> = None;
+    let mut histogram: Option<Histogram> = None;
+    let mut show_histogram = false;
+    let mut show_ruler = false;
+    // The color mode to go back to when the cell hash view is toggled off
+    let mut hidden_mode = None;
+    // The mask shown over a checkerboard, and its alpha for the settled render
+    let mut alpha_preview: Option<AlphaSource> = None;
+    let mut alpha: Option<Buffer<u8>> = None;
+
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        // Changes go through the fast low resolution renders again before settling
+        if lines
+            .as_ref()
+            .is_some_and(|lines| apply_stdin(&mut config, lines))
+        {
+            refresh = Instant::now();
+            settled = false;
+        }
+
+        // The config at the animation's current time, which is what gets rendered
+        let frame = config.at_time(clock.time(Instant::now()).as_secs_f32());
+        if refreshing(&config, refresh, &clock) {
+            let start = Instant::now();
+            let (w, h) = scale.size(width, height);
+            let mut low = Buffer::new(w, h, U8Vec3::ZERO);
+            render_scaled(&mut low, &frame, w as f32 / width as f32);
+            low.scale_into(&mut buffer);
+            scale.update(start.elapsed());
+            dirty = true;
+        } else if !settled && job.is_none() {
+            job = Some(FullRender::spawn(
+                std::mem::take(&mut cache),
+                &frame,
+                width,
+                height,
+            ));
+        }
+
+        if let Some((finished, rendered)) = job.as_ref().and_then(FullRender::finished) {
+            cache = rendered.cache;
+            // Renders overtaken by a change or by low resolution frames are dropped
+            if finished == frame && !refreshing(&config, refresh, &clock) {
+                buffer = rendered.buffer;
+                alpha = None;
+                histogram = cache
+                    .geometry()
+                    .map(|field| Histogram::from_field(field, &frame, HISTOGRAM_BINS));
+                if show_histogram {
+                    print_histogram(histogram.as_ref());
+                }
+                settled = true;
+                dirty = true;
+            }
+            job = None;
+        }
+
+        // Holds the animation on the current frame, which then settles at full resolution
+        // for saving, and resumes from the same time
+        if window.is_key_pressed(Key::Space, KeyRepeat::No) {
+            clock.toggle_pause(Instant::now());
+            window.set_title(&title(&config, clock.is_paused()));
+            settled = false;
+        }
+        if window.is_key_pressed(Key::S, KeyRepeat::No) {
+            try_save(&buffer, Path::new("output.png"));
+        }
+        // Colors alone change, so the cached geometry is recolored without a refresh
+        if window.is_key_pressed(Key::C, KeyRepeat::No) {
+            let color_seed = random();
+            config.color_seed = Some(color_seed);
+            println!("--color-seed {color_seed}");
+            settled = false;
+        }
+        if window.is_key_pressed(Key::P, KeyRepeat::No) {
+            // A palette loaded from a file starts the cycle from the first built-in
+            let next = BuiltinPalette::find(&config.palette)
+                .map_or(BuiltinPalette::ALL[0], BuiltinPalette::next);
+            config.palette = next.palette();
+            window.set_title(&title(&config, clock.is_paused()));
+            settled = false;
+        }
+        if window.is_key_pressed(Key::H, KeyRepeat::No) {
+            show_histogram = !show_histogram;
+            if show_histogram {
+                print_histogram(histogram.as_ref());
+            }
+            dirty = true;
+        }
+
+        if window.is_key_pressed(Key::R, KeyRepeat::No) {
+            show_ruler = !show_ruler;
+            dirty = true;
+        }
+        // Cycles through the alpha sources and back to the plain render
+        if window.is_key_pressed(Key::A, KeyRepeat::No) {
+            alpha_preview = match alpha_preview.map(AlphaSource::next) {
+                None => Some(AlphaSource::ALL[0]),
+                Some(source) if source == AlphaSource::ALL[0] => None,
+                next => next,
+            };
+            alpha = None;
+            dirty = true;
+        }
+        // A color mode, so the cached geometry is recolored without a refresh
+        if window.is_key_pressed(Key::X, KeyRepeat::No) {
+            config.color_mode = if config.color_mode == ColorMode::CellHash {
+                hidden_mode.take().unwrap_or_default()
+            } else {
+                hidden_mode = Some(config.color_mode);
+                ColorMode::CellHash
+            };
+            settled = false;
+        }
+
+        if dirty {
+            let histogram = histogram.as_ref().filter(|_| show_histogram);
+            let hash_view = config.color_mode == ColorMode::CellHash;
+            // Only settled renders are masked, as the mask is sampled at full resolution
+            let alpha_view = alpha_preview.filter(|_| settled);
+            if histogram.is_some() || show_ruler || hash_view || alpha_view.is_some() {
+                // Drawn over a copy so saving still writes the plain render
+                let mut shown = match alpha_view {
+                    Some(source) => {
+                        let alpha = alpha.get_or_insert_with(|| {
+                            let mut alpha = Buffer::new(width, height, 0);
+                            render_alpha(&mut alpha, &frame, IVec2::ZERO, source);
+                            alpha
+                        });
+                        buffer.with_alpha(alpha).over_checkerboard()
+                    }
+                    None => buffer.clone(),
+                };
+                let mut corner = USizeVec2::splat(8);
+                // Straight rulers can't follow world space turned under the view
+                if show_ruler && frame.rotation != 0.0 {
+                    let size = text_size(ROTATED_RULER_LABEL, 2);
+                    let corner = USizeVec2::new(width.saturating_sub(size.x + 8), 8);
+                    draw_text(
+                        &mut shown,
+                        corner,
+                        ROTATED_RULER_LABEL,
+                        U8Vec3::splat(255),
+                        2,
+                    );
+                } else if show_ruler {
+                    // The view shows world space one to one from the origin, with the
+                    // cells of the frame on screen, which a pulse grows and shrinks
+                    draw_ruler(&mut shown, Vec2::ZERO, 1.0, frame.noise.cells);
+                    corner += RULER_SIZE;
+                }
+                if let Some(histogram) = histogram {
+                    let size = USizeVec2::new(width / 3, height / 4).max(USizeVec2::ONE);
+                    histogram.draw(&mut shown, corner, size);
+                }
+                if hash_view {
+                    let size = text_size(HASH_VIEW_LABEL, 2);
+                    let corner = USizeVec2::new(8, height.saturating_sub(size.y + 8));
+                    draw_text(&mut shown, corner, HASH_VIEW_LABEL, U8Vec3::splat(255), 2);
+                }
+                if let Some(source) = alpha_view {
+                    let label = format!("ALPHA {}", source.name());
+                    let size = text_size(&label, 2);
+                    let corner = USizeVec2::new(width, height).saturating_sub(size + 8);
+                    draw_text(&mut shown, corner, &label, U8Vec3::splat(255), 2);
+                }
+                shown.to_minifb_buffer(&mut packed);
+            } else {
+                buffer.to_minifb_buffer(&mut packed);
+            }
+            dirty = false;
+        }
+        window
+            .update_with_buffer(&packed, width, height)
+            .map_err(|e| WorleyError::Window(e.to_string()))?;
+    }
+
+    if !settled {
+        let frame = config.at_time(clock.time(Instant::now()).as_secs_f32());
+        match job.and_then(|job| job.wait().filter(|(finished, _)| *finished == frame)) {
+            Some((_, rendered)) => buffer = rendered.buffer,
+            None => render(&mut buffer, &frame),
+        }
+    }
+    buffer.save("output.png")?;
+    println!("Saved output.png");
+    Ok(())
+}
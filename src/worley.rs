@@ -0,0 +1,1556 @@
+use glam::{DVec2, IVec2, Mat2, Vec2};
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+use crate::{
+    Named,
+    profile::{self, Stage},
+};
+#[cfg(feature = "std")]
+use crate::{WorleyError, error::Result};
+
+// Identifies a cell of the coarsest worley level, usable as a map key
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CellId(pub IVec2);
+
+impl From<IVec2> for CellId {
+    fn from(cell: IVec2) -> Self {
+        Self(cell)
+    }
+}
+
+impl From<CellId> for IVec2 {
+    fn from(cell: CellId) -> Self {
+        cell.0
+    }
+}
+
+// A hierarchical worley sampler over positions of type `V`, for code that works in
+// either 2D or 3D
+pub trait Worley<V> {
+    // Identifies the coarsest level cell a sample lands in
+    type Cell: Copy + PartialEq;
+
+    fn sample(&self, pos: V) -> (Self::Cell, f32);
+}
+
+impl Worley<Vec2> for WorleyNoise {
+    type Cell = CellId;
+
+    fn sample(&self, pos: Vec2) -> (CellId, f32) {
+        WorleyNoise::sample(self, pos)
+    }
+}
+
+// Hashes the seed + cell coordinate
+pub fn cell_hash(cell: IVec2, seed: u64) -> u64 {
+    let mut x = (cell.x as i64 as u64).wrapping_mul(0xa0761d6478bd642f);
+    let mut y = (cell.y as i64 as u64).wrapping_mul(0xe7037ed1a0b428db);
+    let mut s = seed.wrapping_mul(0x8ebc6af09c88c6e3);
+    x ^= y.rotate_left(25);
+    y ^= s.rotate_left(47);
+    s ^= x.rotate_left(17);
+    s ^ y
+}
+
+// Splitmix64's finalizer. Every input bit flips each output bit with probability 1/2.
+pub fn mix64(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+// How cell coordinates and the seed are mixed into a cell's hash. `cell_hash` alone
+// avalanches poorly for high input bits: measured over random cells, flipping bit 31 of
+// x or y flips only ~26% of the output bits, and flipping seed bit 63 only ~3%, against
+// the ideal 50%. Neighboring cells look random enough in practice, so it stays the
+// default to keep existing seeds rendering the same.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HashMixer {
+    #[default]
+    Legacy,
+    // `cell_hash` passed through `mix64`, measured at 50% +- 1% for every input bit
+    SplitMix,
+}
+
+impl HashMixer {
+    pub fn hash(self, cell: IVec2, seed: u64) -> u64 {
+        match self {
+            HashMixer::Legacy => cell_hash(cell, seed),
+            HashMixer::SplitMix => mix64(cell_hash(cell, seed)),
+        }
+    }
+}
+
+impl Named for HashMixer {
+    const KIND: &'static str = "hash mixer";
+    const ALL: &'static [Self] = &[HashMixer::Legacy, HashMixer::SplitMix];
+
+    fn name(self) -> &'static str {
+        match self {
+            HashMixer::Legacy => "legacy",
+            HashMixer::SplitMix => "splitmix",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            HashMixer::Legacy => "the original hash, weak avalanche on high bits",
+            HashMixer::SplitMix => "the original hash through a splitmix64 finalizer",
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::str::FromStr for HashMixer {
+    type Err = WorleyError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        crate::named::parse_named(s)
+    }
+}
+
+// A seed from memorable text. FNV-1a over the UTF-8 bytes, finished with `mix64`, so the
+// same text gives the same seed on every platform and Rust version.
+pub fn seed_from_str(text: &str) -> u64 {
+    let fnv = text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    mix64(fnv)
+}
+
+// Get the center of a worley cell, ZERO to ONE
+pub fn worley_center(cell: IVec2, seed: u64) -> Vec2 {
+    center_from_hash(cell_hash(cell, seed))
+}
+
+// A cell's image within a grid that repeats every `period` cells
+fn wrap(cell: IVec2, period: Option<IVec2>) -> IVec2 {
+    period.map_or(cell, |p| cell.rem_euclid(p))
+}
+
+// The bits of a cell's hash that place its feature point along x and y
+pub(crate) fn center_bits(hash: u64) -> (u32, u32) {
+    ((hash >> 12) as u32, (hash >> 32) as u32)
+}
+
+fn center_from_hash(hash: u64) -> Vec2 {
+    let (bits1, bits2) = center_bits(hash);
+    let x = (bits1 as f32) / (u32::MAX as f32);
+    let y = (bits2 as f32) / (u32::MAX as f32);
+    (x, y).into()
+}
+
+pub fn worley(sample_pos: Vec2, cell_size: Vec2, seed: u64) -> (IVec2, f32) {
+    WorleyNoise {
+        seed,
+        ..Default::default()
+    }
+    .worley(sample_pos, cell_size)
+}
+
+pub fn k_nearest(sample_pos: Vec2, cell_size: Vec2, seed: u64, k: usize) -> KNearest {
+    WorleyNoise {
+        seed,
+        ..Default::default()
+    }
+    .k_nearest(sample_pos, cell_size, k)
+}
+
+pub fn edge_distance(sample_pos: Vec2, cell_size: Vec2, seed: u64) -> f32 {
+    WorleyNoise {
+        seed,
+        ..Default::default()
+    }
+    .edge_distance(sample_pos, cell_size)
+}
+
+pub fn cell_info(cell: IVec2, cell_size: Vec2, seed: u64) -> CellInfo {
+    WorleyNoise {
+        seed,
+        ..Default::default()
+    }
+    .cell_info(cell, cell_size)
+}
+
+pub fn hierarchical_worley(
+    sample_pos: Vec2,
+    cell_size: Vec2,
+    seed: u64,
+    depth: usize,
+    growth: f32,
+) -> (CellId, f32) {
+    WorleyNoise {
+        seed,
+        depth,
+        growth: Vec2::splat(growth),
+        cells: cell_size,
+        ..Default::default()
+    }
+    .sample(sample_pos)
+}
+
+// How the distance from a sample to a feature point is measured
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DistanceMetric {
+    #[default]
+    Euclidean,
+    Manhattan,
+    Chebyshev,
+}
+
+impl DistanceMetric {
+    pub fn distance(self, delta: Vec2) -> f32 {
+        match self {
+            DistanceMetric::Euclidean => delta.length(),
+            DistanceMetric::Manhattan => delta.abs().element_sum(),
+            DistanceMetric::Chebyshev => delta.abs().max_element(),
+        }
+    }
+
+    pub fn distance_f64(self, delta: DVec2) -> f64 {
+        match self {
+            DistanceMetric::Euclidean => delta.length(),
+            DistanceMetric::Manhattan => delta.abs().element_sum(),
+            DistanceMetric::Chebyshev => delta.abs().max_element(),
+        }
+    }
+}
+
+impl Named for DistanceMetric {
+    const KIND: &'static str = "distance metric";
+    const ALL: &'static [Self] = &[
+        DistanceMetric::Euclidean,
+        DistanceMetric::Manhattan,
+        DistanceMetric::Chebyshev,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            DistanceMetric::Euclidean => "euclidean",
+            DistanceMetric::Manhattan => "manhattan",
+            DistanceMetric::Chebyshev => "chebyshev",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            DistanceMetric::Euclidean => "straight line distance, round cells",
+            DistanceMetric::Manhattan => "sum of the axis distances, diamond shaped cells",
+            DistanceMetric::Chebyshev => "largest axis distance, square cells",
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::str::FromStr for DistanceMetric {
+    type Err = WorleyError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        crate::named::parse_named(s)
+    }
+}
+
+// Which way `WorleyNoise::sample_flow` points relative to the nearest feature point
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FlowMode {
+    #[default]
+    Toward,
+    Swirl,
+}
+
+impl Named for FlowMode {
+    const KIND: &'static str = "flow mode";
+    const ALL: &'static [Self] = &[FlowMode::Toward, FlowMode::Swirl];
+
+    fn name(self) -> &'static str {
+        match self {
+            FlowMode::Toward => "toward",
+            FlowMode::Swirl => "swirl",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            FlowMode::Toward => "points at the nearest feature point, draining into cells",
+            FlowMode::Swirl => "circles around the nearest feature point",
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::str::FromStr for FlowMode {
+    type Err = WorleyError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        crate::named::parse_named(s)
+    }
+}
+
+// Inverse distance weighting of the `k` nearest cells' values, each weighted by
+// distance^-power
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Idw {
+    pub k: usize,
+    pub power: f32,
+}
+
+impl Default for Idw {
+    fn default() -> Self {
+        Self { k: 4, power: 2.0 }
+    }
+}
+
+// How many feature points each level's cells hold, coarsest level first. Kept inline
+// rather than in a `Vec` so `WorleyNoise` stays `Copy` and allocation free, which caps
+// the list at `MAX_LEVELS` levels of up to 255 points. Empty means one point everywhere.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PointsPerLevel {
+    counts: [u8; PointsPerLevel::MAX_LEVELS],
+    len: u8,
+}
+
+impl PointsPerLevel {
+    pub const MAX_LEVELS: usize = 16;
+
+    // None if there are more than `MAX_LEVELS` counts or any is outside 1..=255
+    pub fn new(counts: &[usize]) -> Option<Self> {
+        if counts.len() > Self::MAX_LEVELS {
+            return None;
+        }
+        let mut points = Self::default();
+        for (slot, &count) in points.counts.iter_mut().zip(counts) {
+            *slot = u8::try_from(count).ok().filter(|&c| c > 0)?;
+        }
+        points.len = counts.len() as u8;
+        Some(points)
+    }
+
+    // The points per cell at `level`, 1 past the end of the list
+    pub fn get(&self, level: usize) -> usize {
+        self.counts().get(level).map_or(1, |&c| c as usize)
+    }
+
+    pub fn counts(&self) -> &[u8] {
+        &self.counts[..self.len as usize]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl core::fmt::Debug for PointsPerLevel {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_list().entries(self.counts()).finish()
+    }
+}
+
+// Saved as a plain list of counts
+impl Serialize for PointsPerLevel {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.counts())
+    }
+}
+
+impl<'de> Deserialize<'de> for PointsPerLevel {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Counts;
+
+        impl<'de> serde::de::Visitor<'de> for Counts {
+            type Value = PointsPerLevel;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                write!(
+                    f,
+                    "up to {} point counts from 1 to 255",
+                    PointsPerLevel::MAX_LEVELS
+                )
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<PointsPerLevel, A::Error> {
+                use serde::de::{Error, Unexpected};
+
+                let mut points = PointsPerLevel::default();
+                while let Some(count) = seq.next_element::<u8>()? {
+                    let len = points.len as usize;
+                    if count == 0 {
+                        return Err(Error::invalid_value(Unexpected::Unsigned(0), &self));
+                    }
+                    let Some(slot) = points.counts.get_mut(len) else {
+                        return Err(Error::invalid_length(len + 1, &self));
+                    };
+                    *slot = count;
+                    points.len += 1;
+                }
+                Ok(points)
+            }
+        }
+
+        deserializer.deserialize_seq(Counts)
+    }
+}
+
+// Parses counts separated by commas, such as `1,1,2,4`
+#[cfg(feature = "std")]
+impl core::str::FromStr for PointsPerLevel {
+    type Err = WorleyError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            WorleyError::invalid(
+                "points_per_level",
+                format!(
+                    "'{s}' must be up to {} counts from 1 to 255",
+                    Self::MAX_LEVELS
+                ),
+            )
+        };
+        let counts = s
+            .split(',')
+            .map(|c| c.trim().parse().map_err(|_| invalid()))
+            .collect::<Result<Vec<usize>>>()?;
+        Self::new(&counts).ok_or_else(invalid)
+    }
+}
+
+// The distance metric of each level, coarsest first, kept inline like `PointsPerLevel`
+// and capped at `MAX_LEVELS` levels. Empty means `WorleyNoise::metric` everywhere.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct MetricsPerLevel {
+    metrics: [DistanceMetric; MetricsPerLevel::MAX_LEVELS],
+    len: u8,
+}
+
+impl MetricsPerLevel {
+    pub const MAX_LEVELS: usize = PointsPerLevel::MAX_LEVELS;
+
+    // None if there are more than `MAX_LEVELS` metrics
+    pub fn new(metrics: &[DistanceMetric]) -> Option<Self> {
+        if metrics.len() > Self::MAX_LEVELS {
+            return None;
+        }
+        let mut list = Self::default();
+        list.metrics[..metrics.len()].copy_from_slice(metrics);
+        list.len = metrics.len() as u8;
+        Some(list)
+    }
+
+    // The metric at `level`, or None past the end of the list
+    pub fn get(&self, level: usize) -> Option<DistanceMetric> {
+        self.metrics().get(level).copied()
+    }
+
+    pub fn metrics(&self) -> &[DistanceMetric] {
+        &self.metrics[..self.len as usize]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl core::fmt::Debug for MetricsPerLevel {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_list().entries(self.metrics()).finish()
+    }
+}
+
+// Saved as a plain list of metrics
+impl Serialize for MetricsPerLevel {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.metrics())
+    }
+}
+
+impl<'de> Deserialize<'de> for MetricsPerLevel {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Metrics;
+
+        impl<'de> serde::de::Visitor<'de> for Metrics {
+            type Value = MetricsPerLevel;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                write!(f, "up to {} metrics", MetricsPerLevel::MAX_LEVELS)
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<MetricsPerLevel, A::Error> {
+                let mut list = MetricsPerLevel::default();
+                while let Some(metric) = seq.next_element()? {
+                    let len = list.len as usize;
+                    let Some(slot) = list.metrics.get_mut(len) else {
+                        return Err(serde::de::Error::invalid_length(len + 1, &self));
+                    };
+                    *slot = metric;
+                    list.len += 1;
+                }
+                Ok(list)
+            }
+        }
+
+        deserializer.deserialize_seq(Metrics)
+    }
+}
+
+// Parses metric names separated by commas, such as `manhattan,euclidean`
+#[cfg(feature = "std")]
+impl core::str::FromStr for MetricsPerLevel {
+    type Err = WorleyError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let metrics = s
+            .split(',')
+            .map(crate::named::parse_named)
+            .collect::<Result<Vec<DistanceMetric>>>()?;
+        Self::new(&metrics).ok_or_else(|| {
+            WorleyError::invalid(
+                "metrics_per_level",
+                format!("'{s}' lists more than {} levels", Self::MAX_LEVELS),
+            )
+        })
+    }
+}
+
+// Growth was a single factor for both axes before it became a pair, so files saved
+// back then hold a plain number, which still loads as that factor on both axes
+fn deserialize_growth<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec2, D::Error> {
+    struct Growth;
+
+    impl<'de> serde::de::Visitor<'de> for Growth {
+        type Value = Vec2;
+
+        fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            write!(f, "a growth factor or an [x, y] pair of them")
+        }
+
+        fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Vec2, E> {
+            Ok(Vec2::splat(v as f32))
+        }
+
+        fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Vec2, E> {
+            Ok(Vec2::splat(v as f32))
+        }
+
+        fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Vec2, E> {
+            Ok(Vec2::splat(v as f32))
+        }
+
+        fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Vec2, A::Error> {
+            use serde::de::Error;
+
+            let x = seq
+                .next_element()?
+                .ok_or_else(|| Error::invalid_length(0, &self))?;
+            let y = seq
+                .next_element()?
+                .ok_or_else(|| Error::invalid_length(1, &self))?;
+            if seq.next_element::<f32>()?.is_some() {
+                return Err(Error::invalid_length(3, &self));
+            }
+            Ok(Vec2::new(x, y))
+        }
+    }
+
+    deserializer.deserialize_any(Growth)
+}
+
+// The parameters of a single hierarchical worley field
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorleyNoise {
+    pub seed: u64,
+    pub depth: usize,
+    // How much smaller each level's cells are than the level above, per axis. Unequal
+    // components stretch the finer levels' detail along the slower shrinking axis.
+    #[serde(deserialize_with = "deserialize_growth")]
+    pub growth: Vec2,
+    pub cells: Vec2,
+    // Approximate Lloyd relaxation passes applied to each feature point. Each pass only
+    // considers the surrounding 3x3 block of cells, so this is not true Lloyd relaxation
+    // and any value above 0 produces a different layout for the same seed.
+    pub relaxation_iterations: usize,
+    // Pushes feature points at least this far apart, as a fraction of the cell size,
+    // approximating a Poisson disk distribution. 0 turns it off; anything else changes
+    // the layout for the same seed.
+    pub min_spacing: f32,
+    // Measures distances on a torus of this size so the pattern repeats seamlessly.
+    // Each level's cell size is snapped so a whole number of cells fits the domain.
+    pub toroidal_domain: Option<Vec2>,
+    pub metric: DistanceMetric,
+    pub hash: HashMixer,
+    // The level whose raw distance the blend starts from, numbered like `level_weight`.
+    // Finer levels still pick the cells but add nothing to the distance. None keeps the
+    // finest level as the base case, contributing 0.
+    pub base_level: Option<usize>,
+    // Moves every feature point around a small circle, for looping animations
+    pub orbit: Option<Orbit>,
+    // Feature points per cell at each level, for denser detail at chosen levels. A cell
+    // is as near as its nearest point, and cell ids, colors and `center` still belong to
+    // the cell. Only a cell's first point is relaxed or spaced; the rest are placed
+    // straight from its hash. Every level still searches its 3x3 block of cells, so a
+    // level costs in proportion to its points: eight points per cell at every level is
+    // about eight times the work of one.
+    pub points_per_level: PointsPerLevel,
+    // The metric of each level, overriding `metric` for mixed textures, such as
+    // Manhattan diamonds at the coarse levels under round Euclidean detail. Walls between
+    // cells follow each level's own metric.
+    pub metrics_per_level: MetricsPerLevel,
+    // How elongated cells can get. Each cell measures distances through its own 2x2
+    // matrix, stretching it by a random factor of up to `1 + anisotropy_strength` along a
+    // random axis from its hash and squeezing it as much across, for a less uniform,
+    // organic look. 0 turns it off. Each level still searches only its 3x3 block, so
+    // strongly stretched cells are cut off where they would reach past it.
+    pub anisotropy_strength: f32,
+}
+
+impl Default for WorleyNoise {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            depth: 8,
+            growth: Vec2::splat(3.0),
+            cells: Vec2::new(256.0, 256.0),
+            relaxation_iterations: 0,
+            min_spacing: 0.0,
+            toroidal_domain: None,
+            metric: DistanceMetric::Euclidean,
+            hash: HashMixer::Legacy,
+            base_level: None,
+            orbit: None,
+            points_per_level: PointsPerLevel::default(),
+            metrics_per_level: MetricsPerLevel::default(),
+            anisotropy_strength: 0.0,
+        }
+    }
+}
+
+// Each feature point circles its resting place with `radius`, in cells, starting from a
+// per-cell angle. Resting places are pulled toward the cell's middle to make room, so the
+// point never leaves its cell and the 3x3 search stays valid.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Orbit {
+    // 0 to 0.5
+    pub radius: f32,
+    // Radians, shared by every cell
+    pub phase: f32,
+}
+
+impl Orbit {
+    // The orbit `frame` frames into a loop of `frames`. Frame `frames` wraps around to
+    // exactly frame 0, so the last frame leads seamlessly back into the first.
+    pub fn at_frame(radius: f32, frame: usize, frames: usize) -> Self {
+        let t = (frame % frames.max(1)) as f32 / frames.max(1) as f32;
+        Self {
+            radius,
+            phase: t * core::f32::consts::TAU,
+        }
+    }
+
+    fn apply(self, center: Vec2, hash: u64) -> Vec2 {
+        // Independent of the bits that placed the point
+        let start = (mix64(hash) >> 40) as f32 / (1u64 << 24) as f32 * core::f32::consts::TAU;
+        let rest = Vec2::splat(0.5) + (center - 0.5) * (1.0 - 2.0 * self.radius);
+        let point = rest + self.radius * Vec2::from_angle(self.phase + start);
+        point.clamp(Vec2::ZERO, Vec2::splat(1.0 - f32::EPSILON))
+    }
+}
+
+pub type KNearest = SmallVec<[(IVec2, f32); 4]>;
+
+// Where a cell sits in world space, for placing labels and overlays
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CellInfo {
+    // The cell's feature point
+    pub center: Vec2,
+    // Min and max corners around the midpoints to the 8 neighboring feature points. Real
+    // cells are irregular polygons whose corners can reach past these midpoints, so this
+    // is only an approximation of the cell's extent.
+    pub bounds: (Vec2, Vec2),
+}
+
+// A single level's grid, prepared for measuring distances from one sample position
+struct Grid {
+    sample_pos: Vec2,
+    cell_size: Vec2,
+    base_cell: IVec2,
+    // Cells per domain when toroidal
+    period: Option<IVec2>,
+    // Feature points in each cell
+    points: usize,
+    metric: DistanceMetric,
+}
+
+// Separates the hashes of a cell's extra feature points, an odd constant from the digits
+// of pi
+const EXTRA_POINT_SALT: u64 = 0x243f_6a88_85a3_08d3;
+
+// Separates a cell's anisotropy from its feature points, the next digits of pi
+const ANISOTROPY_SALT: u64 = 0x1319_8a2e_0370_7344;
+
+// Samples per cell along each axis when estimating a relaxed cell's centroid
+const RELAX_SAMPLES: i32 = 6;
+
+// Candidate points a cell tries before giving up on its minimum spacing
+const SPACING_ATTEMPTS: u64 = 8;
+
+// Cell, toroidal period, seed, hash mixer, minimum spacing bits and iteration count of a
+// relaxed center
+type RelaxKey = (IVec2, Option<IVec2>, u64, HashMixer, u32, usize);
+
+#[cfg(feature = "std")]
+mod relax_cache {
+    use std::{cell::RefCell, collections::HashMap};
+
+    use glam::Vec2;
+
+    use super::RelaxKey;
+
+    // Relaxed centers cached per thread before the cache is cleared
+    const LIMIT: usize = 1 << 16;
+
+    thread_local! {
+        static CACHE: RefCell<HashMap<RelaxKey, Vec2>> = RefCell::new(HashMap::new());
+    }
+
+    pub fn get(key: RelaxKey) -> Option<Vec2> {
+        CACHE.with_borrow(|cache| cache.get(&key).copied())
+    }
+
+    pub fn insert(key: RelaxKey, center: Vec2) {
+        CACHE.with_borrow_mut(|cache| {
+            if cache.len() >= LIMIT {
+                cache.clear();
+            }
+            cache.insert(key, center);
+        });
+    }
+}
+
+// Without std there is no thread local storage, so relaxed centers are recomputed
+#[cfg(not(feature = "std"))]
+mod relax_cache {
+    use glam::Vec2;
+
+    use super::RelaxKey;
+
+    pub fn get(_key: RelaxKey) -> Option<Vec2> {
+        None
+    }
+
+    pub fn insert(_key: RelaxKey, _center: Vec2) {}
+}
+
+impl WorleyNoise {
+    #[cfg(feature = "std")]
+    pub fn validate(&self) -> Result<()> {
+        let positive = |v: Vec2| v.is_finite() && v.cmpgt(Vec2::ZERO).all();
+        if !positive(self.cells) {
+            return Err(WorleyError::invalid("cells", "must be positive"));
+        }
+        if !positive(self.growth) {
+            return Err(WorleyError::invalid("growth", "must be positive"));
+        }
+        if self.toroidal_domain.is_some_and(|d| !positive(d)) {
+            return Err(WorleyError::invalid("toroidal_domain", "must be positive"));
+        }
+        if !(0.0..1.0).contains(&self.min_spacing) {
+            return Err(WorleyError::invalid("min_spacing", "must be in 0..1"));
+        }
+        if self.base_level.is_some_and(|level| level > self.depth) {
+            return Err(WorleyError::invalid(
+                "base_level",
+                format!("must not exceed depth {}", self.depth),
+            ));
+        }
+        if let Some(orbit) = self.orbit {
+            if !(0.0..=0.5).contains(&orbit.radius) {
+                return Err(WorleyError::invalid("orbit radius", "must be in 0..=0.5"));
+            }
+            if !orbit.phase.is_finite() {
+                return Err(WorleyError::invalid("orbit phase", "must be finite"));
+            }
+        }
+        let levels = self.points_per_level.counts().len();
+        if levels != 0 && levels != self.depth + 1 {
+            return Err(WorleyError::invalid(
+                "points_per_level",
+                format!(
+                    "lists {levels} levels, but depth {} has {}",
+                    self.depth,
+                    self.depth + 1
+                ),
+            ));
+        }
+        if !(self.anisotropy_strength.is_finite() && self.anisotropy_strength >= 0.0) {
+            return Err(WorleyError::invalid(
+                "anisotropy_strength",
+                "must be 0 or more",
+            ));
+        }
+        let levels = self.metrics_per_level.metrics().len();
+        if levels != 0 && levels != self.depth + 1 {
+            return Err(WorleyError::invalid(
+                "metrics_per_level",
+                format!(
+                    "lists {levels} levels, but depth {} has {}",
+                    self.depth,
+                    self.depth + 1
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    // The metric hierarchy level `level` measures with
+    pub fn metric_at(&self, level: usize) -> DistanceMetric {
+        self.metrics_per_level.get(level).unwrap_or(self.metric)
+    }
+
+    pub fn sample(&self, pos: Vec2) -> (CellId, f32) {
+        self.sample_level(pos, self.cells, self.depth, &mut |_, _| {})
+    }
+
+    // Samples while reporting each level's raw nearest distance to `on_level`, finest
+    // first. Levels are numbered from 0 at the coarsest to `depth` at the finest, and
+    // `level_weight` gives each one's share of the returned distance.
+    pub fn sample_traced(&self, pos: Vec2, mut on_level: impl FnMut(usize, f32)) -> (CellId, f32) {
+        self.sample_level(pos, self.cells, self.depth, &mut on_level)
+    }
+
+    // How much a level's raw distance counts towards the sampled distance. Each coarser
+    // level takes a quarter of the blend, and the base level takes the rest. Without a
+    // `base_level` the finest level is the base case and always contributes 0.
+    pub fn level_weight(&self, level: usize) -> f32 {
+        let anchored = self.base_level == Some(level);
+        if level > self.base_level.unwrap_or(self.depth) || (level >= self.depth && !anchored) {
+            return 0.0;
+        }
+        let mut weight = if anchored { 1.0 } else { 0.25 };
+        for _ in 0..level {
+            weight *= 0.75;
+        }
+        weight
+    }
+
+    // The level whose weighted distance makes up the largest share of the sample
+    pub fn dominant_level(&self, pos: Vec2) -> usize {
+        let (mut best_level, mut best) = (self.depth, 0.0);
+        self.sample_traced(pos, |level, dist| {
+            let contribution = dist * self.level_weight(level);
+            if contribution > best {
+                (best_level, best) = (level, contribution);
+            }
+        });
+        best_level
+    }
+
+    // Samples and attaches metadata for the winning cell, such as a biome picked from
+    // `cell_hash`. `meta` should be a pure function of the cell so results stay
+    // deterministic and independent of sampling order.
+    pub fn sample_with<M>(&self, pos: Vec2, meta: impl Fn(CellId) -> M) -> (CellId, f32, M) {
+        let (cell, dist) = self.sample(pos);
+        (cell, dist, meta(cell))
+    }
+
+    // The deepest depth, up to `depth`, whose finest cells are still at least
+    // `min_cell_size` across along their narrower axis. Stops early once cells shrink to
+    // nothing or grow without bound, so it stays cheap for absurd depths.
+    pub fn depth_for_min_cell_size(&self, min_cell_size: f32) -> usize {
+        let mut cell_size = self.cells;
+        for depth in 0..self.depth {
+            cell_size /= self.growth;
+            let smallest = cell_size.min_element();
+            if !(smallest >= min_cell_size && smallest > 0.0 && cell_size.is_finite()) {
+                return depth;
+            }
+        }
+        self.depth
+    }
+
+    // Distances never exceed the coarsest cell's diagonal, so this maps them into 0..=1
+    pub fn sample_normalized(&self, pos: Vec2) -> (CellId, f32) {
+        let (cell, dist) = self.sample(pos);
+        (cell, (dist / self.cells.length()).clamp(0.0, 1.0))
+    }
+
+    // The nearest and second nearest coarse cells, with their distances blended through
+    // the hierarchy the same way as `sample`. The first entry always matches `sample`,
+    // so at depth 0 it is 0 and the second holds the gap between the two raw distances.
+    // With the coarsest level as `base_level` both are raw distances.
+    pub fn sample_f2(&self, pos: Vec2) -> [(CellId, f32); 2] {
+        let cell_size = self.snap_cell_size(self.cells);
+        let (sample_pos, dist) = if self.depth == 0 {
+            (pos, None)
+        } else {
+            let finer_cell_size = self.snap_cell_size(cell_size / self.growth);
+            let (cell, dist) =
+                self.sample_level(pos, finer_cell_size, self.depth - 1, &mut |_, _| {});
+            (cell.0.as_vec2() * finer_cell_size, Some(dist))
+        };
+
+        let near = self.k_nearest(sample_pos, cell_size, 2);
+        // A toroidal domain one cell wide has no second cell, so it sits infinitely far
+        let first = near[0];
+        let second = near.get(1).copied().unwrap_or((first.0, f32::INFINITY));
+        if self.base_level == Some(0) {
+            return [(first.0.into(), first.1), (second.0.into(), second.1)];
+        }
+        match dist {
+            Some(dist) => [
+                (first.0.into(), first.1 * 0.25 + dist * 0.75),
+                (second.0.into(), second.1 * 0.25 + dist * 0.75),
+            ],
+            None => [(first.0.into(), 0.0), (second.0.into(), second.1 - first.1)],
+        }
+    }
+
+    // The coarse cell `sample` picks, and the nearest coarse cell across a wall between
+    // finest cells, with how much farther its finest feature point is (F2 - F1). A
+    // pixel's coarse cell only changes where it crosses such a wall, and there the gap
+    // reaches 0, so it says how close the pixel is to switching and to which cell. Only
+    // the finest points within `max_gap` of the nearest are followed up the hierarchy,
+    // each costing about a `sample`, and None means none of them leads to another cell.
+    pub fn sample_across(&self, pos: Vec2, max_gap: f32) -> (CellId, Option<(CellId, f32)>) {
+        let (near, len) = self.across_level(pos, self.cells, self.depth, max_gap);
+        let (cell, dist) = near[0];
+        let across = near[1..len]
+            .iter()
+            .find(|&&(other, _)| other != cell)
+            .map(|&(other, other_dist)| (other.into(), other_dist - dist));
+        (cell.into(), across)
+    }
+
+    // The finest level's 3x3 block of points, nearest first, each carried up to this
+    // level's cell like `sample_level` carries a pixel's, and how many are within
+    // `max_gap` of the nearest
+    fn across_level(
+        &self,
+        pos: Vec2,
+        cell_size: Vec2,
+        depth: usize,
+        max_gap: f32,
+    ) -> ([(IVec2, f32); 9], usize) {
+        let cell_size = self.snap_cell_size(cell_size);
+        let level = self.depth.saturating_sub(depth);
+        if depth == 0 {
+            let grid = self.grid(pos, cell_size, level);
+            let mut near = [(grid.base_cell, 0.0); 9];
+            for (i, slot) in near.iter_mut().enumerate() {
+                let offset = IVec2::new(i as i32 / 3 - 1, i as i32 % 3 - 1);
+                *slot = self.feature(&grid, grid.base_cell + offset);
+            }
+            near.sort_unstable_by(|a, b| a.1.total_cmp(&b.1));
+            let len = near.iter().filter(|c| c.1 - near[0].1 <= max_gap).count();
+            return (near, len.max(1));
+        }
+
+        let finer_cell_size = self.snap_cell_size(cell_size / self.growth);
+        let (mut near, len) = self.across_level(pos, finer_cell_size, depth - 1, max_gap);
+        for (cell, _) in &mut near[..len] {
+            let new_sample_pos = cell.as_vec2() * finer_cell_size;
+            *cell = self.nearest_feature_at(new_sample_pos, cell_size, level).0;
+        }
+        (near, len)
+    }
+
+    // The size of the finest level's cells, after snapping to any toroidal domain
+    pub fn finest_cell_size(&self) -> Vec2 {
+        let mut cell_size = self.snap_cell_size(self.cells);
+        for _ in 0..self.depth {
+            cell_size = self.snap_cell_size(cell_size / self.growth);
+        }
+        cell_size
+    }
+
+    fn sample_level(
+        &self,
+        sample_pos: Vec2,
+        cell_size: Vec2,
+        depth: usize,
+        on_level: &mut impl FnMut(usize, f32),
+    ) -> (CellId, f32) {
+        let cell_size = self.snap_cell_size(cell_size);
+        let level = self.depth.saturating_sub(depth);
+        if depth == 0 {
+            let (cell, _, dist) = self.nearest_feature_at(sample_pos, cell_size, level);
+            on_level(level, dist);
+            return (cell.into(), self.blend(level, dist, 0.0));
+        }
+
+        let finer_cell_size = self.snap_cell_size(cell_size / self.growth);
+        let (cell, dist) = self.sample_level(sample_pos, finer_cell_size, depth - 1, on_level);
+
+        let new_sample_pos = cell.0.as_vec2() * finer_cell_size;
+        let (cell_o, _, dist_o) = self.nearest_feature_at(new_sample_pos, cell_size, level);
+        on_level(level, dist_o);
+
+        (cell_o.into(), self.blend(level, dist_o, dist))
+    }
+
+    // One level's step of the hierarchy's distance, from its raw distance and the finer
+    // levels' blended one
+    fn blend(&self, level: usize, dist: f32, finer: f32) -> f32 {
+        if self.base_level == Some(level) {
+            dist
+        } else if level >= self.depth {
+            0.0
+        } else {
+            dist * 0.25 + finer * 0.75
+        }
+    }
+
+    // The nearest feature point to `sample_pos` on a single grid of `cell_size` cells
+    pub fn worley(&self, sample_pos: Vec2, cell_size: Vec2) -> (IVec2, f32) {
+        let (cell, _offset, dist) = self.nearest_feature(sample_pos, cell_size);
+        (cell, dist)
+    }
+
+    // Like `worley`, also returning the offset from `sample_pos` to the feature point
+    pub fn nearest_feature(&self, sample_pos: Vec2, cell_size: Vec2) -> (IVec2, Vec2, f32) {
+        self.nearest_feature_at(sample_pos, cell_size, 0)
+    }
+
+    // `nearest_feature` with the points per cell of hierarchy level `level`
+    fn nearest_feature_at(
+        &self,
+        sample_pos: Vec2,
+        cell_size: Vec2,
+        level: usize,
+    ) -> (IVec2, Vec2, f32) {
+        let _scope = profile::scope(Stage::NeighborSearch);
+        let grid = self.grid(sample_pos, cell_size, level);
+
+        let mut best_cell = grid.base_cell;
+        let mut best_offset = Vec2::ZERO;
+        let mut best_dist = f32::INFINITY;
+
+        for xo in -1..=1 {
+            for yo in -1..=1 {
+                let (cell, offset) =
+                    self.feature_offset(&grid, grid.base_cell + IVec2::new(xo, yo));
+                let dist = self.cell_distance(grid.metric, cell, offset);
+
+                if best_dist > dist {
+                    best_cell = cell;
+                    best_offset = offset;
+                    best_dist = dist;
+                }
+            }
+        }
+
+        (best_cell, best_offset, best_dist)
+    }
+
+    // The distance from a sample to the nearest wall of its Voronoi cell: the closest
+    // perpendicular bisector between the nearest feature point and a neighboring one.
+    // Walls are always straight bisectors, so this is Euclidean whatever the metric, and
+    // ignores anisotropy.
+    pub fn edge_distance(&self, sample_pos: Vec2, cell_size: Vec2) -> f32 {
+        let grid = self.grid(sample_pos, cell_size, 0);
+
+        let mut nearest_cell = grid.base_cell;
+        let mut nearest = Vec2::ZERO;
+        let mut best_dist = f32::INFINITY;
+        for yo in -1..=1 {
+            for xo in -1..=1 {
+                let cell = grid.base_cell + IVec2::new(xo, yo);
+                let (_, offset) = self.feature_offset(&grid, cell);
+                let dist = offset.length_squared();
+                if best_dist > dist {
+                    nearest_cell = cell;
+                    nearest = offset;
+                    best_dist = dist;
+                }
+            }
+        }
+
+        // Any wall closer than the nearest point's own cell is shared with a point in the
+        // 5x5 block around it
+        let mut edge = f32::INFINITY;
+        for yo in -2..=2 {
+            for xo in -2..=2 {
+                let (_, offset) = self.feature_offset(&grid, nearest_cell + IVec2::new(xo, yo));
+                let between = offset - nearest;
+                // Skips the nearest point itself, and its images on a small torus
+                if between.length_squared() > f32::EPSILON {
+                    edge = edge.min((nearest + offset).dot(between.normalize()) * 0.5);
+                }
+            }
+        }
+        edge
+    }
+
+    // A unit vector at `pos` pointing at the nearest feature point of the coarsest grid,
+    // or a quarter turn from that for `FlowMode::Swirl`. Zero exactly on a feature point.
+    pub fn sample_flow(&self, pos: Vec2, mode: FlowMode) -> Vec2 {
+        let (_, offset, _) = self.nearest_feature(pos, self.cells);
+        let toward = offset.normalize_or_zero();
+        match mode {
+            FlowMode::Toward => toward,
+            FlowMode::Swirl => toward.perp(),
+        }
+    }
+
+    // A value from 0 to 1 per cell, independent of its feature point and color
+    pub fn cell_value(&self, cell: IVec2) -> f32 {
+        (mix64(self.cell_hash(cell) ^ 0x5bd1_e995) >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    // The coarsest cells' values blended smoothly across the image by inverse distance
+    // weighting of the `idw.k` nearest feature points. Plain IDW jumps wherever the k-th
+    // nearest point changes, so each weight has the (k+1)-th point's weight subtracted,
+    // fading a point out just as it leaves the k nearest. This keeps the field continuous
+    // everywhere for a `k` of 2 or more, for displacement and terrain. A `k` of 1 gives
+    // flat cells of their own value. Exactly on a feature point it is that cell's value.
+    pub fn sample_smooth_value(&self, pos: Vec2, idw: Idw) -> f32 {
+        let near = self.k_nearest(pos, self.cells, idw.k.max(1) + 1);
+        // A small toroidal domain can have fewer cells than asked for
+        let k = idw.k.max(1).min(near.len() - 1);
+        if k == 0 {
+            return self.cell_value(near[0].0);
+        }
+        let far = near[k].1;
+        let (mut total, mut weights) = (0.0, 0.0);
+        for &(cell, dist) in &near[..k] {
+            if dist <= 0.0 {
+                return self.cell_value(cell);
+            }
+            let inverse = Vec2::new(dist, far).powf(-idw.power);
+            let weight = (inverse.x - inverse.y).max(0.0);
+            total += weight * self.cell_value(cell);
+            weights += weight;
+        }
+        if weights > 0.0 {
+            total / weights
+        } else {
+            // Every point is as far as the (k+1)-th, which only happens on a knife edge
+            near[..k]
+                .iter()
+                .map(|&(cell, _)| self.cell_value(cell))
+                .sum::<f32>()
+                / k as f32
+        }
+    }
+
+    // The gradient of `sample`'s distance by central differences. Meaningless across a
+    // cell wall, where the distance jumps, so `step` should stay well inside the finest
+    // cells.
+    pub fn sample_gradient(&self, pos: Vec2, step: f32) -> Vec2 {
+        let dist = |offset: Vec2| self.sample(pos + offset).1;
+        Vec2::new(
+            dist(Vec2::X * step) - dist(-Vec2::X * step),
+            dist(Vec2::Y * step) - dist(-Vec2::Y * step),
+        ) / (2.0 * step)
+    }
+
+    // The exact gradient of `sample`'s distance away from cell walls. Coarser levels
+    // measure from the finer level's cell, which only changes at a wall, so only the
+    // finest level's distance varies with `pos`. Under the Euclidean metric that
+    // distance's gradient is the unit vector away from its feature point; other metrics
+    // and anisotropic cells fall back to `sample_gradient` with `step`.
+    pub fn sample_gradient_analytic(&self, pos: Vec2, step: f32) -> Vec2 {
+        if self.metric_at(self.depth) != DistanceMetric::Euclidean || self.anisotropy_strength > 0.0
+        {
+            return self.sample_gradient(pos, step);
+        }
+        let weight = self.level_weight(self.depth);
+        if weight == 0.0 {
+            return Vec2::ZERO;
+        }
+        let (_, offset, _) = self.nearest_feature_at(pos, self.finest_cell_size(), self.depth);
+        -offset.normalize_or_zero() * weight
+    }
+
+    // The `k` closest feature points, sorted by distance. Every cell outside the searched
+    // block is at least `radius` whole cells from the sample, so the search widens one
+    // ring at a time until the k-th distance fits inside that radius. The 3x3 block is
+    // only enough while the k-th point is within one cell, so large k searches further.
+    pub fn k_nearest(&self, sample_pos: Vec2, cell_size: Vec2, k: usize) -> KNearest {
+        let _scope = profile::scope(Stage::NeighborSearch);
+        let grid = self.grid(sample_pos, cell_size, 0);
+        let mut found = KNearest::new();
+        if k == 0 {
+            return found;
+        }
+
+        let mut radius: i32 = 0;
+        loop {
+            for yo in -radius..=radius {
+                for xo in -radius..=radius {
+                    if xo.abs() != radius && yo.abs() != radius {
+                        continue;
+                    }
+                    let (cell, dist) = self.feature(&grid, grid.base_cell + IVec2::new(xo, yo));
+                    // A toroidal search can wrap around onto cells it has already seen
+                    match found.iter_mut().find(|(c, _)| *c == cell) {
+                        Some(existing) => existing.1 = existing.1.min(dist),
+                        None => found.push((cell, dist)),
+                    }
+                }
+            }
+            found.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+            let mut covered = radius as f32 * grid.cell_size.min_element();
+            if self.anisotropy_strength > 0.0 {
+                // A squeezed cell measures a Euclidean length as little as 1 / stretch,
+                // and any metric at least 1 / sqrt(2) of it
+                covered /= (1.0 + self.anisotropy_strength) * core::f32::consts::SQRT_2;
+            }
+            if radius >= 1 && found.len() >= k && found[k - 1].1 <= covered {
+                break;
+            }
+            if grid.period.is_some_and(|p| radius > p.max_element()) {
+                break;
+            }
+            radius += 1;
+        }
+
+        found.truncate(k);
+        found
+    }
+
+    fn grid(&self, sample_pos: Vec2, cell_size: Vec2, level: usize) -> Grid {
+        let cell_size = self.snap_cell_size(cell_size);
+        let (sample_pos, period) = match self.toroidal_domain {
+            Some(domain) => (
+                sample_pos.rem_euclid(domain),
+                Some((domain / cell_size).round().as_ivec2()),
+            ),
+            None => (sample_pos, None),
+        };
+        Grid {
+            base_cell: (sample_pos / cell_size).floor().as_ivec2(),
+            sample_pos,
+            cell_size,
+            period,
+            points: self.points_per_level.get(level),
+            metric: self.metric_at(level),
+        }
+    }
+
+    // The id of a cell and the distance from the grid's sample to its feature point
+    fn feature(&self, grid: &Grid, cell: IVec2) -> (IVec2, f32) {
+        let (wrapped, offset) = self.feature_offset(grid, cell);
+        (wrapped, self.cell_distance(grid.metric, wrapped, offset))
+    }
+
+    // The matrix a cell's offsets pass through before their distance is measured: a
+    // squeeze along a random axis, so the cell stretches along it, and a matching stretch
+    // across it. The identity when `anisotropy_strength` is 0.
+    pub fn anisotropy(&self, cell: IVec2) -> Mat2 {
+        if self.anisotropy_strength <= 0.0 {
+            return Mat2::IDENTITY;
+        }
+        let hash = mix64(self.cell_hash(cell) ^ ANISOTROPY_SALT);
+        // An axis and its reverse stretch the same way, so half a turn covers them all
+        let angle = (hash >> 40) as f32 / (1u64 << 24) as f32 * core::f32::consts::PI;
+        let amount = ((hash >> 16) & 0xff_ffff) as f32 / (1u64 << 24) as f32;
+        let stretch = 1.0 + self.anisotropy_strength * amount;
+        let rotation = Mat2::from_angle(angle);
+        rotation * Mat2::from_diagonal(Vec2::new(1.0 / stretch, stretch)) * rotation.transpose()
+    }
+
+    // The distance of an offset to a feature point of `cell`, through its anisotropy
+    fn cell_distance(&self, metric: DistanceMetric, cell: IVec2, offset: Vec2) -> f32 {
+        if self.anisotropy_strength <= 0.0 {
+            return metric.distance(offset);
+        }
+        metric.distance(self.anisotropy(cell) * offset)
+    }
+
+    // The id of a cell and the offset from the grid's sample to its nearest feature
+    // point, taking the shortest way around a toroidal domain
+    fn feature_offset(&self, grid: &Grid, cell: IVec2) -> (IVec2, Vec2) {
+        // Cells past the seam share the hash of their periodic image
+        let wrapped = wrap(cell, grid.period);
+        let offset = |point: Vec2| {
+            let mut delta = (cell.as_vec2() + point) * grid.cell_size - grid.sample_pos;
+            if let Some(domain) = self.toroidal_domain {
+                delta -= domain * (delta / domain).round();
+            }
+            delta
+        };
+        let mut best = offset(self.periodic_center(wrapped, grid.period));
+        if grid.points > 1 {
+            // Every point of a cell shares its anisotropy
+            let anisotropy = self.anisotropy(wrapped);
+            let dist = |delta: Vec2| grid.metric.distance(anisotropy * delta);
+            for n in 1..grid.points {
+                let delta = offset(self.extra_point(wrapped, n));
+                if dist(delta) < dist(best) {
+                    best = delta;
+                }
+            }
+        }
+        (wrapped, best)
+    }
+
+    // A cell's `n`th feature point past the first, ZERO to ONE, straight from its hash
+    fn extra_point(&self, cell: IVec2, n: usize) -> Vec2 {
+        let hash = mix64(self.cell_hash(cell) ^ (n as u64).wrapping_mul(EXTRA_POINT_SALT));
+        let point = center_from_hash(hash);
+        match self.orbit {
+            Some(orbit) => orbit.apply(point, hash),
+            None => point,
+        }
+    }
+
+    // Shrinks or grows the cell size so a whole number of cells tiles the toroidal domain
+    fn snap_cell_size(&self, cell_size: Vec2) -> Vec2 {
+        match self.toroidal_domain {
+            Some(domain) => domain / (domain / cell_size).round().max(Vec2::ONE),
+            None => cell_size,
+        }
+    }
+
+    // The world space feature point and approximate extent of a cell on a grid of
+    // `cell_size` cells
+    pub fn cell_info(&self, cell: IVec2, cell_size: Vec2) -> CellInfo {
+        let cell_size = self.snap_cell_size(cell_size);
+        let period = self
+            .toroidal_domain
+            .map(|domain| (domain / cell_size).round().as_ivec2());
+        let point = |c: IVec2| {
+            let wrapped = wrap(c, period);
+            (c.as_vec2() + self.periodic_center(wrapped, period)) * cell_size
+        };
+
+        let center = point(cell);
+        let (mut min, mut max) = (center, center);
+        for yo in -1..=1 {
+            for xo in -1..=1 {
+                if xo != 0 || yo != 0 {
+                    let midpoint = (center + point(cell + IVec2::new(xo, yo))) * 0.5;
+                    min = min.min(midpoint);
+                    max = max.max(midpoint);
+                }
+            }
+        }
+        CellInfo {
+            center,
+            bounds: (min, max),
+        }
+    }
+
+    // The hash behind a cell's feature point and color
+    pub fn cell_hash(&self, cell: IVec2) -> u64 {
+        let _scope = profile::scope(Stage::Hash);
+        self.hash.hash(cell, self.seed)
+    }
+
+    // The feature point of a cell, ZERO to ONE, after any relaxation and orbit
+    pub fn center(&self, cell: IVec2) -> Vec2 {
+        self.periodic_center(cell, None)
+    }
+
+    // `center` on a grid that wraps every `period` cells, as each level of a toroidal
+    // domain does. Relaxation and spacing then see the cells across the seam as ghosts:
+    // the hash of their wrapped image placed at the adjacent position, so points along
+    // the seam settle exactly as they would anywhere else.
+    pub fn periodic_center(&self, cell: IVec2, period: Option<IVec2>) -> Vec2 {
+        let center = self.relaxed_center(cell, period, self.relaxation_iterations);
+        match self.orbit {
+            Some(orbit) => orbit.apply(center, self.cell_hash(cell)),
+            None => center,
+        }
+    }
+
+    // The cell's feature point before relaxation, always found through the cache of
+    // `relaxed_center`. With a minimum spacing, cells settle
+    // conflicts in order of a priority drawn from their hash: a point too close to a
+    // higher priority neighbor's final point is rejected and redrawn, and the first
+    // candidate is kept if every attempt fails. Neighbors are visited in a fixed order
+    // and priorities never tie, so every sample agrees on every point. On a torus,
+    // neighbors across the seam are ghosts of their wrapped images.
+    fn spaced_center(&self, cell: IVec2, period: Option<IVec2>) -> Vec2 {
+        let hash = self.cell_hash(cell);
+        let first = center_from_hash(hash);
+        let priority = (mix64(hash), cell.x, cell.y);
+        let clear = |candidate: Vec2| {
+            (-1..=1)
+                .flat_map(|y| (-1..=1).map(move |x| IVec2::new(x, y)))
+                .filter(|&offset| offset != IVec2::ZERO)
+                .all(|offset| {
+                    let neighbor = wrap(cell + offset, period);
+                    // A period of 1 wraps a cell onto itself, which it can't conflict with
+                    if neighbor == cell {
+                        return true;
+                    }
+                    let neighbor_hash = self.cell_hash(neighbor);
+                    if (mix64(neighbor_hash), neighbor.x, neighbor.y) < priority {
+                        return true;
+                    }
+                    // Skip neighbors whose cell can't hold a point that close
+                    let nearest = candidate.clamp(offset.as_vec2(), offset.as_vec2() + 1.0);
+                    if candidate.distance(nearest) >= self.min_spacing {
+                        return true;
+                    }
+                    let point = offset.as_vec2() + self.relaxed_center(neighbor, period, 0);
+                    candidate.distance(point) >= self.min_spacing
+                })
+        };
+
+        (0..SPACING_ATTEMPTS)
+            .map(|attempt| match attempt {
+                0 => first,
+                _ => center_from_hash(mix64(hash ^ attempt)),
+            })
+            .find(|&candidate| clear(candidate))
+            .unwrap_or(first)
+    }
+
+    fn relaxed_center(&self, cell: IVec2, period: Option<IVec2>, iterations: usize) -> Vec2 {
+        if iterations == 0 && self.min_spacing <= 0.0 {
+            return center_from_hash(self.cell_hash(cell));
+        }
+
+        let key = (
+            cell,
+            period,
+            self.seed,
+            self.hash,
+            self.min_spacing.to_bits(),
+            iterations,
+        );
+        if let Some(center) = relax_cache::get(key) {
+            return center;
+        }
+        let center = match iterations {
+            0 => self.spaced_center(cell, period),
+            _ => self.relax(cell, period, iterations),
+        };
+        relax_cache::insert(key, center);
+        center
+    }
+
+    // One approximate Lloyd step of the cell's point, from its neighborhood relaxed one
+    // step less
+    fn relax(&self, cell: IVec2, period: Option<IVec2>, iterations: usize) -> Vec2 {
+        // Feature points of the 3x3 block, relative to this cell's origin
+        let mut points = [Vec2::ZERO; 9];
+        for (i, point) in points.iter_mut().enumerate() {
+            let offset = IVec2::new(i as i32 % 3 - 1, i as i32 / 3 - 1);
+            let neighbor = wrap(cell + offset, period);
+            *point = offset.as_vec2() + self.relaxed_center(neighbor, period, iterations - 1);
+        }
+
+        // Estimate the centroid of this cell's region by sampling the block on a grid
+        let mut sum = Vec2::ZERO;
+        let mut count = 0;
+        for sy in -RELAX_SAMPLES..2 * RELAX_SAMPLES {
+            for sx in -RELAX_SAMPLES..2 * RELAX_SAMPLES {
+                let p = (Vec2::new(sx as f32, sy as f32) + 0.5) / RELAX_SAMPLES as f32;
+                let nearest = points
+                    .iter()
+                    .map(|q| q.distance_squared(p))
+                    .enumerate()
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(i, _)| i);
+                if nearest == Some(4) {
+                    sum += p;
+                    count += 1;
+                }
+            }
+        }
+
+        // Keep the point inside its own cell so the 3x3 search stays valid
+        if count == 0 {
+            points[4]
+        } else {
+            (sum / count as f32).clamp(Vec2::ZERO, Vec2::splat(1.0 - f32::EPSILON))
+        }
+    }
+}
+
+// High precision sampling for very large positions, where f32 can no longer resolve a
+// sub-pixel step (past about 1e7, every f32 is a whole number). Cell lookup and distances
+// run in f64; the cell hash and feature points are shared with the f32 path, so both agree
+// wherever f32 is precise enough. The f32 methods above stay the fast default. Cell
+// indices are still i32, so the finest level must have fewer than 2^31 cells per axis.
+impl WorleyNoise {
+    pub fn sample_f64(&self, pos: DVec2) -> (CellId, f32) {
+        self.sample_level_f64(pos, self.cells.as_dvec2(), self.depth)
+    }
+
+    fn sample_level_f64(&self, sample_pos: DVec2, cell_size: DVec2, depth: usize) -> (CellId, f32) {
+        let cell_size = self.snap_cell_size_f64(cell_size);
+        let level = self.depth.saturating_sub(depth);
+        if depth == 0 {
+            let (cell, dist) = self.worley_f64_at(sample_pos, cell_size, level);
+            return (cell.into(), self.blend(level, dist, 0.0));
+        }
+
+        let finer_cell_size = self.snap_cell_size_f64(cell_size / self.growth.as_dvec2());
+        let (cell, dist) = self.sample_level_f64(sample_pos, finer_cell_size, depth - 1);
+
+        let new_sample_pos = cell.0.as_dvec2() * finer_cell_size;
+        let (cell_o, dist_o) = self.worley_f64_at(new_sample_pos, cell_size, level);
+
+        (cell_o.into(), self.blend(level, dist_o, dist))
+    }
+
+    // `worley` with the sample position and cell size in f64
+    pub fn worley_f64(&self, sample_pos: DVec2, cell_size: DVec2) -> (IVec2, f32) {
+        self.worley_f64_at(sample_pos, cell_size, 0)
+    }
+
+    // `worley_f64` with the points per cell of hierarchy level `level`
+    fn worley_f64_at(&self, sample_pos: DVec2, cell_size: DVec2, level: usize) -> (IVec2, f32) {
+        let cell_size = self.snap_cell_size_f64(cell_size);
+        let domain = self.toroidal_domain.map(|d| d.as_dvec2());
+        let (sample_pos, period) = match domain {
+            Some(domain) => (
+                sample_pos.rem_euclid(domain),
+                Some((domain / cell_size).round().as_ivec2()),
+            ),
+            None => (sample_pos, None),
+        };
+        let base_cell = (sample_pos / cell_size).floor().as_ivec2();
+
+        let mut best_cell = base_cell;
+        let mut best_dist = f64::INFINITY;
+
+        for xo in -1..=1 {
+            for yo in -1..=1 {
+                let cell = base_cell + IVec2::new(xo, yo);
+                let wrapped = wrap(cell, period);
+                let (points, metric) = (self.points_per_level.get(level), self.metric_at(level));
+                let anisotropy = self.anisotropy(wrapped).as_dmat2();
+                for n in 0..points {
+                    let point = match n {
+                        0 => self.periodic_center(wrapped, period),
+                        _ => self.extra_point(wrapped, n),
+                    };
+                    let mut delta = (cell.as_dvec2() + point.as_dvec2()) * cell_size - sample_pos;
+                    if let Some(domain) = domain {
+                        delta -= domain * (delta / domain).round();
+                    }
+                    let dist = metric.distance_f64(anisotropy * delta);
+
+                    if best_dist > dist {
+                        best_cell = wrapped;
+                        best_dist = dist;
+                    }
+                }
+            }
+        }
+
+        (best_cell, best_dist as f32)
+    }
+
+    fn snap_cell_size_f64(&self, cell_size: DVec2) -> DVec2 {
+        match self.toroidal_domain {
+            Some(domain) => {
+                let domain = domain.as_dvec2();
+                domain / (domain / cell_size).round().max(DVec2::ONE)
+            }
+            None => cell_size,
+        }
+    }
+}
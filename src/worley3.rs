@@ -0,0 +1,100 @@
+//! Hierarchical worley noise over 3D space, for volumes or for animating a 2D slice by
+//! moving it along z. It mirrors the 2D sampler's hierarchy, without the toroidal,
+//! relaxation and metric options.
+
+use glam::{IVec3, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::Worley;
+
+// Hashes the seed + cell coordinate
+pub fn cell_hash3(cell: IVec3, seed: u64) -> u64 {
+    let mut x = (cell.x as i64 as u64).wrapping_mul(0xa0761d6478bd642f);
+    let mut y = (cell.y as i64 as u64).wrapping_mul(0xe7037ed1a0b428db);
+    let z = (cell.z as i64 as u64).wrapping_mul(0x589965cc75374cc3);
+    let mut s = seed.wrapping_mul(0x8ebc6af09c88c6e3);
+    x ^= y.rotate_left(25) ^ z.rotate_left(11);
+    y ^= s.rotate_left(47);
+    s ^= x.rotate_left(17);
+    s ^ y ^ z.rotate_left(37)
+}
+
+// Get the center of a worley cell, ZERO to ONE, from 21 hash bits per axis
+pub fn worley_center3(cell: IVec3, seed: u64) -> Vec3 {
+    let hash = cell_hash3(cell, seed);
+    let bits = |shift: u32| ((hash >> shift) & 0x1f_ffff) as f32 / 0x1f_ffff as f32;
+    Vec3::new(bits(0), bits(21), bits(42))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorleyNoise3 {
+    pub seed: u64,
+    pub depth: usize,
+    pub growth: f32,
+    pub cells: Vec3,
+}
+
+impl Default for WorleyNoise3 {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            depth: 8,
+            growth: 3.0,
+            cells: Vec3::splat(256.0),
+        }
+    }
+}
+
+impl WorleyNoise3 {
+    pub fn sample(&self, pos: Vec3) -> (IVec3, f32) {
+        self.sample_level(pos, self.cells, self.depth)
+    }
+
+    fn sample_level(&self, sample_pos: Vec3, cell_size: Vec3, depth: usize) -> (IVec3, f32) {
+        if depth == 0 {
+            let (cell, _dist) = self.worley(sample_pos, cell_size);
+            return (cell, 0.0);
+        }
+
+        let finer_cell_size = cell_size / self.growth;
+        let (cell, dist) = self.sample_level(sample_pos, finer_cell_size, depth - 1);
+
+        let new_sample_pos = cell.as_vec3() * finer_cell_size;
+        let (cell_o, dist_o) = self.worley(new_sample_pos, cell_size);
+
+        (cell_o, dist_o * 0.25 + dist * 0.75)
+    }
+
+    // The nearest feature point to `sample_pos` on a single grid of `cell_size` cells
+    pub fn worley(&self, sample_pos: Vec3, cell_size: Vec3) -> (IVec3, f32) {
+        let base_cell = (sample_pos / cell_size).floor().as_ivec3();
+
+        let mut best_cell = base_cell;
+        let mut best_dist = f32::INFINITY;
+
+        for zo in -1..=1 {
+            for yo in -1..=1 {
+                for xo in -1..=1 {
+                    let cell = base_cell + IVec3::new(xo, yo, zo);
+                    let center = (cell.as_vec3() + worley_center3(cell, self.seed)) * cell_size;
+                    let dist = center.distance(sample_pos);
+
+                    if best_dist > dist {
+                        best_cell = cell;
+                        best_dist = dist;
+                    }
+                }
+            }
+        }
+
+        (best_cell, best_dist)
+    }
+}
+
+impl Worley<Vec3> for WorleyNoise3 {
+    type Cell = IVec3;
+
+    fn sample(&self, pos: Vec3) -> (IVec3, f32) {
+        WorleyNoise3::sample(self, pos)
+    }
+}
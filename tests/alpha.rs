@@ -0,0 +1,111 @@
+use glam::{IVec2, U8Vec3, U8Vec4, USizeVec2, Vec2};
+use layered_worley::{
+    AlphaSource, Buffer, CHECKER_SIZE, Config, MaxDist, Named, WorleyNoise, checkerboard,
+    composite_checkerboard, render_alpha, rgba_from_vec, sample,
+};
+
+fn config() -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 4,
+            depth: 2,
+            cells: Vec2::splat(40.0),
+            ..Default::default()
+        },
+        max_dist: MaxDist::Pixels(12.0),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn checkerboard_alternates_squares() {
+    let light = checkerboard(USizeVec2::ZERO);
+    assert_ne!(light, checkerboard(USizeVec2::new(CHECKER_SIZE, 0)));
+    assert_eq!(light, checkerboard(USizeVec2::splat(CHECKER_SIZE)));
+    assert_eq!(light, checkerboard(USizeVec2::splat(CHECKER_SIZE - 1)));
+}
+
+#[test]
+fn compositing_blends_by_alpha() {
+    let pos = USizeVec2::new(3, 12);
+    let color = U8Vec3::new(250, 10, 90);
+    assert_eq!(composite_checkerboard(color.extend(255), pos), color);
+    assert_eq!(
+        composite_checkerboard(color.extend(0), pos),
+        checkerboard(pos)
+    );
+    let half = composite_checkerboard(color.extend(128), pos);
+    let back = checkerboard(pos);
+    for i in 0..3 {
+        let (lo, hi) = (color[i].min(back[i]), color[i].max(back[i]));
+        assert!((lo..=hi).contains(&half[i]), "{half}");
+    }
+}
+
+#[test]
+fn rgba_buffers_pack_and_preview() {
+    let rgb = Buffer {
+        buff: vec![U8Vec3::new(1, 2, 3), U8Vec3::new(200, 100, 0)],
+        width: 2,
+        height: 1,
+    };
+    let alpha = Buffer {
+        buff: vec![255, 0],
+        width: 2,
+        height: 1,
+    };
+    let rgba = rgb.with_alpha(&alpha);
+    assert_eq!(
+        rgba.buff,
+        [U8Vec4::new(1, 2, 3, 255), U8Vec4::new(200, 100, 0, 0)]
+    );
+
+    let mut packed = Vec::new();
+    rgba.to_packed_rgba(&mut packed);
+    assert_eq!(packed, [rgba_from_vec(rgba.buff[0]), 0x00c86400]);
+
+    let preview = rgba.over_checkerboard();
+    assert_eq!(preview.buff[0], U8Vec3::new(1, 2, 3));
+    assert_eq!(preview.buff[1], checkerboard(USizeVec2::new(1, 0)));
+}
+
+#[test]
+fn distance_alpha_fades_out_to_max_dist() {
+    let config = config();
+    let mut alpha = Buffer::new(48, 32, 0);
+    render_alpha(&mut alpha, &config, IVec2::new(5, 9), AlphaSource::Distance);
+    alpha.par_for_each(|pos, &mut a| {
+        let dist = sample((pos + USizeVec2::new(5, 9)).as_vec2(), &config).1;
+        let expected = ((1.0 - dist / 12.0).clamp(0.0, 1.0) * 255.0).round() as u8;
+        assert_eq!(a, expected, "{pos}");
+    });
+}
+
+#[test]
+fn edge_gap_alpha_clears_along_cell_walls() {
+    let config = config();
+    let mut alpha = Buffer::new(64, 64, 0);
+    render_alpha(&mut alpha, &config, IVec2::ZERO, AlphaSource::EdgeGap);
+    let noise = config.rendered_noise();
+    for (i, &a) in alpha.buff.iter().enumerate() {
+        let pos = Vec2::new((i % 64) as f32, (i / 64) as f32);
+        let [(_, f1), (_, f2)] = noise.sample_f2(pos);
+        assert_eq!(a == 0, f2 - f1 < 0.5 / 255.0 * 12.0, "{pos}");
+    }
+    assert!(alpha.buff.iter().any(|&a| a < 16));
+    assert!(alpha.buff.iter().any(|&a| a > 128));
+}
+
+#[test]
+fn sources_parse_and_cycle() {
+    assert_eq!(
+        "f2-f1".parse::<AlphaSource>().unwrap(),
+        AlphaSource::EdgeGap
+    );
+    assert!("depth".parse::<AlphaSource>().is_err());
+    let mut source = AlphaSource::default();
+    for _ in 0..AlphaSource::ALL.len() {
+        source = source.next();
+    }
+    assert_eq!(source, AlphaSource::default());
+}
@@ -0,0 +1,106 @@
+use glam::{IVec2, Mat2, Vec2};
+use layered_worley::{DistanceMetric, WorleyNoise};
+
+fn noise(anisotropy_strength: f32) -> WorleyNoise {
+    WorleyNoise {
+        seed: 23,
+        depth: 3,
+        cells: Vec2::splat(64.0),
+        anisotropy_strength,
+        ..Default::default()
+    }
+}
+
+fn positions() -> impl Iterator<Item = Vec2> {
+    (0..400).map(|i| Vec2::new((i * 37 % 500) as f32 + 0.3, (i * 91 % 300) as f32 - 40.7))
+}
+
+fn cells() -> impl Iterator<Item = IVec2> {
+    (-20..20).map(|i| IVec2::new(i, i * 7 - 3))
+}
+
+#[test]
+fn zero_strength_leaves_cells_round() {
+    let noise = noise(0.0);
+    noise.validate().unwrap();
+    assert!(cells().all(|cell| noise.anisotropy(cell) == Mat2::IDENTITY));
+    let plain = WorleyNoise {
+        anisotropy_strength: 0.0,
+        ..WorleyNoise::default()
+    };
+    assert_eq!(plain, WorleyNoise::default());
+}
+
+#[test]
+fn matrices_keep_area_and_stay_within_the_strength() {
+    let noise = noise(1.5);
+    for cell in cells() {
+        let matrix = noise.anisotropy(cell);
+        assert!((matrix.determinant() - 1.0).abs() < 1e-4, "{cell}");
+        // Symmetric, so its eigenvalues are the squeeze and the stretch
+        assert!((matrix.x_axis.y - matrix.y_axis.x).abs() < 1e-5, "{cell}");
+        let stretch = (0..64)
+            .map(|i| (matrix * Vec2::from_angle(i as f32 / 64.0 * 3.2)).length())
+            .fold(0.0, f32::max);
+        assert!((1.0..=2.5 + 1e-4).contains(&stretch), "{cell} {stretch}");
+    }
+}
+
+#[test]
+fn axes_and_stretches_vary_by_cell() {
+    let noise = noise(1.0);
+    let matrices: Vec<Mat2> = cells().map(|cell| noise.anisotropy(cell)).collect();
+    let distinct = matrices
+        .iter()
+        .enumerate()
+        .filter(|(i, m)| !matrices[..*i].contains(m))
+        .count();
+    assert_eq!(distinct, matrices.len());
+}
+
+#[test]
+fn anisotropy_changes_the_layout() {
+    let (round, stretched) = (noise(0.0), noise(1.0));
+    let changed = positions()
+        .filter(|&pos| round.sample(pos) != stretched.sample(pos))
+        .count();
+    assert!(changed > positions().count() / 4, "{changed}");
+}
+
+#[test]
+fn f64_path_agrees() {
+    for metric in [DistanceMetric::Euclidean, DistanceMetric::Manhattan] {
+        let noise = WorleyNoise {
+            metric,
+            ..noise(1.0)
+        };
+        for pos in positions() {
+            let (cell, dist) = noise.sample(pos);
+            let (cell_f64, dist_f64) = noise.sample_f64(pos.as_dvec2());
+            assert_eq!(cell, cell_f64, "{pos}");
+            assert!((dist - dist_f64).abs() < 1e-3, "{pos}");
+        }
+    }
+}
+
+// `k_nearest` widens its search for stretched cells, while `worley` keeps to the 3x3
+// block and clips cells reaching past it, so it can only ever find a farther point
+#[test]
+fn k_nearest_finds_cells_past_the_block() {
+    let noise = noise(2.0);
+    let mut same = 0;
+    for pos in positions() {
+        let near = noise.k_nearest(pos, noise.cells, 3);
+        let nearest = noise.worley(pos, noise.cells);
+        assert!(near[0].1 <= nearest.1 + 1e-4, "{pos}");
+        assert!(near.windows(2).all(|w| w[0].1 <= w[1].1));
+        same += (near[0] == nearest) as usize;
+    }
+    assert!(same >= positions().count() * 9 / 10, "{same}");
+}
+
+#[test]
+fn negative_strength_is_rejected() {
+    assert!(noise(-0.5).validate().is_err());
+    assert!(noise(f32::NAN).validate().is_err());
+}
@@ -0,0 +1,65 @@
+use glam::{IVec2, Vec2};
+use layered_worley::{
+    Buffer, Config, MaxDist, PixelGeometry, WorleyNoise, compute_field, estimate_max_dist,
+};
+
+fn config(cells: f32) -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 12,
+            depth: 3,
+            cells: Vec2::splat(cells),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+// The fraction of a full render's pixels within `max_dist`
+fn fraction_within(config: &Config, width: usize, height: usize, max_dist: f32) -> f32 {
+    let mut field = Buffer::new(width, height, PixelGeometry::default());
+    compute_field(&mut field, config, IVec2::ZERO);
+    let within = field.buff.iter().filter(|g| g.dist <= max_dist).count();
+    within as f32 / field.buff.len() as f32
+}
+
+#[test]
+fn estimate_covers_the_percentile_of_the_full_render() {
+    let (width, height) = (256, 192);
+    for cells in [40.0, 160.0] {
+        let config = config(cells);
+        let max_dist = estimate_max_dist(&config, width, height, 0.95);
+        let fraction = fraction_within(&config, width, height, max_dist);
+        assert!(
+            (0.9..=0.99).contains(&fraction),
+            "{cells} {max_dist} {fraction}"
+        );
+    }
+}
+
+#[test]
+fn estimate_follows_the_cell_size() {
+    let small = estimate_max_dist(&config(40.0), 256, 256, 0.95);
+    let large = estimate_max_dist(&config(160.0), 256, 256, 0.95);
+    assert!(large > small * 2.0, "{small} {large}");
+}
+
+#[test]
+fn higher_percentiles_give_larger_distances() {
+    let config = config(80.0);
+    let estimates = [0.5, 0.9, 1.0].map(|p| estimate_max_dist(&config, 128, 128, p));
+    assert!(estimates.windows(2).all(|w| w[0] <= w[1]), "{estimates:?}");
+}
+
+#[test]
+fn estimate_ignores_the_current_max_dist() {
+    let mut config = config(80.0);
+    let before = estimate_max_dist(&config, 128, 96, 0.95);
+    config.max_dist = MaxDist::CellFraction(0.05);
+    assert_eq!(estimate_max_dist(&config, 128, 96, 0.95), before);
+}
+
+#[test]
+fn empty_images_estimate_zero() {
+    assert_eq!(estimate_max_dist(&config(80.0), 0, 64, 0.95), 0.0);
+}
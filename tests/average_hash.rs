@@ -0,0 +1,56 @@
+use glam::{U8Vec3, Vec2};
+use layered_worley::{Buffer, Config, Pulse, WorleyNoise, hash_distance, render};
+
+fn config() -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 2,
+            depth: 3,
+            cells: Vec2::splat(40.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn hash(config: &Config) -> u64 {
+    let mut buffer = Buffer::new(120, 80, U8Vec3::ZERO);
+    render(&mut buffer, config);
+    buffer.average_hash()
+}
+
+#[test]
+fn visible_changes_change_the_hash() {
+    let base = hash(&config());
+    let mut bigger = config();
+    bigger.noise.cells = Vec2::splat(70.0);
+    let mut reseeded = config();
+    reseeded.noise.seed = 3;
+    for changed in [bigger, reseeded] {
+        assert!(hash_distance(base, hash(&changed)) > 8);
+    }
+}
+
+#[test]
+fn no_op_changes_keep_the_hash() {
+    let base = hash(&config());
+    // Pulsing only applies in the viewer, and rendering every level is the default
+    let mut pulsing = config();
+    pulsing.pulse = Some(Pulse {
+        amplitude: 0.5,
+        speed: 1.0,
+    });
+    let mut full_depth = config();
+    full_depth.render_depth = Some(3);
+    assert_eq!(hash(&pulsing), base);
+    assert_eq!(hash(&full_depth), base);
+}
+
+#[test]
+fn tiny_and_flat_images_hash() {
+    let tiny = Buffer::new(3, 2, U8Vec3::new(9, 9, 9));
+    assert_eq!(tiny.average_hash(), 0);
+    let mut half = Buffer::new(16, 16, U8Vec3::ZERO);
+    half.par_for_each(|pos, p| *p = U8Vec3::splat(if pos.y < 8 { 255 } else { 0 }));
+    assert_eq!(half.average_hash(), u32::MAX as u64);
+}
@@ -0,0 +1,70 @@
+use glam::Vec2;
+use layered_worley::WorleyNoise;
+
+fn noise(base_level: Option<usize>) -> WorleyNoise {
+    WorleyNoise {
+        seed: 23,
+        depth: 4,
+        cells: Vec2::splat(64.0),
+        base_level,
+        ..Default::default()
+    }
+}
+
+fn positions() -> impl Iterator<Item = Vec2> {
+    (0..300).map(|i| Vec2::new((i * 37 % 500) as f32 + 0.3, (i * 91 % 300) as f32 - 40.7))
+}
+
+#[test]
+fn base_level_keeps_the_cells() {
+    let finest = noise(None);
+    for base in 0..=4 {
+        let anchored = noise(Some(base));
+        for pos in positions() {
+            assert_eq!(anchored.sample(pos).0, finest.sample(pos).0);
+        }
+    }
+}
+
+#[test]
+fn weights_match_the_sampled_distance() {
+    for base in [None, Some(0), Some(2), Some(4)] {
+        let noise = noise(base);
+        for pos in positions() {
+            let mut total = 0.0;
+            let (_, dist) =
+                noise.sample_traced(pos, |level, d| total += d * noise.level_weight(level));
+            assert!((total - dist).abs() < 1e-3, "{base:?} {total} {dist}");
+        }
+    }
+}
+
+#[test]
+fn coarsest_base_level_is_the_raw_coarse_distance() {
+    let noise = noise(Some(0));
+    for pos in positions() {
+        let mut coarse = None;
+        let (_, dist) = noise.sample_traced(pos, |level, d| {
+            if level == 0 {
+                coarse = Some(d);
+            }
+        });
+        assert_eq!(Some(dist), coarse);
+        assert_eq!(noise.sample_f2(pos)[0].1, dist);
+    }
+}
+
+#[test]
+fn finest_base_level_is_no_longer_zero_at_depth_0() {
+    let noise = WorleyNoise {
+        depth: 0,
+        ..noise(Some(0))
+    };
+    assert!(positions().any(|pos| noise.sample(pos).1 > 0.0));
+}
+
+#[test]
+fn base_level_must_be_within_depth() {
+    assert!(noise(Some(4)).validate().is_ok());
+    assert!(noise(Some(5)).validate().is_err());
+}
@@ -0,0 +1,43 @@
+use glam::{USizeVec2, Vec2};
+use layered_worley::Buffer;
+
+#[test]
+fn set_then_get_round_trips_inside() {
+    let mut buffer = Buffer::new(7, 5, 0u32);
+    for y in 0..5 {
+        for x in 0..7 {
+            let pos = USizeVec2::new(x, y);
+            let value = (x * 100 + y) as u32 + 1;
+            buffer.set(pos, value);
+            assert_eq!(buffer.get(pos), Some(value));
+            assert_eq!(buffer.buff[buffer.index(pos).unwrap()], value);
+        }
+    }
+}
+
+#[test]
+fn coordinates_past_the_edges_do_not_wrap() {
+    let mut buffer = Buffer::new(7, 5, 0u32);
+    for pos in [
+        USizeVec2::new(7, 0),
+        USizeVec2::new(9, 2),
+        USizeVec2::new(0, 5),
+        USizeVec2::new(20, 20),
+    ] {
+        buffer.set(pos, 1);
+        assert_eq!(buffer.index(pos), None);
+        assert_eq!(buffer.get(pos), None);
+    }
+    // Nothing landed on a wrapped neighbor
+    assert!(buffer.buff.iter().all(|&v| v == 0));
+}
+
+#[test]
+fn negative_float_positions_are_skipped() {
+    let mut buffer = Buffer::new(4, 4, 0u32);
+    buffer.setf(Vec2::new(-3.0, 1.0), 1);
+    buffer.setf(Vec2::new(1.0, -0.7), 1);
+    assert!(buffer.buff.iter().all(|&v| v == 0));
+    buffer.setf(Vec2::new(-0.4, 1.2), 2);
+    assert_eq!(buffer.get(USizeVec2::new(0, 1)), Some(2));
+}
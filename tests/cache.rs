@@ -0,0 +1,116 @@
+use glam::{IVec2, U8Vec3, Vec2, Vec3};
+use layered_worley::{
+    Buffer, CellColoring, ColorMode, Config, MaxDist, Palette, PixelGeometry, RenderCache, ToneMap,
+    WorleyNoise, colorize, compute_field, render_region,
+};
+
+fn config() -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 8,
+            depth: 3,
+            cells: Vec2::splat(24.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn direct(config: &Config, origin: IVec2) -> Vec<U8Vec3> {
+    let mut buffer = Buffer::new(48, 32, U8Vec3::ZERO);
+    render_region(&mut buffer, config, origin);
+    buffer.buff
+}
+
+#[test]
+fn color_changes_reuse_the_geometry() {
+    let origin = IVec2::new(5, 9);
+    let mut cache = RenderCache::new();
+    let mut buffer = Buffer::new(48, 32, U8Vec3::ZERO);
+    let mut config = config();
+    assert!(cache.render_region(&mut buffer, &config, origin));
+    assert_eq!(buffer.buff, direct(&config, origin));
+
+    let tweaks: [fn(&mut Config); 6] = [
+        |c| c.dist_power = 2.5,
+        |c| c.max_dist = MaxDist::CellFraction(0.4),
+        |c| c.palette = Palette::new([(Vec3::new(10.0, 220.0, 90.0), 1)]),
+        |c| c.tonemap = ToneMap::Aces,
+        |c| c.background = Vec3::splat(200.0),
+        |c| c.spatial_color_bias = Vec2::new(0.2, -0.1),
+    ];
+    for tweak in tweaks {
+        tweak(&mut config);
+        assert!(!cache.render_region(&mut buffer, &config, origin));
+        assert_eq!(buffer.buff, direct(&config, origin));
+    }
+}
+
+#[test]
+fn geometry_changes_resample() {
+    let mut cache = RenderCache::new();
+    let mut buffer = Buffer::new(48, 32, U8Vec3::ZERO);
+    let mut config = config();
+    cache.render(&mut buffer, &config);
+
+    let tweaks: [fn(&mut Config); 5] = [
+        |c| c.noise.seed = 9,
+        |c| c.noise.growth = Vec2::splat(2.0),
+        |c| c.render_depth = Some(1),
+        // Blending and level colors need more of the geometry than plain colors
+        |c| c.color_blend = 0.1,
+        |c| c.color_mode = ColorMode::LevelDebug,
+    ];
+    for tweak in tweaks {
+        tweak(&mut config);
+        assert!(cache.render(&mut buffer, &config));
+        assert_eq!(buffer.buff, direct(&config, IVec2::ZERO));
+        assert!(!cache.render(&mut buffer, &config));
+    }
+}
+
+#[test]
+fn rotation_changes_resample() {
+    let mut cache = RenderCache::new();
+    let mut buffer = Buffer::new(48, 32, U8Vec3::ZERO);
+    let mut config = config();
+    cache.render(&mut buffer, &config);
+
+    let tweaks: [fn(&mut Config); 2] = [
+        |c| c.rotation = 0.7,
+        |c| c.rotation_center = Vec2::new(24.0, 16.0),
+    ];
+    for tweak in tweaks {
+        tweak(&mut config);
+        assert!(cache.render(&mut buffer, &config));
+        assert_eq!(buffer.buff, direct(&config, IVec2::ZERO));
+        assert!(!cache.render(&mut buffer, &config));
+    }
+}
+
+#[test]
+fn one_field_colors_every_mode() {
+    let origin = IVec2::new(2, 7);
+    let mut field = Buffer::new(48, 32, PixelGeometry::default());
+    compute_field(&mut field, &config(), origin);
+
+    let looks: [fn(&mut Config); 7] = [
+        |_| {},
+        |c| c.color_blend = 0.08,
+        |c| c.color_mode = ColorMode::LevelDebug,
+        |c| c.color_mode = ColorMode::CellHash,
+        |c| {
+            c.color_mode = ColorMode::DistanceBanded;
+            c.palette = Palette::new([(Vec3::ONE, 1), (Vec3::splat(90.0), 2)]);
+        },
+        |c| c.cell_coloring = CellColoring::Dithered,
+        |c| c.cell_coloring = CellColoring::Interpolated,
+    ];
+    for look in looks {
+        let mut config = config();
+        look(&mut config);
+        let mut buffer = Buffer::new(48, 32, U8Vec3::ZERO);
+        colorize(&mut buffer, &field, &config, origin);
+        assert_eq!(buffer.buff, direct(&config, origin));
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use glam::{U8Vec3, USizeVec2, Vec2};
+use layered_worley::{
+    Buffer, CellColoring, CellId, Config, MaxDist, WorleyNoise, render, render_pixel, sample,
+};
+
+const SIZE: usize = 96;
+
+// No falloff, so every pixel shows only its cell coloring
+fn config(cell_coloring: CellColoring) -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 21,
+            depth: 1,
+            cells: Vec2::splat(32.0),
+            ..Default::default()
+        },
+        max_dist: MaxDist::Pixels(1e6),
+        dist_power: 0.0,
+        cell_coloring,
+        ..Default::default()
+    }
+}
+
+fn pixels(config: &Config) -> Vec<(CellId, U8Vec3)> {
+    let mut buffer = Buffer::new(SIZE, SIZE, U8Vec3::ZERO);
+    render(&mut buffer, config);
+    (0..SIZE * SIZE)
+        .map(|i| {
+            let pos = USizeVec2::new(i % SIZE, i / SIZE);
+            (sample(pos.as_vec2(), config).0, buffer.buff[i])
+        })
+        .collect()
+}
+
+#[test]
+fn flat_is_the_default() {
+    assert_eq!(Config::default().cell_coloring, CellColoring::Flat);
+}
+
+#[test]
+fn flat_cells_have_one_color() {
+    let mut colors = HashMap::new();
+    for (cell, rgb) in pixels(&config(CellColoring::Flat)) {
+        assert_eq!(*colors.entry(cell).or_insert(rgb), rgb, "{cell:?}");
+    }
+    assert!(colors.len() > 2);
+}
+
+#[test]
+fn dithered_cells_vary_per_pixel() {
+    let mut colors: HashMap<CellId, Vec<U8Vec3>> = HashMap::new();
+    for (cell, rgb) in pixels(&config(CellColoring::Dithered)) {
+        colors.entry(cell).or_default().push(rgb);
+    }
+    for (cell, rgbs) in colors.iter().filter(|(_, rgbs)| rgbs.len() > 100) {
+        assert!(rgbs.iter().any(|&rgb| rgb != rgbs[0]), "{cell:?} is flat");
+    }
+}
+
+// Walks a line across several cells in small steps, returning the largest change in
+// any channel from one step to the next
+fn largest_step(coloring: CellColoring) -> i32 {
+    let config = config(coloring);
+    let mut previous = render_pixel(Vec2::new(0.0, 40.0), &config).as_ivec3();
+    let mut largest = 0;
+    for i in 1..SIZE * 64 {
+        let rgb = render_pixel(Vec2::new(i as f32 / 64.0, 40.0), &config).as_ivec3();
+        largest = largest.max((rgb - previous).abs().max_element());
+        previous = rgb;
+    }
+    largest
+}
+
+#[test]
+fn interpolated_colors_never_jump() {
+    assert!(largest_step(CellColoring::Flat) > 40);
+    assert!(largest_step(CellColoring::Interpolated) <= 3);
+}
+
+// A domain one cell wide leaves nothing to blend towards
+#[test]
+fn interpolated_one_cell_torus_is_flat() {
+    let mut config = config(CellColoring::Interpolated);
+    config.noise.toroidal_domain = Some(config.noise.cells);
+    let pixels = pixels(&config);
+    for &(_, rgb) in &pixels {
+        let step = (rgb.as_ivec3() - pixels[0].1.as_ivec3())
+            .abs()
+            .max_element();
+        assert!(step <= 1, "{rgb} vs {}", pixels[0].1);
+    }
+}
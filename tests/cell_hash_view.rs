@@ -0,0 +1,56 @@
+use glam::{U8Vec3, Vec2, Vec3};
+use layered_worley::{
+    Buffer, CellColoring, ColorMode, Config, DitherTexture, HashMixer, ToneMap, WorleyNoise,
+    render, sample,
+};
+
+fn config(hash: HashMixer) -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 21,
+            depth: 2,
+            cells: Vec2::splat(18.0),
+            hash,
+            ..Default::default()
+        },
+        color_mode: ColorMode::CellHash,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn pixels_show_the_low_hash_bits() {
+    for hash in [HashMixer::Legacy, HashMixer::SplitMix] {
+        let config = config(hash);
+        let mut buffer = Buffer::new(40, 30, U8Vec3::ZERO);
+        render(&mut buffer, &config);
+        buffer.par_for_each(|pos, pixel| {
+            let (cell, _) = sample(pos.as_vec2(), &config);
+            let bits = config.noise.cell_hash(cell.into());
+            let expected = U8Vec3::new((bits >> 16) as u8, (bits >> 8) as u8, bits as u8);
+            assert_eq!(*pixel, expected, "{pos}");
+        });
+    }
+}
+
+#[test]
+fn color_settings_leave_the_hashes_alone() {
+    let plain = config(HashMixer::Legacy);
+    let styled = Config {
+        cell_coloring: CellColoring::Dithered,
+        color_blend: 0.2,
+        tonemap: ToneMap::Aces,
+        background: Vec3::splat(90.0),
+        dither: Some(DitherTexture {
+            width: 1,
+            height: 1,
+            thresholds: vec![0.9],
+        }),
+        ..plain.clone()
+    };
+    let mut a = Buffer::new(40, 30, U8Vec3::ZERO);
+    let mut b = Buffer::new(40, 30, U8Vec3::ZERO);
+    render(&mut a, &plain);
+    render(&mut b, &styled);
+    assert_eq!(a.buff, b.buff);
+}
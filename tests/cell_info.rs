@@ -0,0 +1,33 @@
+use glam::{IVec2, Vec2};
+use layered_worley::{WorleyNoise, cell_info, worley};
+
+#[test]
+fn cell_info_center_is_the_feature_point() {
+    let cell_size = Vec2::new(40.0, 30.0);
+    for y in -3..3 {
+        for x in -3..3 {
+            let cell = IVec2::new(x, y);
+            let info = cell_info(cell, cell_size, 17);
+            let (min, max) = info.bounds;
+            assert!(min.cmple(info.center).all() && info.center.cmple(max).all());
+            assert!(min.cmplt(max).all());
+
+            let (nearest, dist) = worley(info.center, cell_size, 17);
+            assert_eq!(nearest, cell);
+            assert!(dist < 1e-3);
+        }
+    }
+}
+
+#[test]
+fn cell_info_wraps_on_a_torus() {
+    let noise = WorleyNoise {
+        seed: 4,
+        toroidal_domain: Some(Vec2::splat(100.0)),
+        ..Default::default()
+    };
+    let cell_size = Vec2::splat(25.0);
+    let inside = noise.cell_info(IVec2::new(0, 2), cell_size);
+    let shifted = noise.cell_info(IVec2::new(4, 2), cell_size);
+    assert!((shifted.center - inside.center - Vec2::new(100.0, 0.0)).length() < 1e-3);
+}
@@ -0,0 +1,63 @@
+use glam::{U8Vec3, U8Vec4};
+use layered_worley::{
+    Buffer, rgb_from_u8, rgb_from_vec, rgba_from_u8, rgba_from_vec, unpack_rgb, unpack_rgba,
+};
+
+#[test]
+fn rgb_round_trips_every_channel_value() {
+    for v in 0..=255u8 {
+        for rgb in [
+            U8Vec3::new(v, 0, 0),
+            U8Vec3::new(0, v, 0),
+            U8Vec3::new(0, 0, v),
+            U8Vec3::new(v, 255 - v, v / 2),
+        ] {
+            assert_eq!(unpack_rgb(rgb_from_vec(rgb)), rgb);
+            assert_eq!(rgb_from_vec(rgb), rgb_from_u8(rgb.x, rgb.y, rgb.z));
+        }
+    }
+}
+
+#[test]
+fn rgba_round_trips_every_channel_value() {
+    for v in 0..=255u8 {
+        for rgba in [
+            U8Vec4::new(v, 0, 0, 0),
+            U8Vec4::new(0, v, 0, 0),
+            U8Vec4::new(0, 0, v, 0),
+            U8Vec4::new(0, 0, 0, v),
+            U8Vec4::new(v, 255 - v, v / 2, v ^ 0x5a),
+        ] {
+            assert_eq!(unpack_rgba(rgba_from_vec(rgba)), rgba);
+            assert_eq!(
+                rgba_from_vec(rgba),
+                rgba_from_u8(rgba.x, rgba.y, rgba.z, rgba.w)
+            );
+        }
+    }
+}
+
+#[test]
+fn rgba_keeps_rgb_layout() {
+    let rgba = U8Vec4::new(1, 2, 3, 4);
+    assert_eq!(
+        rgba_from_vec(rgba) & 0xffffff,
+        rgb_from_vec(rgba.truncate())
+    );
+    assert_eq!(unpack_rgb(rgba_from_vec(rgba)), rgba.truncate());
+}
+
+#[test]
+fn minifb_buffer_reuses_its_allocation() {
+    let mut buffer = Buffer::new(4, 3, U8Vec3::new(1, 2, 3));
+    let mut packed = Vec::new();
+    buffer.to_minifb_buffer(&mut packed);
+    assert_eq!(packed, vec![0x010203; 12]);
+
+    let capacity = packed.capacity();
+    buffer.set((2, 1).into(), U8Vec3::new(255, 0, 128));
+    buffer.to_minifb_buffer(&mut packed);
+    assert_eq!(packed.len(), 12);
+    assert_eq!(packed[6], 0xff0080);
+    assert_eq!(packed.capacity(), capacity);
+}
@@ -0,0 +1,63 @@
+use glam::{IVec2, U8Vec3, Vec2};
+use layered_worley::{Buffer, Config, RenderCache, WorleyNoise, render};
+
+fn config(color_seed: Option<u64>) -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 12,
+            depth: 3,
+            cells: Vec2::splat(24.0),
+            ..Default::default()
+        },
+        color_seed,
+        ..Default::default()
+    }
+}
+
+fn rendered(config: &Config) -> Buffer<U8Vec3> {
+    let mut buffer = Buffer::new(64, 48, U8Vec3::ZERO);
+    render(&mut buffer, config);
+    buffer
+}
+
+#[test]
+fn no_color_seed_uses_the_cell_hash() {
+    let config = config(None);
+    for y in -5..5 {
+        for x in -5..5 {
+            let cell = IVec2::new(x, y);
+            assert_eq!(config.color_hash(cell), config.noise.cell_hash(cell));
+        }
+    }
+}
+
+#[test]
+fn color_seed_is_independent_of_the_seed() {
+    // Even the same number as the seed picks other colors
+    let config = config(Some(12));
+    let same = (-10..10)
+        .filter(|&x| {
+            config.color_hash(IVec2::new(x, 3)) == config.noise.cell_hash(IVec2::new(x, 3))
+        })
+        .count();
+    assert_eq!(same, 0);
+}
+
+#[test]
+fn rerolling_colors_keeps_the_geometry() {
+    let mut cache = RenderCache::new();
+    let mut buffer = Buffer::new(64, 48, U8Vec3::ZERO);
+    assert!(cache.render(&mut buffer, &config(Some(1))));
+    let first = buffer.clone();
+    assert!(!cache.render(&mut buffer, &config(Some(2))));
+    assert_eq!(buffer.buff, rendered(&config(Some(2))).buff);
+    assert_ne!(buffer.buff, first.buff);
+}
+
+#[test]
+fn color_seed_is_deterministic() {
+    assert_eq!(
+        rendered(&config(Some(99))).buff,
+        rendered(&config(Some(99))).buff
+    );
+}
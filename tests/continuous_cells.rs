@@ -0,0 +1,137 @@
+use glam::{IVec2, Vec2};
+use layered_worley::{
+    Buffer, Config, MaxDist, PixelGeometry, RenderCache, WorleyNoise, colorize, compute_field,
+    render, sample, sample_geometry, shade_float,
+};
+
+// Large finest cells and no falloff, so colors change only between cells
+fn config(continuous_cells: f32) -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 9,
+            depth: 2,
+            cells: Vec2::splat(200.0),
+            ..Default::default()
+        },
+        max_dist: MaxDist::Pixels(1e6),
+        dist_power: 0.0,
+        continuous_cells,
+        ..Default::default()
+    }
+}
+
+fn positions() -> impl Iterator<Item = Vec2> {
+    (0..600).map(|i| Vec2::new((i * 37 % 700) as f32 + 0.3, (i * 91 % 500) as f32 - 40.7))
+}
+
+// The largest change in any channel between neighboring samples a tenth of a pixel apart
+// along a few rows
+fn largest_jump(config: &Config) -> f32 {
+    let color = |pos: Vec2| shade_float(&sample_geometry(pos, config), config);
+    let mut largest = 0.0f32;
+    for y in [13.5, 97.25, 171.0, 240.75] {
+        let mut previous = color(Vec2::new(0.0, y));
+        for x in 1..2560 {
+            let next = color(Vec2::new(x as f32 * 0.1, y));
+            largest = largest.max((next - previous).abs().max_element());
+            previous = next;
+        }
+    }
+    largest
+}
+
+#[test]
+fn across_starts_from_the_sampled_cell() {
+    let noise = config(0.0).noise;
+    let mut found = 0;
+    for pos in positions() {
+        let (cell, across) = noise.sample_across(pos, 10.0);
+        assert_eq!(cell, sample(pos, &config(0.0)).0, "{pos}");
+        if let Some((other, gap)) = across {
+            assert_ne!(other, cell, "{pos}");
+            assert!((0.0..=10.0).contains(&gap), "{pos} {gap}");
+            found += 1;
+        }
+    }
+    assert!(found > 0 && found < positions().count(), "{found}");
+}
+
+#[test]
+fn wider_gaps_find_more_walls() {
+    let noise = config(0.0).noise;
+    let count = |max_gap| {
+        positions()
+            .filter(|&pos| noise.sample_across(pos, max_gap).1.is_some())
+            .count()
+    };
+    assert_eq!(count(0.0), 0);
+    assert!(count(2.0) < count(8.0), "{} {}", count(2.0), count(8.0));
+}
+
+#[test]
+fn finest_cell_size_shrinks_by_the_growth() {
+    let noise = config(0.0).noise;
+    assert_eq!(noise.finest_cell_size(), Vec2::splat(200.0 / 9.0));
+    let flat = WorleyNoise { depth: 0, ..noise };
+    assert_eq!(flat.finest_cell_size(), Vec2::splat(200.0));
+}
+
+#[test]
+fn off_samples_nothing_and_changes_nothing() {
+    let config = config(0.0);
+    assert_eq!(sample_geometry(Vec2::new(10.0, 20.0), &config).across, None);
+    let mut plain = Buffer::new(64, 48, Default::default());
+    let mut listed = plain.clone();
+    render(&mut plain, &Config::default());
+    render(
+        &mut listed,
+        &Config {
+            continuous_cells: 0.0,
+            ..Default::default()
+        },
+    );
+    assert_eq!(plain.buff, listed.buff);
+}
+
+#[test]
+fn colors_change_continuously_across_cell_walls() {
+    let (popping, continuous) = (largest_jump(&config(0.0)), largest_jump(&config(0.25)));
+    assert!(popping > 40.0, "{popping}");
+    assert!(continuous < popping / 4.0, "{popping} {continuous}");
+}
+
+#[test]
+fn pixels_away_from_walls_keep_their_color() {
+    let (hard, soft) = (config(0.0), config(0.25));
+    let mut unchanged = 0;
+    for pos in positions() {
+        let geometry = sample_geometry(pos, &soft);
+        if geometry.across.is_none() {
+            let color = |config| shade_float(&geometry, config);
+            assert_eq!(color(&hard), color(&soft), "{pos}");
+            unchanged += 1;
+        }
+    }
+    assert!(unchanged > positions().count() / 2, "{unchanged}");
+}
+
+#[test]
+fn the_cache_keeps_the_wall_blend() {
+    let config = config(0.25);
+    let mut field = Buffer::new(48, 32, PixelGeometry::default());
+    compute_field(&mut field, &config, IVec2::ZERO);
+    let mut colorized = Buffer::new(48, 32, Default::default());
+    colorize(&mut colorized, &field, &config, IVec2::ZERO);
+    let (mut direct, mut cached) = (colorized.clone(), colorized.clone());
+    render(&mut direct, &config);
+    RenderCache::new().render(&mut cached, &config);
+    assert_eq!(colorized.buff, direct.buff);
+    assert_eq!(cached.buff, direct.buff);
+}
+
+#[test]
+fn widths_outside_0_to_1_are_rejected() {
+    assert!(config(-0.5).validate().is_err());
+    assert!(config(1.5).validate().is_err());
+    assert!(config(f32::NAN).validate().is_err());
+}
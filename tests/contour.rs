@@ -0,0 +1,91 @@
+use glam::{USizeVec2, Vec2};
+use layered_worley::{Buffer, contours, draw_polyline};
+
+fn field(width: usize, height: usize, f: impl Fn(Vec2) -> f32 + Sync) -> Buffer<f32> {
+    let mut field = Buffer::new(width, height, 0.0);
+    field.par_for_each(|pos, value| *value = f(pos.as_vec2()));
+    field
+}
+
+#[test]
+fn radial_field_gives_concentric_circles() {
+    let center = Vec2::new(20.3, 19.6);
+    let field = field(41, 41, |pos| pos.distance(center));
+    let levels = [4.0, 9.0, 15.0];
+    let lines = contours(&field, &levels);
+    assert_eq!(lines.len(), 3);
+    for (line, level) in lines.iter().zip(levels) {
+        assert_eq!(line.first(), line.last(), "rings close");
+        assert!(line.len() > 4 * level as usize);
+        for point in line {
+            // Linear interpolation cuts the corners of the curve a little
+            let radius = point.distance(center);
+            assert!((radius - level).abs() < 0.1, "{level} {radius}");
+        }
+        // Every point is visited once, besides the closing repeat
+        let mut points = line[1..].to_vec();
+        points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        points.dedup();
+        assert_eq!(points.len(), line.len() - 1);
+    }
+}
+
+#[test]
+fn contours_leaving_the_field_end_at_the_border() {
+    let field = field(10, 6, |pos| pos.x + 0.1 * pos.y);
+    let lines = contours(&field, &[3.25]);
+    assert_eq!(lines.len(), 1);
+    let line = &lines[0];
+    // One point per row, and one where it steps across a column
+    assert_eq!(line.len(), 7);
+    for point in line {
+        assert!((point.x + 0.1 * point.y - 3.25).abs() < 1e-5);
+    }
+    let ends = [line[0].y, line[6].y];
+    assert!(ends == [0.0, 5.0] || ends == [5.0, 0.0]);
+}
+
+#[test]
+fn separate_bumps_give_separate_rings() {
+    let bumps = [Vec2::new(6.0, 6.0), Vec2::new(22.0, 9.0)];
+    let field = field(30, 16, |pos| {
+        bumps
+            .iter()
+            .map(|b| 5.0 - pos.distance(*b))
+            .fold(f32::MIN, f32::max)
+    });
+    let lines = contours(&field, &[2.0]);
+    assert_eq!(lines.len(), 2);
+    for (line, bump) in lines.iter().zip(bumps) {
+        assert_eq!(line.first(), line.last());
+        assert!(line.iter().all(|p| (p.distance(bump) - 3.0).abs() < 0.1));
+    }
+}
+
+#[test]
+fn flat_and_tiny_fields_have_no_contours() {
+    assert!(contours(&field(8, 8, |_| 1.0), &[0.5, 1.0, 2.0]).is_empty());
+    assert!(contours(&field(1, 8, |pos| pos.y), &[3.5]).is_empty());
+    assert!(contours(&field(8, 8, |pos| pos.x), &[]).is_empty());
+}
+
+#[test]
+fn polylines_draw_connected_and_clipped() {
+    let mut buffer = Buffer::new(12, 8, 0u8);
+    draw_polyline(
+        &mut buffer,
+        &[
+            Vec2::new(-3.0, 2.0),
+            Vec2::new(5.0, 2.0),
+            Vec2::new(5.0, 20.0),
+        ],
+        1,
+    );
+    for x in 0..=5 {
+        assert_eq!(buffer.get(USizeVec2::new(x, 2)), Some(1));
+    }
+    for y in 2..8 {
+        assert_eq!(buffer.get(USizeVec2::new(5, y)), Some(1));
+    }
+    assert_eq!(buffer.buff.iter().filter(|&&v| v == 1).count(), 6 + 5);
+}
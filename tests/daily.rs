@@ -0,0 +1,45 @@
+use layered_worley::{Date, daily_seed, seed_from_str};
+
+fn date(year: i32, month: u32, day: u32) -> Date {
+    Date { year, month, day }
+}
+
+#[test]
+fn days_count_from_the_unix_epoch() {
+    assert_eq!(Date::from_days(0), date(1970, 1, 1));
+    assert_eq!(Date::from_days(-1), date(1969, 12, 31));
+    assert_eq!(Date::from_days(11_016), date(2000, 2, 29));
+    assert_eq!(Date::from_days(19_723), date(2024, 1, 1));
+}
+
+#[test]
+fn dates_round_trip_through_text() {
+    for days in (-700_000..800_000).step_by(997) {
+        let day = Date::from_days(days);
+        assert_eq!(day.to_string().parse::<Date>().unwrap(), day);
+    }
+    assert_eq!(date(812, 3, 4).to_string(), "0812-03-04");
+}
+
+#[test]
+fn impossible_dates_are_rejected() {
+    for text in [
+        "2023-02-29",
+        "1900-02-29",
+        "2024-13-01",
+        "2024-04-31",
+        "2024-00-10",
+        "2024-1",
+        "today",
+    ] {
+        assert!(text.parse::<Date>().is_err(), "{text}");
+    }
+    assert!("2000-02-29".parse::<Date>().is_ok());
+}
+
+#[test]
+fn each_day_has_its_own_seed() {
+    let day = date(2026, 10, 16);
+    assert_eq!(daily_seed(day), seed_from_str("2026-10-16"));
+    assert_ne!(daily_seed(day), daily_seed(date(2026, 10, 17)));
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "rayon")]
+
+use glam::{IVec2, U8Vec3, Vec2, Vec3};
+use layered_worley::{
+    Buffer, Config, WorleyNoise, dump_pixels, mip_chain, render, render_float, render_tiled,
+    rgb_from_vec,
+};
+
+fn render_with_threads(config: &Config, threads: usize) -> Vec<U8Vec3> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .unwrap();
+    let mut buffer = Buffer::new(96, 64, U8Vec3::ZERO);
+    pool.install(|| render(&mut buffer, config));
+    buffer.buff
+}
+
+#[test]
+fn fixed_seed_renders_identically_across_runs_and_thread_counts() {
+    let config = Config {
+        noise: WorleyNoise {
+            seed: 0,
+            depth: 4,
+            cells: Vec2::splat(40.0),
+            relaxation_iterations: 1,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let reference = render_with_threads(&config, 1);
+    assert_eq!(reference, render_with_threads(&config, 1));
+    assert_eq!(reference, render_with_threads(&config, 4));
+    assert_eq!(reference, render_with_threads(&config, 7));
+}
+
+// Every parallel path, not just `render`, must be independent of the thread count
+#[test]
+fn parallel_paths_ignore_thread_count() {
+    let config = Config {
+        noise: WorleyNoise {
+            seed: 3,
+            depth: 3,
+            cells: Vec2::splat(24.0),
+            ..Default::default()
+        },
+        color_blend: 0.05,
+        ..Default::default()
+    };
+    let run = |threads: usize| {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        pool.install(|| {
+            let mut tiled = Buffer::new(70, 45, U8Vec3::ZERO);
+            render_tiled(&mut tiled, &config, IVec2::new(3, 8), 16);
+            let mut float = Buffer::new(70, 45, Vec3::ZERO);
+            render_float(&mut float, &config, IVec2::ZERO);
+            let mips: Vec<_> = mip_chain(&tiled, true)
+                .into_iter()
+                .map(|level| level.buff)
+                .collect();
+            (tiled.buff, float.buff, mips)
+        })
+    };
+
+    let reference = run(1);
+    assert_eq!(reference, run(3));
+    assert_eq!(reference, run(8));
+}
+
+// The dump reports the same colors the render writes, row by row
+#[test]
+fn pixel_dump_matches_the_render() {
+    let config = Config {
+        noise: WorleyNoise {
+            seed: 5,
+            depth: 3,
+            cells: Vec2::splat(20.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut buffer = Buffer::new(6, 4, U8Vec3::ZERO);
+    render(&mut buffer, &config);
+
+    let dump = dump_pixels(&config, 6, 10);
+    assert_eq!(dump, dump_pixels(&config, 6, 10));
+    let lines: Vec<_> = dump.lines().collect();
+    assert_eq!(lines.len(), 10);
+    assert!(lines[7].starts_with("1,1 cell "), "{}", lines[7]);
+    for (line, &pixel) in lines.iter().zip(&buffer.buff) {
+        let color = format!("color #{:06x}", rgb_from_vec(pixel));
+        assert!(line.ends_with(&color), "{line} {color}");
+    }
+}
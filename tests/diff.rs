@@ -0,0 +1,62 @@
+use glam::{U8Vec3, USizeVec2, Vec2};
+use layered_worley::{Buffer, Config, DiffStats, WorleyNoise, render};
+
+fn rendered(config: &Config) -> Buffer<U8Vec3> {
+    let mut buffer = Buffer::new(48, 32, U8Vec3::ZERO);
+    render(&mut buffer, config);
+    buffer
+}
+
+fn config() -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 4,
+            depth: 3,
+            cells: Vec2::splat(24.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn identical_renders_have_no_difference() {
+    let (diff, stats) = rendered(&config()).diff(&rendered(&config()), 8.0);
+    assert_eq!(stats, DiffStats::default());
+    assert!(diff.buff.iter().all(|&p| p == U8Vec3::ZERO));
+}
+
+#[test]
+fn differences_are_absolute_and_amplified() {
+    let mut a = Buffer::new(2, 2, U8Vec3::splat(100));
+    let b = Buffer::new(2, 2, U8Vec3::splat(100));
+    a.set(USizeVec2::new(1, 0), U8Vec3::new(90, 103, 100));
+    let (diff, stats) = a.diff(&b, 10.0);
+    assert_eq!(
+        diff.get(USizeVec2::new(1, 0)),
+        Some(U8Vec3::new(100, 30, 0))
+    );
+    assert_eq!(diff.get(USizeVec2::new(0, 0)), Some(U8Vec3::ZERO));
+    assert_eq!(stats.max, 10);
+    assert_eq!(stats.changed, 1);
+    assert!((stats.mean - 13.0 / 12.0).abs() < 1e-6);
+    // Either way round
+    assert_eq!(b.diff(&a, 10.0).1, stats);
+}
+
+#[test]
+fn amplified_differences_saturate() {
+    let a = Buffer::new(1, 1, U8Vec3::splat(0));
+    let b = Buffer::new(1, 1, U8Vec3::splat(200));
+    assert_eq!(a.diff(&b, 8.0).0.buff[0], U8Vec3::splat(255));
+}
+
+#[test]
+fn color_changes_show_up() {
+    let other = Config {
+        color_seed: Some(1),
+        ..config()
+    };
+    let (_, stats) = rendered(&config()).diff(&rendered(&other), 1.0);
+    assert!(stats.changed > 0 && stats.mean > 0.0);
+}
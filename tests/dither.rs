@@ -0,0 +1,82 @@
+use glam::{U8Vec3, Vec2};
+use image::{GrayImage, Luma};
+use layered_worley::{Buffer, Config, DitherTexture, WorleyNoise, render};
+
+fn save_tile(dir: &tempfile::TempDir, value: impl Fn(u32, u32) -> u8) -> DitherTexture {
+    let path = dir.path().join("tile.png");
+    GrayImage::from_fn(4, 2, |x, y| Luma([value(x, y)]))
+        .save(&path)
+        .unwrap();
+    DitherTexture::load(&path).unwrap()
+}
+
+fn rendered(dither: Option<DitherTexture>) -> Vec<U8Vec3> {
+    let config = Config {
+        noise: WorleyNoise {
+            seed: 6,
+            depth: 2,
+            cells: Vec2::splat(24.0),
+            ..Default::default()
+        },
+        dither,
+        ..Default::default()
+    };
+    let mut buffer = Buffer::new(40, 30, U8Vec3::ZERO);
+    render(&mut buffer, &config);
+    buffer.buff
+}
+
+#[test]
+fn dither_texture_tiles_across_the_plane() {
+    let dir = tempfile::tempdir().unwrap();
+    let texture = save_tile(&dir, |x, y| (x * 2 + y) as u8 * 32);
+    assert_eq!((texture.width, texture.height), (4, 2));
+    for (pos, expected) in [
+        (Vec2::new(1.5, 1.0), 3),
+        (Vec2::new(5.0, 3.0), 3),
+        (Vec2::new(-3.0, -1.0), 3),
+        (Vec2::new(7.9, 0.0), 6),
+    ] {
+        assert_eq!(
+            texture.threshold(pos),
+            expected as f32 * 32.0 / 256.0,
+            "{pos}"
+        );
+    }
+}
+
+#[test]
+fn dither_only_ever_rounds_up_by_one() {
+    let dir = tempfile::tempdir().unwrap();
+    let plain = rendered(None);
+    assert_eq!(rendered(Some(save_tile(&dir, |_, _| 0))), plain);
+
+    let dithered = rendered(Some(save_tile(&dir, |x, y| {
+        [0, 255][((x + y) % 2) as usize]
+    })));
+    let mut raised = 0;
+    for (d, p) in dithered.iter().zip(&plain) {
+        let diff = d.as_ivec3() - p.as_ivec3();
+        assert!(diff.min_element() >= 0 && diff.max_element() <= 1);
+        raised += (diff.max_element() > 0) as usize;
+    }
+    assert!(raised > 0);
+}
+
+#[test]
+fn mismatched_tiles_fail_validation() {
+    let tile = |width, height, len| DitherTexture {
+        width,
+        height,
+        thresholds: vec![0.5; len],
+    };
+    assert!(tile(2, 3, 6).validate().is_ok());
+    for bad in [tile(0, 3, 0), tile(2, 0, 0), tile(2, 3, 5), tile(2, 3, 7)] {
+        assert!(bad.validate().is_err(), "{bad:?}");
+        let config = Config {
+            dither: Some(bad),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
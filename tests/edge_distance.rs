@@ -0,0 +1,69 @@
+use glam::{IVec2, Vec2};
+use layered_worley::{edge_distance, worley_center};
+
+const CELL: Vec2 = Vec2::splat(32.0);
+const SEED: u64 = 21;
+
+fn feature(cell: IVec2) -> Vec2 {
+    (cell.as_vec2() + worley_center(cell, SEED)) * CELL
+}
+
+// The feature point nearest `pos` and the one nearest to that, from a wide block
+fn pair(pos: Vec2) -> (Vec2, Vec2) {
+    let base = (pos / CELL).floor().as_ivec2();
+    let mut points: Vec<Vec2> = (-4..=4)
+        .flat_map(|y| (-4..=4).map(move |x| feature(base + IVec2::new(x, y))))
+        .collect();
+    points.sort_by(|a, b| a.distance(pos).total_cmp(&b.distance(pos)));
+    let a = points[0];
+    let b = points[1..]
+        .iter()
+        .copied()
+        .min_by(|p, q| p.distance(a).total_cmp(&q.distance(a)))
+        .unwrap();
+    (a, b)
+}
+
+#[test]
+fn feature_point_is_half_way_from_its_nearest_wall() {
+    for i in 0..20 {
+        let (a, b) = pair(Vec2::new(i as f32 * 37.3, i as f32 * -11.9));
+        let edge = edge_distance(a, CELL, SEED);
+        assert!((edge - a.distance(b) / 2.0).abs() < 1e-3, "{edge}");
+    }
+}
+
+#[test]
+fn midpoint_between_neighbors_is_on_the_wall() {
+    for i in 0..20 {
+        let (a, b) = pair(Vec2::new(i as f32 * 41.7, i as f32 * 13.1));
+        let edge = edge_distance((a + b) / 2.0, CELL, SEED);
+        assert!(edge.abs() < 1e-3, "{edge}");
+    }
+}
+
+#[test]
+fn edge_distance_matches_brute_force() {
+    for i in 0..200 {
+        let pos = Vec2::new((i * 97 % 300) as f32 - 150.3, (i * 61 % 300) as f32 - 100.7);
+        let base = (pos / CELL).floor().as_ivec2();
+        let points: Vec<Vec2> = (-4..=4)
+            .flat_map(|y| (-4..=4).map(move |x| feature(base + IVec2::new(x, y))))
+            .collect();
+        let a = *points
+            .iter()
+            .min_by(|p, q| p.distance(pos).total_cmp(&q.distance(pos)))
+            .unwrap();
+        let expected = points
+            .iter()
+            .filter(|&&b| b != a)
+            .map(|&b| ((a + b) / 2.0 - pos).dot((b - a).normalize()))
+            .fold(f32::INFINITY, f32::min);
+        let edge = edge_distance(pos, CELL, SEED);
+        assert!(edge >= 0.0);
+        assert!(
+            (edge - expected).abs() < 1e-3,
+            "{pos}: {edge} vs {expected}"
+        );
+    }
+}
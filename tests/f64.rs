@@ -0,0 +1,85 @@
+use glam::{DVec2, Vec2};
+use layered_worley::WorleyNoise;
+
+// Far enough out that f32 can't represent anything between whole numbers
+const FAR: f64 = 3.0e7;
+
+#[test]
+fn f64_matches_f32_near_the_origin() {
+    let noise = WorleyNoise {
+        seed: 9,
+        depth: 3,
+        cells: Vec2::splat(64.0),
+        ..Default::default()
+    };
+    let mut mismatches = 0;
+    for y in (0..200).step_by(5) {
+        for x in (0..200).step_by(5) {
+            let pos = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+            let (cell, dist) = noise.sample(pos);
+            let (cell_f64, dist_f64) = noise.sample_f64(pos.as_dvec2());
+            if cell != cell_f64 {
+                mismatches += 1;
+            } else {
+                assert!(
+                    (dist - dist_f64).abs() < 1e-2,
+                    "{dist} vs {dist_f64} at {pos}"
+                );
+            }
+        }
+    }
+    // Rounding can only flip the odd sample sitting on a cell boundary
+    assert!(mismatches <= 4, "{mismatches} cells differ");
+}
+
+#[test]
+fn f64_distance_resolves_sub_pixel_steps_far_from_the_origin() {
+    let noise = WorleyNoise::default();
+    let cell_size = Vec2::splat(64.0);
+    let step = 0.125;
+
+    let far_f32: Vec<f32> = (0..64)
+        .map(|i| {
+            noise
+                .worley(Vec2::splat((FAR + i as f64 * step) as f32), cell_size)
+                .1
+        })
+        .collect();
+    let far_f64: Vec<f32> = (0..64)
+        .map(|i| {
+            let pos = DVec2::splat(FAR + i as f64 * step);
+            noise.worley_f64(pos, cell_size.as_dvec2()).1
+        })
+        .collect();
+
+    // f32 collapses runs of samples onto the same position, so distances stall and jump
+    assert!(far_f32.windows(2).any(|w| w[0] == w[1]));
+    // In f64 every step moves, and by no more than the step length along both axes
+    let max_change = (step * 2f64.sqrt()) as f32 + 1e-3;
+    for w in far_f64.windows(2) {
+        assert!(w[0] != w[1]);
+        assert!((w[0] - w[1]).abs() <= max_change, "{} -> {}", w[0], w[1]);
+    }
+}
+
+#[test]
+fn f64_far_samples_match_the_periodic_image_near_the_origin() {
+    let noise = WorleyNoise {
+        seed: 3,
+        depth: 2,
+        cells: Vec2::splat(32.0),
+        toroidal_domain: Some(Vec2::splat(256.0)),
+        ..Default::default()
+    };
+    // A whole number of domains away, so the pattern repeats exactly
+    let offset = DVec2::splat(256.0 * (FAR / 256.0).round());
+    for y in (0..256).step_by(9) {
+        for x in (0..256).step_by(9) {
+            let pos = Vec2::new(x as f32 + 0.375, y as f32 + 0.625);
+            let (cell, dist) = noise.sample(pos);
+            let (far_cell, far_dist) = noise.sample_f64(pos.as_dvec2() + offset);
+            assert_eq!(cell, far_cell, "cell differs at {pos}");
+            assert!((dist - far_dist).abs() < 1e-2);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+#![cfg(feature = "fixed")]
+
+use glam::{IVec2, Vec2};
+use layered_worley::{
+    DistanceMetric, Fixed, FixedVec2, WorleyNoise,
+    fixed::{self, TOLERANCE},
+};
+
+fn noise(metric: DistanceMetric) -> WorleyNoise {
+    WorleyNoise {
+        seed: 41,
+        depth: 4,
+        cells: Vec2::splat(192.0),
+        metric,
+        ..Default::default()
+    }
+}
+
+fn positions() -> impl Iterator<Item = Vec2> {
+    (0..4000).map(|i| Vec2::new((i * 37 % 2000) as f32 - 700.3, (i * 91 % 1500) as f32 + 0.6))
+}
+
+#[test]
+fn fixed_arithmetic() {
+    let half = Fixed::from_f32(0.5);
+    assert_eq!(half, Fixed(1 << 15));
+    assert_eq!(Fixed::from_f32(-0.5), Fixed(-(1 << 15)));
+    assert_eq!(Fixed::from_int(3) * half, Fixed::from_f32(1.5));
+    assert_eq!(
+        Fixed::from_int(3) / Fixed::from_int(4),
+        Fixed::from_f32(0.75)
+    );
+    assert_eq!(Fixed::from_f32(-1.25).floor_int(), -2);
+    assert_eq!(Fixed::from_f32(1e9), Fixed::MAX);
+
+    let delta = FixedVec2::from_vec2(Vec2::new(3.0, -4.0));
+    assert_eq!(
+        DistanceMetric::Euclidean.distance_fixed(delta),
+        Fixed::from_int(5)
+    );
+    assert_eq!(
+        DistanceMetric::Manhattan.distance_fixed(delta),
+        Fixed::from_int(7)
+    );
+    assert_eq!(
+        DistanceMetric::Chebyshev.distance_fixed(delta),
+        Fixed::from_int(4)
+    );
+}
+
+#[test]
+fn centers_round_the_float_centers() {
+    let noise = noise(DistanceMetric::Euclidean);
+    for x in -20..20 {
+        let cell = IVec2::new(x, x * 7 - 3);
+        let error = noise.center_fixed(cell).to_vec2() - noise.center(cell);
+        assert!(error.abs().max_element() <= 1.0 / 65536.0, "{cell} {error}");
+    }
+}
+
+#[test]
+fn single_level_matches_the_float_reference() {
+    for metric in [DistanceMetric::Euclidean, DistanceMetric::Manhattan] {
+        let noise = noise(metric);
+        let cell_size = Vec2::new(24.0, 31.5);
+        for pos in positions() {
+            let (cell, dist) = noise.worley(pos, cell_size);
+            let fixed =
+                noise.worley_fixed(FixedVec2::from_vec2(pos), FixedVec2::from_vec2(cell_size));
+            if fixed.0 == cell {
+                assert!((fixed.1.to_f32() - dist).abs() < TOLERANCE, "{pos}");
+            }
+        }
+    }
+}
+
+// Rounding may pick the other of two nearly equidistant points, but only rarely, and
+// the distances otherwise stay within the documented tolerance
+#[test]
+fn hierarchy_stays_within_tolerance() {
+    for metric in [DistanceMetric::Euclidean, DistanceMetric::Manhattan] {
+        let noise = noise(metric);
+        let (mut same, mut close) = (0, 0);
+        for pos in positions() {
+            let (cell, dist) = noise.sample(pos);
+            let (fixed_cell, fixed_dist) = noise.sample_fixed(FixedVec2::from_vec2(pos));
+            same += (fixed_cell == cell) as usize;
+            close += ((fixed_dist.to_f32() - dist).abs() < fixed::TOLERANCE) as usize;
+        }
+        let most = positions().count() * 99 / 100;
+        assert!(same >= most, "{metric:?} {same}");
+        assert!(close >= most, "{metric:?} {close}");
+    }
+}
@@ -0,0 +1,52 @@
+use glam::{IVec2, U8Vec3, Vec2, Vec3};
+use image::{GrayImage, Luma};
+use layered_worley::{
+    Buffer, Config, DitherTexture, ToneMap, WorleyNoise, quantize, render_float, render_region,
+};
+
+fn config(dither: Option<DitherTexture>) -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 14,
+            depth: 3,
+            cells: Vec2::splat(20.0),
+            ..Default::default()
+        },
+        color_blend: 0.05,
+        tonemap: ToneMap::Reinhard,
+        dither,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn quantized_float_render_matches_direct_render() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("tile.png");
+    GrayImage::from_fn(3, 5, |x, y| Luma([(x * 70 + y * 40) as u8]))
+        .save(&path)
+        .unwrap();
+    let texture = DitherTexture::load(&path).unwrap();
+
+    let origin = IVec2::new(13, 4);
+    for config in [config(None), config(Some(texture))] {
+        let mut float = Buffer::new(36, 24, Vec3::ZERO);
+        render_float(&mut float, &config, origin);
+        let mut direct = Buffer::new(36, 24, U8Vec3::ZERO);
+        render_region(&mut direct, &config, origin);
+        assert_eq!(quantize(&float, &config, origin).buff, direct.buff);
+    }
+}
+
+#[test]
+fn float_render_keeps_sub_step_precision() {
+    let mut float = Buffer::new(36, 24, Vec3::ZERO);
+    render_float(&mut float, &config(None), IVec2::ZERO);
+    assert!(
+        float.buff.iter().all(|c| c.is_finite()
+            && c.cmpge(Vec3::ZERO).all()
+            && c.cmple(Vec3::splat(255.0)).all())
+    );
+    // The falloff and tone curve leave fractions that 8 bits would truncate
+    assert!(float.buff.iter().any(|c| c.fract() != Vec3::ZERO));
+}
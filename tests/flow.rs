@@ -0,0 +1,44 @@
+use glam::{IVec2, Vec2};
+use layered_worley::{Buffer, Config, FlowMode, WorleyNoise, cell_info, render_flow};
+
+fn noise() -> WorleyNoise {
+    WorleyNoise {
+        seed: 13,
+        cells: Vec2::splat(32.0),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn flow_points_at_the_nearest_feature_point() {
+    let noise = noise();
+    for i in 0..100 {
+        let pos = Vec2::new(i as f32 * 5.3, i as f32 * 2.9);
+        let (cell, dist) = noise.worley(pos, noise.cells);
+        let center = cell_info(cell, noise.cells, noise.seed).center;
+        let toward = noise.sample_flow(pos, FlowMode::Toward);
+        if dist > 1e-3 {
+            assert!((toward.length() - 1.0).abs() < 1e-4);
+            assert!((pos + toward * dist - center).length() < 1e-2);
+        }
+        let swirl = noise.sample_flow(pos, FlowMode::Swirl);
+        assert!(swirl.dot(toward).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn flow_image_encodes_x_and_y_in_red_and_green() {
+    let config = Config {
+        noise: noise(),
+        ..Default::default()
+    };
+    let mut field = Buffer::new(20, 10, Vec2::ZERO);
+    render_flow(&mut field, &config, IVec2::new(3, 4), FlowMode::Swirl);
+    let image = field.to_flow_image();
+    for (i, pixel) in image.pixels().enumerate() {
+        let flow = field.buff[i];
+        let decoded = Vec2::new(pixel.0[0] as f32, pixel.0[1] as f32) / 255.0 * 2.0 - 1.0;
+        assert!((decoded - flow).abs().max_element() <= 1.0 / 255.0);
+        assert_eq!(pixel.0[2], 0);
+    }
+}
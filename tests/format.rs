@@ -0,0 +1,88 @@
+use glam::U8Vec3;
+use image::{ImageFormat, ImageReader};
+use layered_worley::{Buffer, OutputFormat, WorleyError};
+
+#[test]
+fn extensions_pick_formats() {
+    let cases = [
+        ("a.png", OutputFormat::Png),
+        ("a.jpg", OutputFormat::Jpeg),
+        ("a.JPEG", OutputFormat::Jpeg),
+        ("a.bmp", OutputFormat::Bmp),
+        ("a.tiff", OutputFormat::Tiff),
+        ("a.tif", OutputFormat::Tiff),
+        ("a.exr", OutputFormat::Exr),
+        ("dir.v2/a.npy", OutputFormat::Npy),
+        ("a.svg", OutputFormat::Svg),
+    ];
+    for (path, format) in cases {
+        assert_eq!(OutputFormat::from_path(path).unwrap(), format, "{path}");
+    }
+    for path in ["a.gif", "a.webp", "a", "a."] {
+        assert!(
+            matches!(
+                OutputFormat::from_path(path),
+                Err(WorleyError::InvalidParameter { .. } | WorleyError::UnsupportedFormat(_))
+            ),
+            "{path}"
+        );
+    }
+}
+
+#[test]
+fn unsupported_extensions_name_the_choices() {
+    let message = OutputFormat::from_path("out.gif").unwrap_err().to_string();
+    assert!(message.contains("gif"), "{message}");
+    assert!(message.contains("png, jpg, bmp, tiff, exr"), "{message}");
+}
+
+// The encoder is read back from the file's contents, not its name
+#[test]
+fn each_extension_routes_to_its_encoder() {
+    let buffer = Buffer::new(5, 3, U8Vec3::new(200, 100, 50));
+    let dir = tempfile::tempdir().unwrap();
+    let cases = [
+        ("png", ImageFormat::Png),
+        ("jpg", ImageFormat::Jpeg),
+        ("jpeg", ImageFormat::Jpeg),
+        ("bmp", ImageFormat::Bmp),
+        ("tiff", ImageFormat::Tiff),
+        ("exr", ImageFormat::OpenExr),
+    ];
+    for (extension, expected) in cases {
+        let path = dir.path().join(format!("out.{extension}"));
+        buffer.save(&path).unwrap();
+        let reader = ImageReader::open(&path)
+            .unwrap()
+            .with_guessed_format()
+            .unwrap();
+        assert_eq!(reader.format(), Some(expected), "{extension}");
+        let image = reader.decode().unwrap().to_rgb8();
+        assert_eq!(image.dimensions(), (5, 3), "{extension}");
+        let pixel = image.get_pixel(2, 1).0;
+        assert!(
+            pixel
+                .iter()
+                .zip([200, 100, 50])
+                .all(|(&a, b)| a.abs_diff(b) <= 4),
+            "{extension} {pixel:?}"
+        );
+    }
+}
+
+#[test]
+fn explicit_format_overrides_the_extension() {
+    let buffer = Buffer::new(2, 2, U8Vec3::ONE);
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("out.img");
+    buffer.save_as(&path, OutputFormat::Bmp).unwrap();
+    let reader = ImageReader::open(&path)
+        .unwrap()
+        .with_guessed_format()
+        .unwrap();
+    assert_eq!(reader.format(), Some(ImageFormat::Bmp));
+
+    assert!(buffer.save(&path).is_err());
+    assert!(buffer.save(dir.path().join("out.npy")).is_err());
+    assert!(buffer.save_as(&path, OutputFormat::Svg).is_err());
+}
@@ -0,0 +1,34 @@
+use std::path::Path;
+
+use layered_worley::{ffmpeg_args, frame_file_name};
+
+#[test]
+fn frame_names_sort_in_order() {
+    assert_eq!(frame_file_name(7, 60), "frame_0007.png");
+    assert_eq!(frame_file_name(0, 1), "frame_0000.png");
+    assert_eq!(frame_file_name(12_345, 20_000), "frame_12345.png");
+    assert_eq!(frame_file_name(3, 20_000), "frame_00003.png");
+
+    let names: Vec<_> = (0..10_001).map(|f| frame_file_name(f, 10_001)).collect();
+    assert!(names.is_sorted());
+}
+
+#[test]
+fn ffmpeg_reads_the_frame_names() {
+    for frames in [1, 60, 10_000, 10_001] {
+        let args = ffmpeg_args(Path::new("out"), frames, 24, Path::new("loop.mp4"));
+        let input = &args[args.iter().position(|a| a == "-i").unwrap() + 1];
+        let digits = frame_file_name(0, frames).len() - "frame_.png".len();
+        assert_eq!(
+            *input,
+            Path::new("out")
+                .join(format!("frame_%0{digits}d.png"))
+                .to_string_lossy()
+        );
+        assert_eq!(
+            args[args.iter().position(|a| a == "-framerate").unwrap() + 1],
+            "24"
+        );
+        assert_eq!(args.last().unwrap(), "loop.mp4");
+    }
+}
@@ -0,0 +1,146 @@
+//! Renders small fixed-parameter images and compares them against the PNGs
+//! checked in under `tests/golden`. Run with `BLESS=1` to regenerate them
+//! after an intentional visual change.
+//!
+//! The geometry goldens allow a little float drift. The pipeline goldens don't: they
+//! run everything from the palette pick to the packed pixels and must match exactly, so
+//! any nondeterminism in the color stage fails them. `--dump-first-pixels` helps find
+//! where a platform that fails them diverges.
+
+use std::path::PathBuf;
+
+use glam::{U8Vec3, Vec2};
+use image::RgbImage;
+use layered_worley::{
+    Buffer, CellColoring, Config, MaxDist, WorleyNoise, render, render_into, unpack_rgb,
+};
+
+const SIZE: usize = 64;
+// Mean absolute error per channel, in 8-bit steps, tolerated before failing
+const TOLERANCE: f64 = 0.5;
+
+fn golden_config() -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 0x5eed,
+            depth: 3,
+            growth: Vec2::splat(3.0),
+            cells: Vec2::new(32.0, 32.0),
+            ..Default::default()
+        },
+        max_dist: MaxDist::Pixels(20.0),
+        ..Default::default()
+    }
+}
+
+fn check_golden(name: &str, config: &Config) {
+    let mut buffer = Buffer::new(SIZE, SIZE, U8Vec3::ZERO);
+    render(&mut buffer, config);
+    compare_golden(name, &buffer.to_image(), TOLERANCE);
+}
+
+// Renders through `render_into`, the packed output GUIs use, twice to catch any
+// nondeterminism within a run, and compares exactly
+fn check_pipeline_golden(name: &str, config: &Config) {
+    let packed = || {
+        let mut out = vec![0; SIZE * SIZE];
+        render_into(&mut out, SIZE, SIZE, config).unwrap();
+        out
+    };
+    let first = packed();
+    assert_eq!(
+        first,
+        packed(),
+        "{name} rendered differently the second time"
+    );
+    let buffer = Buffer {
+        buff: first.into_iter().map(unpack_rgb).collect(),
+        width: SIZE,
+        height: SIZE,
+    };
+    compare_golden(name, &buffer.to_image(), 0.0);
+}
+
+fn compare_golden(name: &str, actual: &RgbImage, tolerance: f64) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{name}.png"));
+
+    if std::env::var_os("BLESS").is_some() {
+        actual.save(&path).expect("Failed to bless golden image");
+        return;
+    }
+
+    let expected: RgbImage = image::open(&path)
+        .unwrap_or_else(|e| panic!("Missing golden {}: {e} (run with BLESS=1)", path.display()))
+        .to_rgb8();
+
+    assert_eq!(expected.dimensions(), actual.dimensions());
+    let error = expected
+        .as_raw()
+        .iter()
+        .zip(actual.as_raw())
+        .map(|(a, b)| a.abs_diff(*b) as f64)
+        .sum::<f64>()
+        / expected.as_raw().len() as f64;
+    assert!(
+        error <= tolerance,
+        "{name} differs from golden (mean abs error {error:.3}), run with BLESS=1 if intended"
+    );
+}
+
+#[test]
+fn golden_default() {
+    check_golden("default", &golden_config());
+}
+
+#[test]
+fn golden_deep() {
+    check_golden(
+        "deep",
+        &Config {
+            noise: WorleyNoise {
+                depth: 6,
+                growth: Vec2::splat(2.0),
+                ..golden_config().noise
+            },
+            ..golden_config()
+        },
+    );
+}
+
+#[test]
+fn golden_relaxed() {
+    check_golden(
+        "relaxed",
+        &Config {
+            noise: WorleyNoise {
+                relaxation_iterations: 2,
+                ..golden_config().noise
+            },
+            ..golden_config()
+        },
+    );
+}
+
+fn pipeline_config(cell_coloring: CellColoring) -> Config {
+    Config {
+        color_seed: Some(77),
+        dist_power: 2.0,
+        cell_coloring,
+        ..golden_config()
+    }
+}
+
+#[test]
+fn golden_pipeline_flat() {
+    check_pipeline_golden("pipeline_flat", &pipeline_config(CellColoring::Flat));
+}
+
+#[test]
+fn golden_pipeline_dithered() {
+    check_pipeline_golden(
+        "pipeline_dithered",
+        &pipeline_config(CellColoring::Dithered),
+    );
+}
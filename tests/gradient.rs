@@ -0,0 +1,101 @@
+use glam::Vec2;
+use layered_worley::{DistanceMetric, MetricsPerLevel, PointsPerLevel, WorleyNoise};
+
+const STEP: f32 = 0.01;
+
+fn noise(depth: usize) -> WorleyNoise {
+    WorleyNoise {
+        seed: 31,
+        depth,
+        cells: Vec2::splat(64.0),
+        base_level: Some(depth),
+        ..Default::default()
+    }
+}
+
+fn positions() -> impl Iterator<Item = Vec2> {
+    (0..400).map(|i| Vec2::new((i * 37 % 500) as f32 + 0.3, (i * 91 % 300) as f32 - 40.7))
+}
+
+// Finite differences straddling a cell wall disagree, so allow a few misses
+fn agreeing(noise: &WorleyNoise) -> usize {
+    positions()
+        .filter(|&pos| {
+            let analytic = noise.sample_gradient_analytic(pos, STEP);
+            let numeric = noise.sample_gradient(pos, STEP);
+            (analytic - numeric).length() < 1e-2
+        })
+        .count()
+}
+
+#[test]
+fn single_level_matches_finite_differences() {
+    let noise = noise(0);
+    assert!(agreeing(&noise) > 390, "{}", agreeing(&noise));
+    for pos in positions() {
+        let gradient = noise.sample_gradient_analytic(pos, STEP);
+        assert!((gradient.length() - 1.0).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn hierarchy_matches_finite_differences() {
+    let noise = noise(2);
+    assert!(agreeing(&noise) > 380, "{}", agreeing(&noise));
+}
+
+#[test]
+fn finest_level_points_per_cell_match_finite_differences() {
+    let noise = WorleyNoise {
+        points_per_level: PointsPerLevel::new(&[1, 6]).unwrap(),
+        ..noise(1)
+    };
+    noise.validate().unwrap();
+    assert!(agreeing(&noise) > 380, "{}", agreeing(&noise));
+}
+
+// The finest level measures with its own metric, so the coarse level's walls don't
+// decide which point the gradient points away from
+#[test]
+fn per_level_metrics_match_finite_differences() {
+    let uniform = agreeing(&noise(1));
+    let noise = WorleyNoise {
+        metrics_per_level: MetricsPerLevel::new(&[
+            DistanceMetric::Chebyshev,
+            DistanceMetric::Euclidean,
+        ])
+        .unwrap(),
+        ..noise(1)
+    };
+    noise.validate().unwrap();
+    assert!(
+        agreeing(&noise) >= uniform,
+        "{} {uniform}",
+        agreeing(&noise)
+    );
+}
+
+#[test]
+fn finest_level_without_weight_is_flat() {
+    let noise = WorleyNoise {
+        base_level: None,
+        ..noise(2)
+    };
+    for pos in positions() {
+        assert_eq!(noise.sample_gradient_analytic(pos, STEP), Vec2::ZERO);
+    }
+}
+
+#[test]
+fn other_metrics_fall_back_to_finite_differences() {
+    let noise = WorleyNoise {
+        metric: DistanceMetric::Manhattan,
+        ..noise(1)
+    };
+    for pos in positions() {
+        assert_eq!(
+            noise.sample_gradient_analytic(pos, STEP),
+            noise.sample_gradient(pos, STEP)
+        );
+    }
+}
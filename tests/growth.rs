@@ -0,0 +1,43 @@
+use glam::Vec2;
+use layered_worley::{WorleyNoise, hierarchical_worley};
+
+fn noise(growth: Vec2) -> WorleyNoise {
+    WorleyNoise {
+        seed: 17,
+        depth: 4,
+        growth,
+        cells: Vec2::splat(64.0),
+        ..Default::default()
+    }
+}
+
+fn positions() -> impl Iterator<Item = Vec2> {
+    (0..400).map(|i| Vec2::new((i * 37 % 500) as f32 + 0.3, (i * 91 % 300) as f32 - 40.7))
+}
+
+#[test]
+fn equal_components_match_scalar_growth() {
+    let noise = noise(Vec2::splat(2.5));
+    for pos in positions() {
+        let scalar = hierarchical_worley(pos, Vec2::splat(64.0), 17, 4, 2.5);
+        assert_eq!(noise.sample(pos), scalar);
+    }
+}
+
+#[test]
+fn unequal_components_change_the_finer_levels() {
+    let even = noise(Vec2::splat(3.0));
+    let stretched = noise(Vec2::new(3.0, 1.5));
+    let differ = positions()
+        .filter(|&pos| even.sample(pos).1 != stretched.sample(pos).1)
+        .count();
+    assert!(differ > 300, "{differ}");
+}
+
+#[test]
+fn both_components_must_be_positive() {
+    assert!(noise(Vec2::new(3.0, 2.0)).validate().is_ok());
+    assert!(noise(Vec2::new(3.0, 0.0)).validate().is_err());
+    assert!(noise(Vec2::new(-1.0, 3.0)).validate().is_err());
+    assert!(noise(Vec2::new(3.0, f32::NAN)).validate().is_err());
+}
@@ -0,0 +1,85 @@
+use glam::IVec2;
+use layered_worley::HashMixer;
+
+// Cheap deterministic inputs, so failures reproduce
+fn lcg(state: &mut u64) -> u64 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *state
+}
+
+// For each of the 128 input bits (x, y, seed), the fraction of output bits that flip
+fn avalanche(mixer: HashMixer) -> Vec<f64> {
+    const SAMPLES: usize = 2000;
+    let mut state = 1;
+    let mut flips = vec![0u32; 128];
+    for _ in 0..SAMPLES {
+        let r = lcg(&mut state);
+        let cell = IVec2::new(r as i32, (r >> 32) as i32);
+        let seed = lcg(&mut state);
+        let base = mixer.hash(cell, seed);
+        for (bit, flips) in flips.iter_mut().enumerate() {
+            let flipped = match bit {
+                0..32 => mixer.hash(cell ^ IVec2::new(1 << bit, 0), seed),
+                32..64 => mixer.hash(cell ^ IVec2::new(0, 1 << (bit - 32)), seed),
+                _ => mixer.hash(cell, seed ^ (1 << (bit - 64))),
+            };
+            *flips += (base ^ flipped).count_ones();
+        }
+    }
+    flips
+        .into_iter()
+        .map(|f| f as f64 / (SAMPLES * 64) as f64)
+        .collect()
+}
+
+// Chi-square of the low 4 bits over a 256x256 block of neighboring cells
+fn chi_square(mixer: HashMixer) -> f64 {
+    let mut counts = [0u32; 16];
+    for y in 0..256 {
+        for x in 0..256 {
+            counts[(mixer.hash(IVec2::new(x, y), 42) & 15) as usize] += 1;
+        }
+    }
+    let expected = 256.0 * 256.0 / 16.0;
+    counts
+        .iter()
+        .map(|&c| (c as f64 - expected).powi(2) / expected)
+        .sum()
+}
+
+#[test]
+fn splitmix_avalanches_every_input_bit() {
+    for (bit, rate) in avalanche(HashMixer::SplitMix).into_iter().enumerate() {
+        assert!((0.48..0.52).contains(&rate), "bit {bit}: {rate}");
+    }
+}
+
+// Documents the weakness that motivated `HashMixer::SplitMix`. If this starts failing
+// because the legacy hash got better, every existing seed has changed.
+#[test]
+fn legacy_avalanche_is_weak_on_high_bits() {
+    let rates = avalanche(HashMixer::Legacy);
+    assert!((0.45..0.6).contains(&rates[0]), "x bit 0: {}", rates[0]);
+    assert!(rates[31] < 0.35, "x bit 31: {}", rates[31]);
+    assert!(rates[127] < 0.1, "seed bit 63: {}", rates[127]);
+}
+
+#[test]
+fn low_bits_are_uniform_over_neighboring_cells() {
+    // 15 degrees of freedom; 37.7 is the 0.1% critical value
+    for mixer in [HashMixer::Legacy, HashMixer::SplitMix] {
+        let chi = chi_square(mixer);
+        assert!(chi < 37.7, "{mixer:?}: {chi}");
+    }
+}
+
+#[test]
+fn legacy_is_the_default() {
+    assert_eq!(HashMixer::default(), HashMixer::Legacy);
+    assert_eq!(
+        HashMixer::Legacy.hash(IVec2::new(3, -7), 9),
+        layered_worley::cell_hash(IVec2::new(3, -7), 9)
+    );
+}
@@ -0,0 +1,79 @@
+use glam::{IVec2, U8Vec3, USizeVec2, Vec2};
+use layered_worley::{
+    Buffer, Config, Histogram, MaxDist, PixelGeometry, RenderCache, WorleyNoise, compute_field,
+};
+
+fn config(max_dist: f32) -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 5,
+            depth: 3,
+            cells: Vec2::splat(32.0),
+            ..Default::default()
+        },
+        max_dist: MaxDist::Pixels(max_dist),
+        ..Default::default()
+    }
+}
+
+fn field(config: &Config) -> Buffer<PixelGeometry> {
+    let mut field = Buffer::new(48, 32, PixelGeometry::default());
+    compute_field(&mut field, config, IVec2::ZERO);
+    field
+}
+
+#[test]
+fn every_pixel_is_counted() {
+    let config = config(20.0);
+    let histogram = Histogram::from_field(&field(&config), &config, 30);
+    assert_eq!(histogram.total(), 48 * 32);
+}
+
+#[test]
+fn smaller_max_dist_shifts_the_distribution_up() {
+    let wide = config(100.0);
+    let narrow = config(10.0);
+    let field = field(&wide);
+    let wide = Histogram::from_field(&field, &wide, 30);
+    let narrow = Histogram::from_field(&field, &narrow, 30);
+    assert!(wide.quantile(0.5) < narrow.quantile(0.5));
+    assert!(wide.count_from(1.0) <= narrow.count_from(1.0));
+}
+
+#[test]
+fn quantiles_and_counts() {
+    let mut histogram = Histogram::new(10, 1.0);
+    for value in [0.05, 0.15, 0.15, 0.55, 0.95, 3.0] {
+        histogram.add(value);
+    }
+    assert_eq!(histogram.clipped, 1);
+    assert_eq!(histogram.total(), 6);
+    assert!((histogram.quantile(0.5) - 0.2).abs() < 1e-6);
+    assert_eq!(histogram.quantile(1.0), 1.0);
+    assert_eq!(histogram.count_from(0.5), 3);
+}
+
+#[test]
+fn cache_geometry_matches_compute_field() {
+    let config = config(20.0);
+    let mut cache = RenderCache::new();
+    assert!(cache.geometry().is_none());
+    let mut buffer = Buffer::new(48, 32, U8Vec3::ZERO);
+    cache.render(&mut buffer, &config);
+    let cached = Histogram::from_field(cache.geometry().unwrap(), &config, 30);
+    assert_eq!(cached, Histogram::from_field(&field(&config), &config, 30));
+}
+
+#[test]
+fn drawing_stays_inside_the_panel() {
+    let mut histogram = Histogram::new(4, 1.0);
+    histogram.add(0.1);
+    let mut buffer = Buffer::new(20, 20, U8Vec3::splat(1));
+    histogram.draw(&mut buffer, USizeVec2::new(4, 4), USizeVec2::new(8, 8));
+    for y in 0..20 {
+        for x in 0..20 {
+            let inside = (4..12).contains(&x) && (4..12).contains(&y);
+            assert_eq!(buffer.buff[y * 20 + x] != U8Vec3::splat(1), inside);
+        }
+    }
+}
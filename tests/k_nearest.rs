@@ -0,0 +1,48 @@
+use glam::{IVec2, Vec2};
+use layered_worley::{k_nearest, worley, worley_center};
+
+// Every feature point in a block wide enough to contain the true k nearest
+fn brute_force(pos: Vec2, cell_size: Vec2, seed: u64, k: usize) -> Vec<(IVec2, f32)> {
+    let base = (pos / cell_size).floor().as_ivec2();
+    let mut all = Vec::new();
+    for y in -6..=6 {
+        for x in -6..=6 {
+            let cell = base + IVec2::new(x, y);
+            let center = (cell.as_vec2() + worley_center(cell, seed)) * cell_size;
+            all.push((cell, center.distance(pos)));
+        }
+    }
+    all.sort_by(|a, b| a.1.total_cmp(&b.1));
+    all.truncate(k);
+    all
+}
+
+#[test]
+fn k_nearest_matches_brute_force() {
+    let cell_size = Vec2::new(30.0, 20.0);
+    for seed in 0..4 {
+        for i in 0..50 {
+            let pos = Vec2::new(i as f32 * 13.7 - 300.0, i as f32 * 7.3 - 150.0);
+            for k in [1, 2, 5, 9, 20] {
+                let found = k_nearest(pos, cell_size, seed, k);
+                let expected = brute_force(pos, cell_size, seed, k);
+                assert_eq!(found.len(), k);
+                for (a, b) in found.iter().zip(&expected) {
+                    assert_eq!(a.0, b.0, "k = {k} at {pos} with seed {seed}");
+                    assert!((a.1 - b.1).abs() < 1e-4);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn first_nearest_matches_worley() {
+    let cell_size = Vec2::splat(25.0);
+    for i in 0..100 {
+        let pos = Vec2::new(i as f32 * 3.1, i as f32 * 1.7);
+        let (cell, dist) = worley(pos, cell_size, 9);
+        let found = k_nearest(pos, cell_size, 9, 1);
+        assert_eq!(found[0], (cell, dist));
+    }
+}
@@ -0,0 +1,25 @@
+use glam::Vec2;
+use layered_worley::WorleyNoise;
+
+#[test]
+fn traced_levels_add_up_to_the_sampled_distance() {
+    let noise = WorleyNoise {
+        seed: 11,
+        depth: 4,
+        cells: Vec2::splat(90.0),
+        ..Default::default()
+    };
+    for i in 0..200 {
+        let pos = Vec2::new(i as f32 * 7.3, i as f32 * 3.1);
+        let mut seen = Vec::new();
+        let mut total = 0.0;
+        let (cell, dist) = noise.sample_traced(pos, |level, d| {
+            seen.push(level);
+            total += d * noise.level_weight(level);
+        });
+        assert_eq!((cell, dist), noise.sample(pos));
+        assert_eq!(seen, [4, 3, 2, 1, 0]);
+        assert!((total - dist).abs() < 1e-3, "{total} vs {dist}");
+        assert!(noise.dominant_level(pos) < noise.depth);
+    }
+}
@@ -0,0 +1,47 @@
+use std::{fs::File, io::BufReader};
+
+use glam::{U8Vec3, Vec2};
+use layered_worley::{Buffer, Config, WorleyNoise, export_levels, render};
+
+#[test]
+fn each_level_is_a_truncated_render_with_metadata() {
+    let config = Config {
+        noise: WorleyNoise {
+            seed: 31,
+            depth: 2,
+            cells: Vec2::splat(30.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let dir = tempfile::tempdir().unwrap();
+    let paths = export_levels(&config, 40, 24, dir.path()).unwrap();
+    let names: Vec<_> = paths
+        .iter()
+        .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names, ["level_0.png", "level_1.png", "level_2.png"]);
+
+    for (level, path) in paths.iter().enumerate() {
+        let mut expected = Buffer::new(40, 24, U8Vec3::ZERO);
+        let truncated = Config {
+            render_depth: Some(2 - level),
+            ..config.clone()
+        };
+        render(&mut expected, &truncated);
+        let image = image::open(path).unwrap().to_rgb8();
+        assert_eq!(image.as_raw(), expected.to_image().as_raw());
+
+        let decoder = png::Decoder::new(BufReader::new(File::open(path).unwrap()));
+        let reader = decoder.read_info().unwrap();
+        let text = &reader.info().uncompressed_latin1_text;
+        let value = |key: &str| {
+            text.iter()
+                .find(|t| t.keyword == key)
+                .map(|t| t.text.clone())
+        };
+        assert_eq!(value("worley:level"), Some(level.to_string()));
+        assert_eq!(value("worley:seed"), Some("31".to_string()));
+        assert_eq!(value("worley:render_depth"), Some((2 - level).to_string()));
+    }
+}
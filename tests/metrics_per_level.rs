@@ -0,0 +1,99 @@
+use glam::{DVec2, Vec2};
+use layered_worley::{DistanceMetric, MetricsPerLevel, WorleyNoise};
+
+use DistanceMetric::{Chebyshev, Euclidean, Manhattan};
+
+fn noise(metrics: &[DistanceMetric]) -> WorleyNoise {
+    WorleyNoise {
+        seed: 17,
+        depth: 3,
+        cells: Vec2::splat(48.0),
+        metrics_per_level: MetricsPerLevel::new(metrics).unwrap(),
+        ..Default::default()
+    }
+}
+
+fn positions() -> impl Iterator<Item = Vec2> {
+    (0..400).map(|i| Vec2::new((i * 37 % 500) as f32 + 0.3, (i * 91 % 300) as f32 - 40.7))
+}
+
+#[test]
+fn a_uniform_list_matches_the_single_metric() {
+    for metric in [Euclidean, Manhattan, Chebyshev] {
+        let single = WorleyNoise {
+            metric,
+            ..noise(&[])
+        };
+        let listed = noise(&[metric; 4]);
+        listed.validate().unwrap();
+        for pos in positions() {
+            assert_eq!(single.sample(pos), listed.sample(pos), "{metric:?}");
+        }
+    }
+}
+
+#[test]
+fn default_is_euclidean_everywhere() {
+    let noise = WorleyNoise::default();
+    assert!(noise.metrics_per_level.is_empty());
+    assert!((0..=noise.depth).all(|level| noise.metric_at(level) == Euclidean));
+}
+
+#[test]
+fn each_level_measures_with_its_own_metric() {
+    let mixed = noise(&[Manhattan, Manhattan, Euclidean, Euclidean]);
+    assert_eq!(mixed.metric_at(1), Manhattan);
+    assert_eq!(mixed.metric_at(2), Euclidean);
+
+    // The finest level's raw distance follows its own metric, so only changing the
+    // coarse levels leaves it alone
+    let finest = |noise: &WorleyNoise, pos| {
+        let mut finest = 0.0;
+        noise.sample_traced(pos, |level, d| {
+            if level == 3 {
+                finest = d;
+            }
+        });
+        finest
+    };
+    let plain = noise(&[Euclidean; 4]);
+    let mut differs = 0;
+    for pos in positions() {
+        assert_eq!(finest(&mixed, pos), finest(&plain, pos));
+        differs += (mixed.sample(pos) != plain.sample(pos)) as usize;
+    }
+    assert!(differs > 100, "{differs}");
+}
+
+#[test]
+fn list_length_must_match_depth() {
+    assert!(noise(&[Euclidean; 3]).validate().is_err());
+    assert!(noise(&[Euclidean; 5]).validate().is_err());
+    assert!(MetricsPerLevel::new(&[Euclidean; MetricsPerLevel::MAX_LEVELS + 1]).is_none());
+}
+
+#[test]
+fn parses_and_round_trips() {
+    let metrics: MetricsPerLevel = "manhattan, Euclidean,chebyshev".parse().unwrap();
+    assert_eq!(metrics.metrics(), [Manhattan, Euclidean, Chebyshev]);
+    assert_eq!(metrics.get(3), None);
+    assert!("manhattan,,euclidean".parse::<MetricsPerLevel>().is_err());
+    assert!("taxicab".parse::<MetricsPerLevel>().is_err());
+
+    let json = serde_json::to_string(&metrics).unwrap();
+    assert_eq!(
+        serde_json::from_str::<MetricsPerLevel>(&json).unwrap(),
+        metrics
+    );
+}
+
+#[test]
+fn f64_path_agrees() {
+    let noise = noise(&[Chebyshev, Manhattan, Euclidean, Manhattan]);
+    for pos in positions() {
+        let (cell, dist) = noise.sample(pos);
+        let (cell_f64, dist_f64) = noise.sample_f64(DVec2::new(pos.x as f64, pos.y as f64));
+        assert_eq!(cell, cell_f64);
+        assert!((dist - dist_f64).abs() < 1e-2, "{pos} {dist} {dist_f64}");
+    }
+}
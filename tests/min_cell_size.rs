@@ -0,0 +1,71 @@
+use glam::{U8Vec3, Vec2};
+use layered_worley::{Buffer, Config, WorleyNoise, render};
+
+fn config(depth: usize, min_cell_size: f32) -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 8,
+            depth,
+            cells: Vec2::splat(81.0),
+            ..Default::default()
+        },
+        min_cell_size,
+        ..Default::default()
+    }
+}
+
+fn rendered(config: &Config) -> Vec<U8Vec3> {
+    let mut buffer = Buffer::new(48, 32, U8Vec3::ZERO);
+    render(&mut buffer, config);
+    buffer.buff
+}
+
+#[test]
+fn levels_stop_at_the_minimum_cell_size() {
+    // 81 / 3^n: 27, 9, 3, 1, 1/3, ...
+    let noise = config(6, 0.0).noise;
+    assert_eq!(noise.depth_for_min_cell_size(1.0), 4);
+    assert_eq!(noise.depth_for_min_cell_size(2.0), 3);
+    assert_eq!(noise.depth_for_min_cell_size(100.0), 0);
+    assert_eq!(noise.depth_for_min_cell_size(0.0), 6);
+
+    // The narrower axis decides
+    let stretched = WorleyNoise {
+        cells: Vec2::new(810.0, 81.0),
+        ..noise
+    };
+    assert_eq!(stretched.depth_for_min_cell_size(1.0), 4);
+}
+
+#[test]
+fn default_keeps_every_level_of_the_default_config() {
+    let config = Config::default();
+    assert_eq!(config.effective_depth(), config.noise.depth);
+}
+
+#[test]
+fn runaway_depths_render_like_the_levels_kept() {
+    let deep = config(1_000_000, 1.0);
+    deep.validate().unwrap();
+    assert_eq!(deep.effective_depth(), 4);
+    assert_eq!(rendered(&deep), rendered(&config(4, 0.0)));
+
+    // Without a minimum, levels still stop once f32 cell sizes shrink to nothing
+    let unguarded = config(1_000_000, 0.0);
+    assert!(unguarded.effective_depth() < 200);
+}
+
+#[test]
+fn render_depth_still_applies_below_the_guard() {
+    let config = Config {
+        render_depth: Some(2),
+        ..config(6, 1.0)
+    };
+    assert_eq!(config.effective_depth(), 2);
+}
+
+#[test]
+fn negative_minimum_is_rejected() {
+    assert!(config(4, -1.0).validate().is_err());
+    assert!(config(4, f32::NAN).validate().is_err());
+}
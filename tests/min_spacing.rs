@@ -0,0 +1,71 @@
+use glam::IVec2;
+use layered_worley::{WorleyNoise, worley_center};
+
+fn spaced(min_spacing: f32) -> WorleyNoise {
+    WorleyNoise {
+        seed: 4,
+        min_spacing,
+        ..Default::default()
+    }
+}
+
+fn cells() -> impl Iterator<Item = IVec2> {
+    (-30..30).flat_map(|y| (-30..30).map(move |x| IVec2::new(x, y)))
+}
+
+// Distances between the points of every neighboring pair of cells in a block, in cells
+fn neighbor_distances(noise: &WorleyNoise) -> Vec<f32> {
+    let point = |c: IVec2| c.as_vec2() + noise.center(c);
+    let offsets = [
+        IVec2::new(1, 0),
+        IVec2::new(0, 1),
+        IVec2::new(1, 1),
+        IVec2::new(1, -1),
+    ];
+    cells()
+        .flat_map(|c| offsets.map(|o| point(c).distance(point(c + o))))
+        .collect()
+}
+
+#[test]
+fn spacing_is_off_by_default() {
+    let noise = spaced(0.0);
+    assert_eq!(WorleyNoise::default().min_spacing, 0.0);
+    assert!(cells().all(|c| noise.center(c) == worley_center(c, noise.seed)));
+}
+
+#[test]
+fn moderate_spacing_holds_everywhere() {
+    let unspaced = neighbor_distances(&spaced(0.0));
+    assert!(unspaced.iter().any(|&d| d < 0.2));
+    for min_spacing in [0.2, 0.4] {
+        let distances = neighbor_distances(&spaced(min_spacing));
+        assert!(distances.iter().all(|&d| d >= min_spacing), "{min_spacing}");
+    }
+}
+
+// Each thread has its own cache, so a fresh thread visiting the cells backwards has to
+// settle every conflict from scratch in a different order
+#[test]
+fn spaced_points_are_independent_of_query_order() {
+    let noise = spaced(0.45);
+    let forward: Vec<_> = cells().map(|c| noise.center(c)).collect();
+    let mut backward = std::thread::spawn(move || {
+        let all: Vec<_> = cells().collect();
+        all.iter()
+            .rev()
+            .map(|&c| noise.center(c))
+            .collect::<Vec<_>>()
+    })
+    .join()
+    .unwrap();
+    backward.reverse();
+    assert_eq!(forward, backward);
+}
+
+#[test]
+fn spacing_must_be_below_a_cell() {
+    assert!(spaced(0.99).validate().is_ok());
+    assert!(spaced(1.0).validate().is_err());
+    assert!(spaced(-0.1).validate().is_err());
+}
@@ -0,0 +1,59 @@
+use glam::{U8Vec3, USizeVec2};
+use layered_worley::{Buffer, mip_chain, save_mips};
+
+#[test]
+fn each_level_halves_down_to_one_pixel() {
+    let buffer = Buffer::new(64, 40, U8Vec3::new(10, 200, 90));
+    let levels = mip_chain(&buffer, false);
+    let sizes: Vec<_> = levels.iter().map(|l| (l.width, l.height)).collect();
+    assert_eq!(
+        sizes,
+        [(64, 40), (32, 20), (16, 10), (8, 5), (4, 3), (2, 2), (1, 1)]
+    );
+    // A flat color survives every level
+    for level in &levels {
+        assert!(level.buff.iter().all(|&p| p == U8Vec3::new(10, 200, 90)));
+    }
+}
+
+#[test]
+fn averaging_happens_in_linear_light() {
+    // Black and white checks average to half the light, which is 188 in sRGB rather
+    // than the 128 a naive average would give
+    let mut buffer = Buffer::new(2, 2, U8Vec3::ZERO);
+    buffer.set(USizeVec2::new(0, 0), U8Vec3::splat(255));
+    buffer.set(USizeVec2::new(1, 1), U8Vec3::splat(255));
+    let levels = mip_chain(&buffer, false);
+    assert_eq!(levels[1].buff, [U8Vec3::splat(188)]);
+}
+
+#[test]
+fn odd_edges_clamp_or_wrap() {
+    // A white first column. Halving 5 columns to 3 leaves the last block covering
+    // column 4 and one past the edge, which wraps back to column 0.
+    let mut buffer = Buffer::new(5, 2, U8Vec3::ZERO);
+    for y in 0..2 {
+        buffer.set(USizeVec2::new(0, y), U8Vec3::splat(255));
+    }
+    let clamped = &mip_chain(&buffer, false)[1];
+    let wrapped = &mip_chain(&buffer, true)[1];
+    assert_eq!(clamped.width, 3);
+    assert_eq!(clamped.buff[2], U8Vec3::ZERO);
+    assert_eq!(wrapped.buff[2], U8Vec3::splat(188));
+    assert_eq!(clamped.buff[..2], wrapped.buff[..2]);
+}
+
+#[test]
+fn mips_are_saved_beside_the_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("texture.png");
+    let levels = mip_chain(&Buffer::new(8, 4, U8Vec3::ONE), false);
+    save_mips(&levels, &path).unwrap();
+    for (level, name) in ["texture.png", "texture_mip1.png", "texture_mip3.png"]
+        .iter()
+        .enumerate()
+    {
+        let image = image::open(dir.path().join(name)).unwrap();
+        assert_eq!(image.width(), [8, 4, 1][level]);
+    }
+}
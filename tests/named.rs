@@ -0,0 +1,31 @@
+use layered_worley::{BuiltinPalette, CellColoring, ColorMode, ColorSpace, DistanceMetric, Named};
+
+fn round_trips<T: Named + std::str::FromStr + PartialEq + std::fmt::Debug>() {
+    for &v in T::ALL {
+        assert!(!v.description().is_empty());
+        assert_eq!(T::from_name(v.name()), Some(v));
+        assert_eq!(v.name().to_uppercase().parse::<T>().ok(), Some(v));
+    }
+    assert!("no such name".parse::<T>().is_err());
+}
+
+#[test]
+fn names_round_trip_case_insensitively() {
+    round_trips::<DistanceMetric>();
+    round_trips::<ColorMode>();
+    round_trips::<BuiltinPalette>();
+    round_trips::<CellColoring>();
+    round_trips::<ColorSpace>();
+}
+
+#[test]
+fn metrics_agree_on_axis_aligned_deltas() {
+    let delta = glam::Vec2::new(-3.0, 0.0);
+    for &metric in DistanceMetric::ALL {
+        assert_eq!(metric.distance(delta), 3.0);
+    }
+    let diagonal = glam::Vec2::new(3.0, -4.0);
+    assert_eq!(DistanceMetric::Euclidean.distance(diagonal), 5.0);
+    assert_eq!(DistanceMetric::Manhattan.distance(diagonal), 7.0);
+    assert_eq!(DistanceMetric::Chebyshev.distance(diagonal), 4.0);
+}
@@ -0,0 +1,67 @@
+use glam::{DVec2, IVec2, Vec2, Vec3};
+use layered_worley::{WorleyNoise, WorleyNoise3, worley, worley_center};
+
+const CELL: f32 = 16.0;
+// Steps across three cells either side of zero
+const STEP: f32 = 0.05;
+
+// Positions along one axis from -3 cells to +3 cells, crossing zero
+fn crossing() -> impl Iterator<Item = f32> {
+    let steps = (6.0 * CELL / STEP) as i32;
+    (0..=steps).map(|i| -3.0 * CELL + i as f32 * STEP)
+}
+
+// A nearest-point distance moves at most as far as the sample does, so any floor vs
+// truncation mistake around zero shows up as a jump
+#[test]
+fn distance_is_continuous_across_zero() {
+    for seed in 0..4 {
+        for offset in [-7.3, 0.0, 5.1] {
+            let along_x = |x: f32| worley(Vec2::new(x, offset), Vec2::splat(CELL), seed).1;
+            let along_y = |y: f32| worley(Vec2::new(offset, y), Vec2::splat(CELL), seed).1;
+            for along in [&along_x as &dyn Fn(f32) -> f32, &along_y] {
+                let mut last = along(-3.0 * CELL - STEP);
+                for t in crossing() {
+                    let d = along(t);
+                    assert!((d - last).abs() <= STEP * 1.01, "seed {seed} at {t}");
+                    last = d;
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn negative_cells_match_brute_force() {
+    for seed in 0..4 {
+        for t in crossing().step_by(7) {
+            let pos = Vec2::new(t, -t * 0.7);
+            let nearest = (-5..=5)
+                .flat_map(|y| (-5..=5).map(move |x| IVec2::new(x, y)))
+                .map(|c| (c, (c.as_vec2() + worley_center(c, seed)) * CELL))
+                .min_by(|a, b| a.1.distance(pos).total_cmp(&b.1.distance(pos)))
+                .unwrap();
+            let (cell, dist) = worley(pos, Vec2::splat(CELL), seed);
+            assert_eq!(cell, nearest.0, "seed {seed} at {pos}");
+            assert!((dist - nearest.1.distance(pos)).abs() < 1e-4);
+        }
+    }
+}
+
+#[test]
+fn f64_and_3d_are_continuous_across_zero() {
+    let noise = WorleyNoise::default();
+    let noise3 = WorleyNoise3::default();
+    let (mut last, mut last3): (Option<f32>, Option<f32>) = (None, None);
+    for t in crossing() {
+        let d = noise
+            .worley_f64(DVec2::new(t as f64, 3.3), DVec2::splat(CELL as f64))
+            .1;
+        let d3 = noise3.worley(Vec3::new(t, -2.1, 0.4), Vec3::splat(CELL)).1;
+        if let (Some(last), Some(last3)) = (last, last3) {
+            assert!((d - last).abs() <= STEP * 1.01, "f64 at {t}");
+            assert!((d3 - last3).abs() <= STEP * 1.01, "3d at {t}");
+        }
+        (last, last3) = (Some(d), Some(d3));
+    }
+}
@@ -0,0 +1,26 @@
+use layered_worley::{Buffer, npy::write_npy};
+
+#[test]
+fn npy_header_and_data() {
+    let mut buffer = Buffer::new(3, 2, 0.0f32);
+    for (i, v) in buffer.buff.iter_mut().enumerate() {
+        *v = i as f32 * 1.5;
+    }
+
+    let mut bytes = Vec::new();
+    write_npy(&mut bytes, &buffer).unwrap();
+
+    assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+    let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+    assert_eq!((10 + header_len) % 64, 0);
+    let header = std::str::from_utf8(&bytes[10..10 + header_len]).unwrap();
+    assert!(header.contains("'descr': '<f4'"));
+    assert!(header.contains("'shape': (2, 3)"));
+    assert!(header.ends_with('\n'));
+
+    let data: Vec<f32> = bytes[10 + header_len..]
+        .chunks_exact(4)
+        .map(|c| f32::from_le_bytes(c.try_into().unwrap()))
+        .collect();
+    assert_eq!(data, buffer.buff);
+}
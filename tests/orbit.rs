@@ -0,0 +1,61 @@
+use glam::{IVec2, U8Vec3, Vec2};
+use layered_worley::{Buffer, Config, Orbit, WorleyNoise, render, render_orbit};
+
+fn orbiting() -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 5,
+            depth: 3,
+            cells: Vec2::splat(40.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn orbit_loops_back_to_the_first_frame() {
+    const FRAMES: usize = 12;
+    let config = orbiting();
+    let mut frames = Vec::new();
+    render_orbit(&config, 96, 64, FRAMES, 0.3, |_, buffer| {
+        frames.push(buffer.clone());
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(frames.len(), FRAMES);
+    assert_ne!(frames[0].buff, frames[FRAMES / 2].buff);
+
+    // The frame after the last is the first again, byte for byte
+    let mut wrapped = config.clone();
+    wrapped.noise.orbit = Some(Orbit::at_frame(0.3, FRAMES, FRAMES));
+    let mut next = Buffer::new(96, 64, U8Vec3::ZERO);
+    render(&mut next, &wrapped);
+    assert_eq!(next.buff, frames[0].buff);
+}
+
+#[test]
+fn orbiting_points_stay_in_their_cells() {
+    for frame in 0..16 {
+        let noise = WorleyNoise {
+            orbit: Some(Orbit::at_frame(0.5, frame, 16)),
+            ..orbiting().noise
+        };
+        for y in -8..8 {
+            for x in -8..8 {
+                let center = noise.center(IVec2::new(x, y));
+                assert!(center.cmpge(Vec2::ZERO).all() && center.cmplt(Vec2::ONE).all());
+            }
+        }
+    }
+}
+
+#[test]
+fn orbit_radius_is_validated() {
+    let mut config = orbiting();
+    config.noise.orbit = Some(Orbit {
+        radius: 0.6,
+        phase: 0.0,
+    });
+    assert!(config.validate().is_err());
+}
@@ -0,0 +1,90 @@
+use glam::Vec3;
+use layered_worley::Palette;
+
+fn sample() -> Palette {
+    Palette::new([
+        (Vec3::new(255.0, 167.0, 0.0), 1),
+        (Vec3::new(49.0, 0.0, 62.0), 6),
+        (Vec3::new(80.0, 250.0, 123.0), 2),
+    ])
+}
+
+#[test]
+fn hex_list_round_trips_with_weights() {
+    let palette = sample();
+    assert_eq!(
+        Palette::from_hex_list(&palette.to_hex_list()).unwrap(),
+        palette
+    );
+}
+
+#[test]
+fn gpl_round_trips() {
+    let palette = Palette::new(sample().entries.iter().map(|e| (e.color, 1)));
+    let text = palette.to_gpl("sample");
+    assert!(text.starts_with("GIMP Palette\nName: sample\n"));
+    assert_eq!(Palette::from_gpl(&text).unwrap(), palette);
+}
+
+#[test]
+fn hex_list_accepts_hashes_comments_and_blank_lines() {
+    let text = "; lospec export\n#ff0000\n\n00ff00 3\n  #0000FF  \n";
+    let palette = Palette::from_hex_list(text).unwrap();
+    let expected = Palette::new([
+        (Vec3::new(255.0, 0.0, 0.0), 1),
+        (Vec3::new(0.0, 255.0, 0.0), 3),
+        (Vec3::new(0.0, 0.0, 255.0), 1),
+    ]);
+    assert_eq!(palette, expected);
+}
+
+#[test]
+fn act_reads_the_color_count() {
+    let mut bytes = vec![0; 772];
+    bytes[..6].copy_from_slice(&[10, 20, 30, 40, 50, 60]);
+    bytes[768..770].copy_from_slice(&2u16.to_be_bytes());
+    let palette = Palette::from_act(&bytes).unwrap();
+    assert_eq!(
+        palette,
+        Palette::new([
+            (Vec3::new(10.0, 20.0, 30.0), 1),
+            (Vec3::new(40.0, 50.0, 60.0), 1)
+        ])
+    );
+    assert_eq!(Palette::from_act(&bytes[..768]).unwrap().entries.len(), 256);
+    assert!(Palette::from_act(&bytes[..100]).is_err());
+}
+
+#[test]
+fn malformed_lines_are_reported_by_number() {
+    let err = Palette::from_hex_list("ff0000\nff00zz\n").unwrap_err();
+    assert!(err.to_string().contains("line 2"), "{err}");
+    let err = Palette::from_gpl("GIMP Palette\nName: x\n255 0 0\n12 300 4\n").unwrap_err();
+    assert!(err.to_string().contains("line 4"), "{err}");
+    let err = Palette::from_gpl("255 0 0\n").unwrap_err();
+    assert!(err.to_string().contains("line 1"), "{err}");
+    assert!(Palette::from_hex_list("; nothing\n").is_err());
+}
+
+#[test]
+fn hex_list_weights_must_fit_and_not_all_be_zero() {
+    let err = Palette::from_hex_list("FF0000 4294967295\n00FF00 1\n").unwrap_err();
+    assert!(err.to_string().contains("line 2"), "{err}");
+    assert!(Palette::from_hex_list("FF0000 4294967294\n00FF00 1\n").is_ok());
+    assert!(Palette::from_hex_list("FF0000 0\n00FF00 0\n").is_err());
+    assert!(Palette::from_hex_list("FF0000 0\n00FF00 1\n").is_ok());
+}
+
+#[test]
+fn load_picks_the_format_from_the_extension() {
+    let dir = tempfile::tempdir().unwrap();
+    let hex = dir.path().join("colors.hex");
+    std::fs::write(&hex, sample().to_hex_list()).unwrap();
+    assert_eq!(Palette::load(&hex).unwrap(), sample());
+
+    let gpl = dir.path().join("colors.GPL");
+    std::fs::write(&gpl, sample().to_gpl("x")).unwrap();
+    assert_eq!(Palette::load(&gpl).unwrap().entries.len(), 3);
+
+    assert!(Palette::load(dir.path().join("colors.png")).is_err());
+}
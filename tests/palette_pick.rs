@@ -0,0 +1,96 @@
+use glam::{IVec2, U8Vec3, Vec2, Vec3};
+use layered_worley::{
+    Buffer, BuiltinPalette, ColorMode, Config, Named, Palette, WorleyNoise, cell_hash, render,
+};
+
+#[test]
+fn hash_picks_follow_the_palette_weights() {
+    let palette = Palette::new([
+        (Vec3::splat(0.0), 1),
+        (Vec3::splat(80.0), 2),
+        (Vec3::splat(160.0), 3),
+        (Vec3::splat(240.0), 4),
+    ]);
+    let mut counts = [0usize; 4];
+    let mut total = 0;
+    for y in -100..100 {
+        for x in -100..100 {
+            let color = palette.pick(cell_hash(IVec2::new(x, y), 99)).unwrap();
+            counts[(color.x / 80.0) as usize] += 1;
+            total += 1;
+        }
+    }
+    for (i, count) in counts.iter().enumerate() {
+        let expected = (i + 1) as f32 / 10.0;
+        let actual = *count as f32 / total as f32;
+        assert!(
+            (actual - expected).abs() < 0.01,
+            "entry {i}: {actual} vs {expected}"
+        );
+    }
+}
+
+#[test]
+fn empty_palettes_pick_nothing() {
+    assert_eq!(Palette::new([]).pick(123), None);
+    assert_eq!(Palette::new([(Vec3::ONE, 0)]).pick_in_band(0, 2, 5), None);
+}
+
+#[test]
+fn empty_palette_renders_the_fallback_color() {
+    let fallback = Vec3::new(10.0, 200.0, 30.0);
+    for color_mode in [ColorMode::Hash, ColorMode::DistanceBanded] {
+        let config = Config {
+            noise: WorleyNoise {
+                depth: 2,
+                cells: Vec2::splat(16.0),
+                ..Default::default()
+            },
+            palette: Palette::new([]),
+            fallback_color: fallback,
+            // Fade toward the same color so the falloff can't hide a stray pixel
+            background: fallback,
+            color_mode,
+            ..Default::default()
+        };
+        let mut buffer = Buffer::new(40, 30, U8Vec3::ZERO);
+        render(&mut buffer, &config);
+        assert!(buffer.buff.iter().all(|&p| p == fallback.as_u8vec3()));
+    }
+}
+
+#[test]
+fn builtin_palettes_cycle_through_every_one() {
+    let first = BuiltinPalette::find(&Palette::default()).unwrap();
+    assert_eq!(first, BuiltinPalette::Dracula);
+    let mut seen = vec![first];
+    let mut current = first.next();
+    while current != first {
+        let palette = current.palette();
+        assert!(palette.total_weight() > 0);
+        assert_eq!(BuiltinPalette::find(&palette), Some(current));
+        seen.push(current);
+        current = current.next();
+    }
+    assert_eq!(seen.len(), BuiltinPalette::ALL.len());
+}
+
+#[test]
+fn unpickable_weights_fail_validation() {
+    let config = |palette| Config {
+        palette,
+        ..Default::default()
+    };
+    assert!(config(Palette::new([])).validate().is_ok());
+    assert!(
+        config(Palette::new([(Vec3::ONE, 0), (Vec3::ZERO, 0)]))
+            .validate()
+            .is_err()
+    );
+    let overflowing = Palette::new([(Vec3::ONE, u32::MAX), (Vec3::ZERO, 1)]);
+    assert_eq!(overflowing.total_weight(), u32::MAX as u64 + 1);
+    assert!(config(overflowing).validate().is_err());
+    let full = Palette::new([(Vec3::ONE, u32::MAX - 1), (Vec3::ZERO, 1)]);
+    assert!(config(full.clone()).validate().is_ok());
+    assert_eq!(full.pick(u32::MAX as u64 - 1), Some(Vec3::ZERO));
+}
@@ -0,0 +1,46 @@
+use glam::Vec3;
+use layered_worley::{BuiltinPalette, Named, Palette};
+
+#[test]
+fn double_inversion_is_the_original() {
+    let loaded = Palette::from_hex_list("ff8800 3\n0a0b0c\n").unwrap();
+    assert_eq!(loaded.inverted().inverted(), loaded);
+    for &builtin in BuiltinPalette::ALL {
+        let palette = builtin.palette();
+        let twice = palette.inverted().inverted();
+        // Grayscale's steps aren't whole numbers
+        for (a, b) in twice.entries.iter().zip(&palette.entries) {
+            assert!(a.color.abs_diff_eq(b.color, 1e-4), "{}", builtin.name());
+            assert_eq!(a.weight, b.weight);
+        }
+        if builtin != BuiltinPalette::Grayscale {
+            assert_eq!(twice, palette, "{}", builtin.name());
+        }
+    }
+}
+
+#[test]
+fn inversion_flips_channels_and_keeps_weights() {
+    let palette = Palette::new([(Vec3::new(0.0, 100.0, 255.0), 3), (Vec3::splat(40.0), 1)]);
+    let inverted = palette.inverted();
+    assert_eq!(inverted.entries[0].color, Vec3::new(255.0, 155.0, 0.0));
+    assert_eq!(inverted.entries[1].color, Vec3::splat(215.0));
+    assert_eq!(inverted.entries[0].weight, 3);
+    assert_eq!(inverted.total_weight(), palette.total_weight());
+}
+
+#[test]
+fn remap_applies_each_channels_curve() {
+    let identity: [u8; 256] = std::array::from_fn(|i| i as u8);
+    let flip: [u8; 256] = std::array::from_fn(|i| 255 - i as u8);
+    let half: [u8; 256] = std::array::from_fn(|i| (i / 2) as u8);
+
+    let palette = Palette::dracula();
+    assert_eq!(palette.remap(&[identity; 3]), palette);
+    assert_eq!(palette.remap(&[flip; 3]), palette.inverted());
+
+    let remapped =
+        Palette::new([(Vec3::new(200.0, 200.0, 200.0), 2)]).remap(&[flip, half, identity]);
+    assert_eq!(remapped.entries[0].color, Vec3::new(55.0, 100.0, 200.0));
+    assert_eq!(remapped.entries[0].weight, 2);
+}
@@ -0,0 +1,14 @@
+use glam::USizeVec2;
+use layered_worley::Buffer;
+
+#[test]
+fn par_for_each_hands_out_each_position_once() {
+    // Not square, so a mixed up width or height shows
+    let mut buffer = Buffer::new(7, 3, USizeVec2::MAX);
+    buffer.par_for_each(|pos, pixel| *pixel = pos);
+    for y in 0..3 {
+        for x in 0..7 {
+            assert_eq!(buffer.buff[x + y * 7], USizeVec2::new(x, y));
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use glam::{DVec2, Vec2};
+use layered_worley::{PointsPerLevel, WorleyNoise};
+
+fn noise(counts: &[usize]) -> WorleyNoise {
+    WorleyNoise {
+        seed: 31,
+        depth: 3,
+        cells: Vec2::splat(48.0),
+        points_per_level: PointsPerLevel::new(counts).unwrap(),
+        ..Default::default()
+    }
+}
+
+fn positions() -> impl Iterator<Item = Vec2> {
+    (0..400).map(|i| Vec2::new((i * 37 % 500) as f32 + 0.3, (i * 91 % 300) as f32 - 40.7))
+}
+
+#[test]
+fn one_point_per_level_matches_the_default() {
+    let plain = noise(&[]);
+    let ones = noise(&[1, 1, 1, 1]);
+    ones.validate().unwrap();
+    for pos in positions() {
+        assert_eq!(plain.sample(pos), ones.sample(pos));
+    }
+}
+
+#[test]
+fn list_length_must_match_depth() {
+    assert!(noise(&[1, 2, 3]).validate().is_err());
+    assert!(noise(&[1, 2, 3, 4, 5]).validate().is_err());
+    assert!(noise(&[1, 2, 3, 4]).validate().is_ok());
+}
+
+#[test]
+fn counts_are_bounded() {
+    assert!(PointsPerLevel::new(&[1, 0]).is_none());
+    assert!(PointsPerLevel::new(&[256]).is_none());
+    assert!(PointsPerLevel::new(&[1; PointsPerLevel::MAX_LEVELS + 1]).is_none());
+    let points = PointsPerLevel::new(&[1, 255]).unwrap();
+    assert_eq!(points.get(1), 255);
+    assert_eq!(points.get(7), 1);
+}
+
+#[test]
+fn parses_and_round_trips() {
+    let points: PointsPerLevel = "1, 2,4".parse().unwrap();
+    assert_eq!(points.counts(), [1, 2, 4]);
+    assert!("1,,2".parse::<PointsPerLevel>().is_err());
+    assert!("0".parse::<PointsPerLevel>().is_err());
+
+    let json = serde_json::to_string(&points).unwrap();
+    assert_eq!(json, "[1,2,4]");
+    assert_eq!(
+        serde_json::from_str::<PointsPerLevel>(&json).unwrap(),
+        points
+    );
+    assert!(serde_json::from_str::<PointsPerLevel>("[1,0]").is_err());
+}
+
+#[test]
+fn more_points_shorten_that_levels_distances() {
+    let mean = |counts: &[usize]| {
+        let noise = noise(counts);
+        let (mut sum, mut n) = (0.0, 0);
+        for pos in positions() {
+            noise.sample_traced(pos, |level, d| {
+                if level == 2 {
+                    sum += d;
+                    n += 1;
+                }
+            });
+        }
+        sum / n as f32
+    };
+    assert!(mean(&[1, 1, 4, 1]) < 0.8 * mean(&[1, 1, 1, 1]));
+}
+
+#[test]
+fn f64_path_agrees() {
+    let noise = noise(&[2, 1, 3, 2]);
+    for pos in positions() {
+        let (cell, dist) = noise.sample(pos);
+        let (cell_f64, dist_f64) = noise.sample_f64(DVec2::new(pos.x as f64, pos.y as f64));
+        assert_eq!(cell, cell_f64);
+        assert!((dist - dist_f64).abs() < 1e-2, "{pos} {dist} {dist_f64}");
+    }
+}
@@ -0,0 +1,46 @@
+use glam::{U8Vec3, Vec2};
+use layered_worley::{
+    Buffer, Config, WorleyNoise,
+    profile::{self, Stage},
+    render,
+};
+
+#[test]
+fn stages_nest_as_they_are_called() {
+    profile::take();
+    let config = Config {
+        noise: WorleyNoise {
+            depth: 2,
+            cells: Vec2::splat(16.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut buffer = Buffer::new(32, 24, U8Vec3::ZERO);
+    render(&mut buffer, &config);
+    buffer.to_image();
+    let profile = profile::take();
+
+    if !profile::ENABLED {
+        assert!(profile.stages.is_empty());
+        return;
+    }
+    let find = |path: &[Stage]| {
+        profile
+            .stages
+            .iter()
+            .find(|s| s.path == path)
+            .unwrap_or_else(|| panic!("no {path:?} in\n{profile}"))
+    };
+    let sample = find(&[Stage::Sample]);
+    assert_eq!(sample.calls, 32 * 24);
+    find(&[Stage::Sample, Stage::NeighborSearch, Stage::Hash]);
+    find(&[Stage::Color, Stage::Dither]);
+    assert_eq!(find(&[Stage::Pack]).calls, 1);
+    for stage in &profile.stages {
+        assert!(stage.self_nanos <= stage.total_nanos);
+    }
+    assert_eq!(profile.folded().lines().count(), profile.stages.len());
+    // Taking clears the timings
+    assert!(profile::take().stages.is_empty());
+}
@@ -0,0 +1,108 @@
+use std::{
+    f32::consts::FRAC_PI_2,
+    time::{Duration, Instant},
+};
+
+use glam::Vec2;
+use layered_worley::{AnimationClock, Config, Pulse, WorleyNoise, sample};
+
+fn pulsing(toroidal_domain: Option<Vec2>) -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 12,
+            depth: 3,
+            cells: Vec2::splat(48.0),
+            toroidal_domain,
+            ..Default::default()
+        },
+        // Peaks at 1.5x one second in
+        pulse: Some(Pulse {
+            amplitude: 0.5,
+            speed: FRAC_PI_2,
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn pulse_zooms_the_layout_without_rearranging_it() {
+    for domain in [None, Some(Vec2::new(192.0, 144.0))] {
+        let config = pulsing(domain);
+        let peak = config.at_time(1.0);
+        assert_eq!(peak.noise.cells, Vec2::splat(72.0));
+        let mut mismatches = 0;
+        for y in (0..144).step_by(6) {
+            for x in (0..192).step_by(6) {
+                let pos = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let (cell, dist) = sample(pos, &config);
+                let (zoomed_cell, zoomed_dist) = sample(pos * 1.5, &peak);
+                if cell != zoomed_cell {
+                    mismatches += 1;
+                } else {
+                    assert!((dist * 1.5 - zoomed_dist).abs() < 1e-2);
+                }
+            }
+        }
+        // Only rounding right on a boundary can flip a cell
+        assert!(mismatches <= 4, "{mismatches} cells moved with {domain:?}");
+    }
+}
+
+#[test]
+fn pulse_changes_little_between_frames() {
+    // Snapping on a torus would pop if the domain didn't scale with the cells
+    let config = pulsing(Some(Vec2::new(192.0, 144.0)));
+    let cells = |t: f32| {
+        let config = config.at_time(t);
+        (0..48 * 36)
+            .map(|i| sample(Vec2::new((i % 48) as f32, (i / 48) as f32) * 4.0, &config).0)
+            .collect::<Vec<_>>()
+    };
+    let mut previous = cells(0.0);
+    for frame in 1..120 {
+        let current = cells(frame as f32 / 60.0);
+        let changed = previous
+            .iter()
+            .zip(&current)
+            .filter(|(a, b)| a != b)
+            .count();
+        assert!(
+            changed * 10 < current.len(),
+            "{changed} cells changed at frame {frame}"
+        );
+        previous = current;
+    }
+}
+
+#[test]
+fn pulse_amplitude_must_keep_cells_positive() {
+    let mut config = pulsing(None);
+    assert!(config.validate().is_ok());
+    config.pulse.as_mut().unwrap().amplitude = 1.0;
+    assert!(config.validate().is_err());
+}
+
+// Pausing holds the time, and resuming carries on from it with no jump
+#[test]
+fn clock_pauses_and_resumes_without_jumping() {
+    let start = Instant::now();
+    let at = |ms: u64| start + Duration::from_millis(ms);
+    let mut clock = AnimationClock::new(start);
+    assert_eq!(clock.time(at(400)), Duration::from_millis(400));
+
+    clock.toggle_pause(at(500));
+    assert!(clock.is_paused());
+    assert_eq!(clock.time(at(500)), Duration::from_millis(500));
+    assert_eq!(clock.time(at(3000)), Duration::from_millis(500));
+
+    clock.toggle_pause(at(3000));
+    assert!(!clock.is_paused());
+    assert_eq!(clock.time(at(3000)), Duration::from_millis(500));
+    assert_eq!(clock.time(at(3250)), Duration::from_millis(750));
+
+    // A paused frame renders the same config however long it's held
+    let config = pulsing(None);
+    clock.toggle_pause(at(3250));
+    let held = |ms| config.at_time(clock.time(at(ms)).as_secs_f32());
+    assert_eq!(held(4000), held(9000));
+}
@@ -0,0 +1,120 @@
+use glam::{U8Vec3, USizeVec2, Vec2};
+use layered_worley::{
+    Buffer, Config, MaxDist, Region, WorleyNoise, render, render_pixel, render_region,
+    render_region_to_tile,
+};
+
+#[test]
+fn region_matches_full_render() {
+    let config = Config {
+        noise: WorleyNoise {
+            seed: 42,
+            depth: 3,
+            cells: Vec2::new(24.0, 24.0),
+            ..Default::default()
+        },
+        max_dist: MaxDist::Pixels(16.0),
+        ..Default::default()
+    };
+
+    let mut full = Buffer::new(80, 60, U8Vec3::ZERO);
+    render(&mut full, &config);
+
+    let region: Region = "17,9,40,30".parse().unwrap();
+    let mut crop = Buffer::new(region.width, region.height, U8Vec3::ZERO);
+    render_region(&mut crop, &config, region.origin());
+
+    for y in 0..region.height {
+        for x in 0..region.width {
+            assert_eq!(
+                crop.get(USizeVec2::new(x, y)).unwrap(),
+                full.get(region.origin().as_usizevec2() + USizeVec2::new(x, y))
+                    .unwrap(),
+                "pixel ({x}, {y}) of the crop differs"
+            );
+        }
+    }
+}
+
+// The world continues past the image's top left corner
+#[test]
+fn negative_region_samples_left_of_and_above_the_image() {
+    let config = Config {
+        noise: WorleyNoise {
+            seed: 42,
+            depth: 3,
+            cells: Vec2::new(24.0, 24.0),
+            ..Default::default()
+        },
+        max_dist: MaxDist::Pixels(16.0),
+        ..Default::default()
+    };
+
+    let region: Region = "-30,-7,40,20".parse().unwrap();
+    assert_eq!((region.x, region.y), (-30, -7));
+    let crop = render_region_to_tile(&config, region);
+    for y in 0..region.height {
+        for x in 0..region.width {
+            let pos = region.origin().as_vec2() + Vec2::new(x as f32, y as f32);
+            assert_eq!(
+                crop.get(USizeVec2::new(x, y)).unwrap(),
+                render_pixel(pos, &config),
+                "pixel ({x}, {y}) of the crop differs"
+            );
+        }
+    }
+}
+
+#[test]
+fn region_parse_rejects_bad_input() {
+    assert!("1,2,3".parse::<Region>().is_err());
+    assert!("1,2,0,4".parse::<Region>().is_err());
+    assert!("1,2,-3,4".parse::<Region>().is_err());
+    assert!("a,2,3,4".parse::<Region>().is_err());
+    // Pixels past i32::MAX can't be addressed
+    assert!("2147483000,0,1000,1".parse::<Region>().is_err());
+    assert!("0,2147483000,1,1000".parse::<Region>().is_err());
+    assert!("2147483000,0,647,1".parse::<Region>().is_ok());
+    assert!(
+        "-2147483648,-2147483648,2147483647,1"
+            .parse::<Region>()
+            .is_ok()
+    );
+}
+
+#[test]
+fn pasted_tiles_match_full_render() {
+    let config = Config {
+        noise: WorleyNoise {
+            seed: 5,
+            depth: 3,
+            cells: Vec2::splat(20.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut full = Buffer::new(50, 35, U8Vec3::ZERO);
+    render(&mut full, &config);
+
+    let mut stitched = Buffer::new(50, 35, U8Vec3::ONE);
+    for (x, y, width, height) in [(0, 0, 31, 12), (31, 0, 19, 12), (0, 12, 50, 23)] {
+        let region = Region {
+            x,
+            y,
+            width,
+            height,
+        };
+        let origin = region.origin().as_usizevec2();
+        stitched.paste(&render_region_to_tile(&config, region), origin);
+    }
+    assert_eq!(stitched.buff, full.buff);
+}
+
+#[test]
+fn paste_clips_at_the_edges() {
+    let mut dst = Buffer::new(4, 3, 0u8);
+    dst.paste(&Buffer::new(3, 3, 1u8), USizeVec2::new(2, 1));
+    assert_eq!(dst.buff, [0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 1, 1]);
+    dst.paste(&Buffer::new(2, 2, 2u8), USizeVec2::new(9, 9));
+    assert_eq!(dst.buff, [0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 1, 1]);
+}
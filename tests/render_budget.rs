@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+use glam::Vec2;
+use layered_worley::{Config, WorleyNoise, fit_render_depth};
+
+fn config() -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 8,
+            depth: 5,
+            cells: Vec2::splat(32.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn generous_budget_keeps_full_depth() {
+    let (depth, projected) = fit_render_depth(&config(), 64, 64, 1, Duration::from_secs(60));
+    assert_eq!(depth, 5);
+    assert!(projected <= Duration::from_secs(60));
+}
+
+#[test]
+fn impossible_budget_falls_back_to_the_coarsest_level() {
+    let (depth, _) = fit_render_depth(&config(), 4096, 4096, 1, Duration::from_nanos(1));
+    assert_eq!(depth, 0);
+}
+
+#[test]
+fn never_deeper_than_the_configured_render_depth() {
+    let config = Config {
+        render_depth: Some(2),
+        ..config()
+    };
+    let (depth, _) = fit_render_depth(&config, 64, 64, 1, Duration::from_secs(60));
+    assert_eq!(depth, 2);
+}
+
+#[test]
+fn supersampling_projects_longer() {
+    let config = config();
+    let budget = Duration::from_secs(60);
+    let (_, plain) = fit_render_depth(&config, 256, 256, 1, budget);
+    let (_, supersampled) = fit_render_depth(&config, 256, 256, 4, budget);
+    // 16 times the samples, with plenty of slack for timing noise
+    assert!(supersampled > plain * 4, "{supersampled:?} vs {plain:?}");
+}
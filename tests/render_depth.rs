@@ -0,0 +1,40 @@
+use glam::{U8Vec3, Vec2};
+use layered_worley::{Buffer, Config, WorleyNoise, render};
+
+fn config(depth: usize, render_depth: Option<usize>) -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 21,
+            depth,
+            cells: Vec2::splat(48.0),
+            ..Default::default()
+        },
+        render_depth,
+        ..Default::default()
+    }
+}
+
+fn rendered(config: &Config) -> Vec<U8Vec3> {
+    let mut buffer = Buffer::new(64, 48, U8Vec3::ZERO);
+    render(&mut buffer, config);
+    buffer.buff
+}
+
+#[test]
+fn render_depth_matches_a_shallower_hierarchy() {
+    let full = config(5, None);
+    assert_eq!(rendered(&config(5, Some(5))), rendered(&full));
+    for render_depth in 0..5 {
+        assert_eq!(
+            rendered(&config(5, Some(render_depth))),
+            rendered(&config(render_depth, None)),
+            "render depth {render_depth}"
+        );
+    }
+}
+
+#[test]
+fn render_depth_cannot_exceed_depth() {
+    assert!(config(3, Some(3)).validate().is_ok());
+    assert!(config(3, Some(4)).validate().is_err());
+}
@@ -0,0 +1,32 @@
+use glam::U8Vec3;
+use layered_worley::{Buffer, Config, WorleyError, render, render_into};
+
+#[test]
+fn matches_a_packed_render() {
+    let config = Config::default();
+    let (width, height) = (48, 32);
+    let mut buffer = Buffer::new(width, height, U8Vec3::ZERO);
+    render(&mut buffer, &config);
+    let mut expected = Vec::new();
+    buffer.to_minifb_buffer(&mut expected);
+
+    let mut out = vec![0; width * height];
+    render_into(&mut out, width, height, &config).unwrap();
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn rejects_a_wrong_sized_slice() {
+    let config = Config::default();
+    for len in [0, 47 * 32, 48 * 32 + 1] {
+        let mut out = vec![0; len];
+        let result = render_into(&mut out, 48, 32, &config);
+        assert!(
+            matches!(
+                result,
+                Err(WorleyError::InvalidParameter { name: "output", .. })
+            ),
+            "{len} pixels"
+        );
+    }
+}
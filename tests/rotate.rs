@@ -0,0 +1,109 @@
+use std::f32::consts::FRAC_PI_2;
+
+use glam::{IVec2, U8Vec3, Vec2};
+use layered_worley::{
+    Buffer, Config, FlowMode, WorleyNoise, render, render_flow, render_region, sample,
+};
+
+const CENTER: Vec2 = Vec2::new(40.0, 30.0);
+
+fn config(rotation: f32) -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 19,
+            depth: 3,
+            cells: Vec2::new(48.0, 20.0),
+            ..Default::default()
+        },
+        rotation,
+        rotation_center: CENTER,
+        ..Default::default()
+    }
+}
+
+fn positions() -> impl Iterator<Item = Vec2> {
+    (0..400).map(|i| Vec2::new((i * 37 % 500) as f32 + 0.3, (i * 91 % 300) as f32 - 40.7))
+}
+
+#[test]
+fn zero_rotation_changes_nothing() {
+    let config = config(0.0);
+    assert!(positions().all(|pos| config.sample_pos(pos) == pos));
+    let mut plain = Buffer::new(64, 48, U8Vec3::ZERO);
+    let mut centered = plain.clone();
+    render(&mut plain, &config);
+    render(
+        &mut centered,
+        &Config {
+            rotation_center: Vec2::new(-500.0, 900.0),
+            ..config
+        },
+    );
+    assert_eq!(plain.buff, centered.buff);
+}
+
+#[test]
+fn positions_turn_about_the_center() {
+    let config = config(FRAC_PI_2);
+    assert_eq!(config.sample_pos(CENTER), CENTER);
+    for pos in positions() {
+        let offset = pos - CENTER;
+        // The pattern turns clockwise on screen, so positions turn the other way
+        let expected = CENTER + Vec2::new(offset.y, -offset.x);
+        assert!(config.sample_pos(pos).distance(expected) < 1e-3, "{pos}");
+        assert!((config.sample_pos(pos).distance(CENTER) - offset.length()).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn a_quarter_turn_moves_the_cells() {
+    let (plain, turned) = (config(0.0), config(FRAC_PI_2));
+    let mut same = 0;
+    for pos in positions() {
+        let offset = pos - CENTER;
+        let source = CENTER + Vec2::new(offset.y, -offset.x);
+        // Rounding in the turn can move a position across a wall, but only rarely
+        same += (sample(pos, &turned) == sample(source, &plain)) as usize;
+    }
+    assert!(same >= positions().count() * 99 / 100, "{same}");
+}
+
+#[test]
+fn panned_windows_stay_consistent() {
+    let config = config(0.6);
+    let mut full = Buffer::new(64, 48, U8Vec3::ZERO);
+    render(&mut full, &config);
+    let origin = IVec2::new(20, 12);
+    let mut window = Buffer::new(24, 20, U8Vec3::ZERO);
+    render_region(&mut window, &config, origin);
+    for (i, pixel) in window.buff.iter().enumerate() {
+        let (x, y) = (origin.x as usize + i % 24, origin.y as usize + i / 24);
+        assert_eq!(*pixel, full.buff[x + y * 64], "{x},{y}");
+    }
+}
+
+#[test]
+fn flows_turn_with_the_pattern() {
+    let (plain, turned) = (config(0.0), config(FRAC_PI_2));
+    let mut flows = Buffer::new(64, 48, Vec2::ZERO);
+    render_flow(&mut flows, &turned, IVec2::ZERO, FlowMode::Toward);
+    for (i, &flow) in flows.buff.iter().enumerate() {
+        let pos = Vec2::new((i % 64) as f32, (i / 64) as f32);
+        let source = plain
+            .noise
+            .sample_flow(turned.sample_pos(pos), FlowMode::Toward);
+        // Clockwise on screen takes +x to +y
+        let expected = Vec2::new(-source.y, source.x);
+        assert!(flow.distance(expected) < 1e-3, "{pos} {flow} {expected}");
+    }
+}
+
+#[test]
+fn non_finite_rotations_are_rejected() {
+    assert!(config(f32::NAN).validate().is_err());
+    let config = Config {
+        rotation_center: Vec2::INFINITY,
+        ..config(0.5)
+    };
+    assert!(config.validate().is_err());
+}
@@ -0,0 +1,46 @@
+use glam::{U8Vec3, USizeVec2, Vec2};
+use layered_worley::{Buffer, RULER_SIZE, draw_ruler, tick_spacing};
+
+const FILL: U8Vec3 = U8Vec3::new(1, 2, 3);
+
+#[test]
+fn tick_spacing_rounds_up_to_one_two_five() {
+    for (min, expected) in [
+        (1.0, 1.0),
+        (3.0, 5.0),
+        (8.0, 10.0),
+        (12.0, 20.0),
+        (50.0, 50.0),
+        (0.3, 0.5),
+    ] {
+        let step = tick_spacing(min);
+        assert!(
+            (step - expected).abs() < 1e-4,
+            "{min} gave {step}, not {expected}"
+        );
+    }
+}
+
+#[test]
+fn rulers_leave_the_interior_untouched() {
+    let mut buffer = Buffer::new(200, 150, FILL);
+    draw_ruler(&mut buffer, Vec2::new(-37.0, 12.0), 1.5, Vec2::splat(40.0));
+    for y in 0..buffer.height {
+        for x in 0..buffer.width {
+            let inside = x >= RULER_SIZE && y >= RULER_SIZE;
+            let pixel = buffer.get(USizeVec2::new(x, y)).unwrap();
+            assert_eq!(pixel == FILL, inside, "pixel {x},{y}");
+        }
+    }
+}
+
+#[test]
+fn cell_marks_line_up_with_the_cells() {
+    let mut buffer = Buffer::new(300, 100, FILL);
+    draw_ruler(&mut buffer, Vec2::ZERO, 1.0, Vec2::splat(64.0));
+    let mark = buffer.get(USizeVec2::new(64, 0)).unwrap();
+    for x in RULER_SIZE..buffer.width {
+        let marked = buffer.get(USizeVec2::new(x, 0)).unwrap() == mark;
+        assert_eq!(marked, x % 64 == 0, "column {x}");
+    }
+}
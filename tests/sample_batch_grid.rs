@@ -0,0 +1,54 @@
+use glam::Vec2;
+use layered_worley::WorleyNoise;
+
+fn noise() -> WorleyNoise {
+    WorleyNoise {
+        seed: 12,
+        depth: 3,
+        cells: Vec2::splat(30.0),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn grid_matches_pointwise_samples() {
+    let noise = noise();
+    let (origin, step) = (Vec2::new(-17.5, 40.0), Vec2::new(0.5, 1.5));
+    let grid = noise.sample_batch_grid(23, 11, origin, step);
+    assert_eq!((grid.width, grid.height), (23, 11));
+    for y in 0..11 {
+        for x in 0..23 {
+            let pos = origin + Vec2::new(x as f32, y as f32) * step;
+            let dist = grid.buff[x + y * 23];
+            assert_eq!(dist, noise.sample_normalized(pos).1);
+            assert!((0.0..=1.0).contains(&dist));
+        }
+    }
+}
+
+// Panning by whole steps shifts the image, and doubling the step keeps every other pixel
+#[test]
+fn origin_and_step_compose_like_pan_and_zoom() {
+    let noise = noise();
+    let full = noise.sample_batch_grid(40, 30, Vec2::ZERO, Vec2::ONE);
+
+    let panned = noise.sample_batch_grid(20, 10, Vec2::new(5.0, 7.0), Vec2::ONE);
+    for y in 0..10 {
+        for x in 0..20 {
+            assert_eq!(panned.buff[x + y * 20], full.buff[x + 5 + (y + 7) * 40]);
+        }
+    }
+
+    let zoomed = noise.sample_batch_grid(20, 15, Vec2::ZERO, Vec2::splat(2.0));
+    for y in 0..15 {
+        for x in 0..20 {
+            assert_eq!(zoomed.buff[x + y * 20], full.buff[x * 2 + y * 2 * 40]);
+        }
+    }
+}
+
+#[test]
+fn empty_grids_are_empty() {
+    let grid = noise().sample_batch_grid(0, 5, Vec2::ZERO, Vec2::ONE);
+    assert!(grid.buff.is_empty());
+}
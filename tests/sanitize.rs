@@ -0,0 +1,57 @@
+use glam::{U8Vec3, Vec2, Vec3};
+use layered_worley::{Buffer, Config, quantize, quantize_pixel};
+
+#[test]
+fn non_finite_colors_become_the_fallback() {
+    let config = Config::default();
+    let fallback = config.fallback_color.as_u8vec3();
+    for rgb in [
+        Vec3::new(f32::NAN, 10.0, 20.0),
+        Vec3::new(10.0, f32::INFINITY, 20.0),
+        Vec3::splat(f32::NEG_INFINITY),
+    ] {
+        assert_eq!(quantize_pixel(rgb, Vec2::ZERO, &config), fallback);
+    }
+}
+
+#[test]
+fn non_finite_fallback_becomes_black() {
+    let config = Config {
+        fallback_color: Vec3::splat(f32::NAN),
+        ..Default::default()
+    };
+    let rgb = Vec3::new(f32::NAN, 0.0, 0.0);
+    assert_eq!(quantize_pixel(rgb, Vec2::ZERO, &config), U8Vec3::ZERO);
+}
+
+#[test]
+fn colors_are_clamped_to_range() {
+    let config = Config::default();
+    let rgb = Vec3::new(-40.0, 128.0, 900.0);
+    assert_eq!(
+        quantize_pixel(rgb, Vec2::ZERO, &config),
+        U8Vec3::new(0, 128, 255)
+    );
+}
+
+#[test]
+fn nan_pixels_stay_contained() {
+    let config = Config::default();
+    let mut buffer = Buffer::new(4, 4, Vec3::splat(100.0));
+    buffer.par_for_each(|pos, pixel| {
+        if pos.x == 2 {
+            *pixel = Vec3::NAN;
+        }
+    });
+    let out = quantize(&buffer, &config, Default::default());
+    for y in 0..4 {
+        for x in 0..4 {
+            let expected = if x == 2 {
+                config.fallback_color.as_u8vec3()
+            } else {
+                U8Vec3::splat(100)
+            };
+            assert_eq!(out.buff[y * 4 + x], expected);
+        }
+    }
+}
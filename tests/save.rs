@@ -0,0 +1,21 @@
+use glam::U8Vec3;
+use layered_worley::Buffer;
+
+#[test]
+fn save_to_missing_directory_errors() {
+    let buffer = Buffer::new(4, 4, U8Vec3::ZERO);
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("does/not/exist/output.png");
+    assert!(buffer.save(&path).is_err());
+}
+
+#[test]
+fn save_round_trips() {
+    let buffer = Buffer::new(4, 3, U8Vec3::new(10, 20, 30));
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("output.png");
+    buffer.save(&path).unwrap();
+    let img = image::open(&path).unwrap().to_rgb8();
+    assert_eq!(img.dimensions(), (4, 3));
+    assert!(img.pixels().all(|p| p.0 == [10, 20, 30]));
+}
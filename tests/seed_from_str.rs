@@ -0,0 +1,32 @@
+use layered_worley::seed_from_str;
+
+// Shared seeds must keep meaning the same thing, so the values are pinned
+#[test]
+fn text_seeds_are_stable() {
+    assert_eq!(seed_from_str("purple lanterns"), 13187906021707509766);
+    assert_eq!(
+        seed_from_str("purple lanterns"),
+        seed_from_str("purple lanterns")
+    );
+}
+
+#[test]
+fn similar_texts_give_unrelated_seeds() {
+    let seeds = [
+        "",
+        "a",
+        "b",
+        "ab",
+        "ba",
+        "purple lantern",
+        "Purple lanterns",
+    ]
+    .map(seed_from_str);
+    for (i, a) in seeds.iter().enumerate() {
+        for b in &seeds[i + 1..] {
+            assert_ne!(a, b);
+            // Not just different, but differing in many bits
+            assert!((a ^ b).count_ones() > 10);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use glam::{IVec2, U8Vec3, USizeVec2, Vec2};
+use layered_worley::{
+    Buffer, CellId, Config, PixelGeometry, WorleyNoise, render_cells, render_region,
+    render_with_shader, sample, shade,
+};
+
+fn config() -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 31,
+            depth: 3,
+            cells: Vec2::splat(40.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn field(config: &Config, origin: IVec2) -> Buffer<(IVec2, f32)> {
+    let mut field = Buffer::new(48, 32, (IVec2::ZERO, 0.0));
+    render_cells(&mut field, config, origin);
+    field
+}
+
+#[test]
+fn cells_match_sample() {
+    let config = config();
+    let origin = IVec2::new(100, 7);
+    let field = field(&config, origin);
+    for (i, &(cell, dist)) in field.buff.iter().enumerate() {
+        let pos = origin + USizeVec2::new(i % field.width, i / field.width).as_ivec2();
+        let (expected, expected_dist) = sample(pos.as_vec2(), &config);
+        assert_eq!((CellId(cell), dist), (expected, expected_dist), "{pos}");
+    }
+}
+
+#[test]
+fn shading_the_cells_reproduces_a_render() {
+    let config = config();
+    let origin = IVec2::new(30, 50);
+    let shaded = render_with_shader(&field(&config, origin), |pos, cell, dist| {
+        let geometry = PixelGeometry {
+            cell: CellId(cell),
+            dist,
+            ..Default::default()
+        };
+        shade(&geometry, (origin + pos.as_ivec2()).as_vec2(), &config)
+    });
+    let mut rendered = Buffer::new(48, 32, U8Vec3::ZERO);
+    render_region(&mut rendered, &config, origin);
+    assert_eq!(shaded.buff, rendered.buff);
+}
+
+#[test]
+fn shaders_see_each_pixel_once_at_its_position() {
+    let field = field(&config(), IVec2::ZERO);
+    let shaded = render_with_shader(&field, |pos, _, _| U8Vec3::new(pos.x as u8, pos.y as u8, 0));
+    assert_eq!((shaded.width, shaded.height), (48, 32));
+    for (i, pixel) in shaded.buff.iter().enumerate() {
+        assert_eq!(*pixel, U8Vec3::new((i % 48) as u8, (i / 48) as u8, 0));
+    }
+}
+
+#[test]
+fn empty_fields_shade_to_empty_buffers() {
+    let field = Buffer::new(0, 5, (IVec2::ZERO, 0.0));
+    let shaded = render_with_shader(&field, |_, _, _| U8Vec3::ONE);
+    assert!(shaded.buff.is_empty());
+}
@@ -0,0 +1,215 @@
+use glam::{U8Vec3, Vec2, Vec3};
+use layered_worley::{
+    Buffer, ColorMode, Config, DistanceMetric, DitherTexture, MaxDist, PNG_CONFIG_KEY, Palette,
+    Pulse, Region, RenderConfig, ToneMap, WorleyNoise, read_png_text, render, render_region,
+    sidecar_path,
+};
+
+fn detailed() -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: u64::MAX - 3,
+            depth: 3,
+            growth: Vec2::splat(2.5),
+            cells: Vec2::new(30.0, 22.5),
+            metric: DistanceMetric::Manhattan,
+            min_spacing: 0.2,
+            ..Default::default()
+        },
+        render_depth: Some(2),
+        max_dist: MaxDist::CellFraction(0.35),
+        palette: Palette::new([(Vec3::new(1.0, 2.0, 3.0), 4), (Vec3::splat(200.0), 1)]),
+        color_mode: ColorMode::DistanceBanded,
+        color_blend: 0.1,
+        pulse: Some(Pulse {
+            amplitude: 0.2,
+            speed: 1.5,
+        }),
+        tonemap: ToneMap::Aces,
+        dither: Some(DitherTexture {
+            width: 2,
+            height: 1,
+            thresholds: vec![0.25, 0.75],
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn sidecar_round_trips_and_reproduces_the_render() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = sidecar_path(dir.path().join("out.png"));
+    assert_eq!(path, dir.path().join("out.json"));
+
+    let saved = RenderConfig::new(&detailed(), 40, 30);
+    saved.save(&path).unwrap();
+    let loaded = RenderConfig::load(&path).unwrap();
+    assert_eq!(loaded, saved);
+    assert_eq!(loaded.version, env!("CARGO_PKG_VERSION"));
+
+    let draw = |config: &RenderConfig| {
+        let mut buffer = Buffer::new(config.width, config.height, U8Vec3::ZERO);
+        render(&mut buffer, &config.config);
+        buffer.buff
+    };
+    assert_eq!(draw(&loaded), draw(&saved));
+}
+
+#[test]
+fn region_round_trips_and_reproduces_the_crop() {
+    let dir = tempfile::tempdir().unwrap();
+    let region = Region {
+        x: -5,
+        y: 7,
+        width: 16,
+        height: 9,
+    };
+    let saved = RenderConfig::new(&detailed(), 40, 30).with_region(Some(region));
+    let draw = |config: &RenderConfig| {
+        let region = config.region.unwrap();
+        let mut buffer = Buffer::new(region.width, region.height, U8Vec3::ZERO);
+        render_region(&mut buffer, &config.config, region.origin());
+        buffer
+    };
+    let buffer = draw(&saved);
+
+    let json = sidecar_path(dir.path().join("crop.png"));
+    saved.save(&json).unwrap();
+    let png = dir.path().join("crop.png");
+    buffer
+        .save_png_with_text(&png, &saved.png_text().unwrap())
+        .unwrap();
+    let text = read_png_text(&png).unwrap();
+    assert!(text.contains(&("worley:region".to_string(), "-5,7,16,9".to_string())));
+
+    for path in [json, png] {
+        let loaded = RenderConfig::load(&path).unwrap();
+        assert_eq!(loaded, saved);
+        assert_eq!(draw(&loaded).buff, buffer.buff);
+    }
+}
+
+#[test]
+fn missing_fields_take_defaults() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("old.json");
+    let json = r#"{"version": "0.0.1", "width": 8, "height": 4, "config": {"noise": {"seed": 9}}}"#;
+    std::fs::write(&path, json).unwrap();
+
+    let loaded = RenderConfig::load(&path).unwrap();
+    assert_eq!(loaded.region, None);
+    let expected = Config {
+        noise: WorleyNoise {
+            seed: 9,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    assert_eq!(loaded.config, expected);
+}
+
+// Sidecars written while growth was one factor for both axes
+#[test]
+fn scalar_growth_loads_on_both_axes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("old.json");
+    for (growth, expected) in [
+        ("3.0", Vec2::splat(3.0)),
+        ("2", Vec2::splat(2.0)),
+        ("[3.0, 1.5]", Vec2::new(3.0, 1.5)),
+    ] {
+        let json = format!(
+            r#"{{"version": "0.1.0", "width": 8, "height": 4,
+                "config": {{"noise": {{"seed": 9, "depth": 2, "growth": {growth}}}}}}}"#
+        );
+        std::fs::write(&path, json).unwrap();
+        let loaded = RenderConfig::load(&path).unwrap();
+        assert_eq!(loaded.config.noise.growth, expected, "{growth}");
+    }
+    for growth in ["[3.0]", "[1.0, 2.0, 3.0]", "\"3\""] {
+        let json = format!(r#"{{"noise": {{"growth": {growth}}}}}"#);
+        assert!(serde_json::from_str::<Config>(&json).is_err(), "{growth}");
+    }
+}
+
+#[test]
+fn regions_past_the_pixel_range_fail_to_load() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("edited.json");
+    let region = Region {
+        x: i32::MAX - 10,
+        y: 0,
+        width: 20,
+        height: 1,
+    };
+    assert!(region.validate().is_err());
+    RenderConfig::new(&Config::default(), 40, 30)
+        .with_region(Some(region))
+        .save(&path)
+        .unwrap();
+    assert!(RenderConfig::load(&path).is_err());
+}
+
+#[test]
+fn malformed_json_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bad.json");
+    std::fs::write(&path, "{\"width\": ").unwrap();
+    assert!(RenderConfig::load(&path).is_err());
+    assert!(RenderConfig::load(dir.path().join("missing.json")).is_err());
+}
+
+#[test]
+fn png_text_carries_the_record() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("out.png");
+    let config = detailed();
+    let saved = RenderConfig::new(&config, 20, 12);
+    let mut buffer = Buffer::new(20, 12, U8Vec3::ZERO);
+    render(&mut buffer, &config);
+    buffer
+        .save_png_with_text(&path, &saved.png_text().unwrap())
+        .unwrap();
+
+    let text = read_png_text(&path).unwrap();
+    let value = |key: &str| text.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+    assert_eq!(value("worley:seed"), Some("18446744073709551612"));
+    assert_eq!(value("worley:depth"), Some("3"));
+    assert_eq!(value("worley:width"), Some("20"));
+    assert!(value(PNG_CONFIG_KEY).is_some());
+
+    let loaded = RenderConfig::load(&path).unwrap();
+    assert_eq!(loaded, saved);
+    let mut again = Buffer::new(loaded.width, loaded.height, U8Vec3::ZERO);
+    render(&mut again, &loaded.config);
+    assert_eq!(again.buff, buffer.buff);
+    assert_eq!(image::open(&path).unwrap().to_rgb8(), buffer.to_image());
+}
+
+#[test]
+fn long_and_unicode_text_round_trips() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("text.png");
+    let text = [
+        ("short".to_string(), "plain".to_string()),
+        ("long".to_string(), "0123456789".repeat(500)),
+        ("unicode".to_string(), "öl → 水".to_string()),
+    ];
+    Buffer::new(3, 2, U8Vec3::ONE)
+        .save_png_with_text(&path, &text)
+        .unwrap();
+    let mut read = read_png_text(&path).unwrap();
+    read.sort();
+    let mut text = text.to_vec();
+    text.sort();
+    assert_eq!(read, text);
+}
+
+#[test]
+fn pngs_without_the_record_fail_to_load() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("plain.png");
+    Buffer::new(3, 2, U8Vec3::ONE).save(&path).unwrap();
+    assert!(read_png_text(&path).unwrap().is_empty());
+    assert!(RenderConfig::load(&path).is_err());
+}
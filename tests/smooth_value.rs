@@ -0,0 +1,107 @@
+use glam::{IVec2, Vec2};
+use layered_worley::{Idw, WorleyNoise};
+
+fn noise() -> WorleyNoise {
+    WorleyNoise {
+        seed: 21,
+        cells: Vec2::splat(32.0),
+        ..Default::default()
+    }
+}
+
+// The cells an IDW of `k` points blends, in a fixed order
+fn blended(noise: &WorleyNoise, pos: Vec2, k: usize) -> Vec<(i32, i32)> {
+    let mut cells: Vec<_> = noise.k_nearest(pos, noise.cells, k)[..k]
+        .iter()
+        .map(|(c, _)| (c.x, c.y))
+        .collect();
+    cells.sort();
+    cells
+}
+
+// Bisects to where the blended cells change, returning points just either side of it
+fn change_between(noise: &WorleyNoise, k: usize, mut a: Vec2, mut b: Vec2) -> (Vec2, Vec2) {
+    let start = blended(noise, a, k);
+    for _ in 0..24 {
+        let mid = (a + b) * 0.5;
+        if blended(noise, mid, k) == start {
+            a = mid;
+        } else {
+            b = mid;
+        }
+    }
+    (a, b)
+}
+
+#[test]
+fn continuous_where_the_nearest_points_change() {
+    let noise = noise();
+    for idw in [
+        Idw::default(),
+        Idw { k: 2, power: 1.0 },
+        Idw { k: 6, power: 3.0 },
+    ] {
+        let mut changes = 0;
+        for row in 0..8 {
+            let y = row as f32 * 13.7 - 40.0;
+            for i in 0..600 {
+                let a = Vec2::new(i as f32 * 0.5 - 100.0, y);
+                let b = a + Vec2::X * 0.5;
+                if blended(&noise, a, idw.k) == blended(&noise, b, idw.k) {
+                    continue;
+                }
+                changes += 1;
+                // Where plain k-nearest IDW would jump
+                let (a, b) = change_between(&noise, idw.k, a, b);
+                let (before, after) = (
+                    noise.sample_smooth_value(a, idw),
+                    noise.sample_smooth_value(b, idw),
+                );
+                assert!(
+                    (before - after).abs() < 1e-3,
+                    "{idw:?} jumps from {before} to {after} at {a}"
+                );
+            }
+        }
+        assert!(changes > 50, "{changes}");
+    }
+}
+
+#[test]
+fn values_stay_in_range_and_hit_feature_points() {
+    let noise = noise();
+    for y in -3..3 {
+        for x in -3..3 {
+            let cell = IVec2::new(x, y);
+            let point = (cell.as_vec2() + noise.center(cell)) * noise.cells;
+            let value = noise.sample_smooth_value(point, Idw::default());
+            assert!((value - noise.cell_value(cell)).abs() < 1e-2);
+            assert!((0.0..=1.0).contains(&value));
+        }
+    }
+}
+
+#[test]
+fn one_neighbor_is_flat_per_cell() {
+    let noise = noise();
+    let idw = Idw { k: 1, power: 2.0 };
+    for i in 0..200 {
+        let pos = Vec2::new(i as f32 * 3.1 - 200.0, i as f32 * 1.7 - 90.0);
+        let (cell, _) = noise.worley(pos, noise.cells);
+        assert!((noise.sample_smooth_value(pos, idw) - noise.cell_value(cell)).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn one_cell_torus_is_that_cell_value() {
+    let noise = WorleyNoise {
+        toroidal_domain: Some(Vec2::splat(32.0)),
+        ..noise()
+    };
+    let idw = Idw { k: 4, power: 2.0 };
+    for i in 0..32 {
+        let pos = Vec2::new(i as f32 + 0.5, 31.5 - i as f32);
+        let (cell, _) = noise.worley(pos, noise.cells);
+        assert_eq!(noise.sample_smooth_value(pos, idw), noise.cell_value(cell));
+    }
+}
@@ -0,0 +1,90 @@
+use glam::{IVec2, Vec2, Vec3};
+use layered_worley::{Config, Palette, WorleyNoise, cell_hash, render_pixel};
+
+fn grays() -> Palette {
+    Palette::new([
+        (Vec3::splat(0.0), 1),
+        (Vec3::splat(80.0), 1),
+        (Vec3::splat(160.0), 1),
+        (Vec3::splat(240.0), 1),
+    ])
+}
+
+fn cells() -> impl Iterator<Item = IVec2> {
+    (-30..30).flat_map(|y| (-30..30).map(move |x| IVec2::new(x, y)))
+}
+
+#[test]
+fn full_window_picks_as_before() {
+    let palette = Palette::new([(Vec3::ONE, 3), (Vec3::splat(2.0), 5), (Vec3::ZERO, 1)]);
+    for cell in cells() {
+        let hash = cell_hash(cell, 17);
+        assert_eq!(palette.pick_in_window(hash, 1.0, 0.0), palette.pick(hash));
+    }
+    assert_eq!(Palette::new([]).pick_in_window(3, 0.5, 0.2), None);
+}
+
+// At half a palette per cell, even columns pick from the middle two entries and odd
+// columns from the outer two
+#[test]
+fn bias_slides_a_window_along_the_palette() {
+    let palette = grays();
+    let bias = Vec2::new(0.5, 0.0);
+    let spread = 1.0 - bias.length();
+    let mut seen = [[false; 4]; 2];
+    for cell in cells() {
+        let shift = bias.dot(cell.as_vec2() + 0.5);
+        let color = palette.pick_in_window(cell_hash(cell, 3), spread, shift);
+        let entry = (color.unwrap().x / 80.0) as usize;
+        seen[cell.x.rem_euclid(2) as usize][entry] = true;
+    }
+    assert_eq!(
+        seen,
+        [[false, true, true, false], [true, false, false, true]]
+    );
+}
+
+#[test]
+fn rendered_colors_follow_the_bias() {
+    let config = |bias: Vec2| Config {
+        noise: WorleyNoise {
+            seed: 5,
+            depth: 0,
+            cells: Vec2::splat(20.0),
+            ..Default::default()
+        },
+        palette: grays(),
+        spatial_color_bias: bias,
+        ..Default::default()
+    };
+    let positions =
+        || (0..60).flat_map(|y| (0..60).map(move |x| Vec2::new(x as f32, y as f32) * 2.0));
+
+    // A slight bias barely moves the window, so it changes nothing here
+    let plain = config(Vec2::ZERO);
+    let slight = config(Vec2::new(1e-6, 0.0));
+    for pos in positions() {
+        assert_eq!(render_pixel(pos, &plain), render_pixel(pos, &slight));
+    }
+
+    // Past a palette length per cell the pick follows the cell's column alone
+    let biased = config(Vec2::new(1.5, 0.0));
+    biased.validate().unwrap();
+    let mut by_column = std::collections::HashMap::new();
+    for pos in positions() {
+        let (cell, _) = biased.noise.sample(pos);
+        let color = render_pixel(pos, &biased);
+        // Colors are jittered per cell, so compare the entry they came from
+        let entry = (color.x as f32 / 80.0).round() as usize;
+        assert_eq!(*by_column.entry(cell.0.x).or_insert(entry), entry, "{pos}");
+    }
+}
+
+#[test]
+fn bias_must_be_finite() {
+    let config = Config {
+        spatial_color_bias: Vec2::new(f32::NAN, 0.0),
+        ..Default::default()
+    };
+    assert!(config.validate().is_err());
+}
@@ -0,0 +1,113 @@
+use glam::{U8Vec3, USizeVec2, Vec2};
+use layered_worley::{
+    Buffer, CombineOp, Config, MaxDist, WorleyNoise, draw_text, render_split, sample, text_size,
+};
+
+fn gray(dist: f32, max_dist: f32) -> U8Vec3 {
+    U8Vec3::splat((dist / max_dist * 255.0).clamp(0.0, 255.0) as u8)
+}
+
+#[test]
+fn halves_show_the_same_area_without_and_with_the_hierarchy() {
+    let config = Config {
+        noise: WorleyNoise {
+            seed: 5,
+            depth: 3,
+            cells: Vec2::splat(40.0),
+            ..Default::default()
+        },
+        max_dist: MaxDist::Pixels(30.0),
+        ..Default::default()
+    };
+    let (width, height) = (121, 50);
+    let half = 61;
+    let mut buffer = Buffer::new(width, height, U8Vec3::ZERO);
+    render_split(&mut buffer, &config);
+
+    let noise = config.noise;
+    // Below the labels
+    for y in 30..height {
+        for x in 0..width - half - 1 {
+            let world = Vec2::new(x as f32, y as f32);
+            let left = buffer.get(USizeVec2::new(x, y)).unwrap();
+            let right = buffer.get(USizeVec2::new(x + half + 1, y)).unwrap();
+            assert_eq!(left, gray(noise.worley(world, noise.cells).1, 30.0));
+            assert_eq!(right, gray(noise.sample(world).1, 30.0));
+        }
+        assert_ne!(buffer.get(USizeVec2::new(half, y)), Some(U8Vec3::ZERO));
+    }
+}
+
+// The split samples like a render, so rotation and the overlay show in both halves
+#[test]
+fn halves_are_turned_and_layered_like_a_render() {
+    let noise = WorleyNoise {
+        seed: 5,
+        depth: 3,
+        cells: Vec2::splat(40.0),
+        ..Default::default()
+    };
+    let turned = Config {
+        noise,
+        max_dist: MaxDist::Pixels(30.0),
+        rotation: 0.6,
+        rotation_center: Vec2::new(30.0, 25.0),
+        ..Default::default()
+    };
+    let layered = Config {
+        overlay: Some(WorleyNoise {
+            seed: 9,
+            cells: Vec2::splat(24.0),
+            ..noise
+        }),
+        combine_op: CombineOp::Max,
+        ..turned.clone()
+    };
+    let (width, height) = (121, 50);
+    let half = 61;
+    for config in [turned, layered] {
+        config.validate().unwrap();
+        let mut buffer = Buffer::new(width, height, U8Vec3::ZERO);
+        render_split(&mut buffer, &config);
+        let (mut left_differs, mut right_differs) = (false, false);
+        for y in 30..height {
+            for x in 0..width - half - 1 {
+                let world = Vec2::new(x as f32, y as f32);
+                let left = buffer.get(USizeVec2::new(x, y)).unwrap();
+                let right = buffer.get(USizeVec2::new(x + half + 1, y)).unwrap();
+                let coarsest = noise.worley(config.sample_pos(world), noise.cells).1;
+                if config.overlay.is_none() {
+                    assert_eq!(left, gray(coarsest, 30.0));
+                }
+                assert_eq!(right, gray(sample(world, &config).1, 30.0));
+                left_differs |= left != gray(noise.worley(world, noise.cells).1, 30.0);
+                right_differs |= right != gray(noise.sample(world).1, 30.0);
+            }
+        }
+        assert!(left_differs && right_differs);
+    }
+}
+
+#[test]
+fn text_stays_inside_its_size() {
+    for scale in [1, 3] {
+        let text = "Worley-0 9";
+        let size = text_size(text, scale);
+        let mut buffer = Buffer::new(130, 30, U8Vec3::ZERO);
+        let corner = USizeVec2::new(2, 3);
+        draw_text(&mut buffer, corner, text, U8Vec3::ONE, scale);
+        for y in 0..buffer.height {
+            for x in 0..buffer.width {
+                let pos = USizeVec2::new(x, y);
+                let inside = pos.cmpge(corner).all() && pos.cmplt(corner + size).all();
+                if !inside {
+                    assert_eq!(buffer.get(pos), Some(U8Vec3::ZERO), "{pos} at {scale}x");
+                }
+            }
+        }
+        // The last glyph reaches the right edge and every glyph the top and bottom
+        let drawn = |x, y| buffer.get(corner + USizeVec2::new(x, y)) == Some(U8Vec3::ONE);
+        assert!((0..size.y).any(|y| drawn(size.x - 1, y)));
+        assert!((0..size.x).any(|x| drawn(x, 0) && (0..size.x).any(|x| drawn(x, size.y - 1))));
+    }
+}
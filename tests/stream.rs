@@ -0,0 +1,32 @@
+use glam::{U8Vec3, Vec2};
+use layered_worley::{
+    Buffer, Config, Region, WorleyNoise, render_region, stream::STRIP_ROWS, write_png_streaming,
+};
+
+#[test]
+fn streamed_png_matches_an_in_memory_render() {
+    let config = Config {
+        noise: WorleyNoise {
+            seed: 8,
+            depth: 3,
+            cells: Vec2::splat(40.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    // Tall enough to end on a partial strip
+    let region = Region {
+        x: 30,
+        y: 10,
+        width: 37,
+        height: STRIP_ROWS + 45,
+    };
+
+    let mut png = Vec::new();
+    write_png_streaming(&mut png, &config, region, 16).unwrap();
+    let decoded = image::load_from_memory(&png).unwrap().to_rgb8();
+
+    let mut expected = Buffer::new(region.width, region.height, U8Vec3::ZERO);
+    render_region(&mut expected, &config, region.origin());
+    assert_eq!(decoded.as_raw(), expected.to_image().as_raw());
+}
@@ -0,0 +1,71 @@
+use glam::{IVec2, U8Vec3, Vec2, Vec3};
+use layered_worley::{
+    Buffer, ColorSpace, Config, MaxDist, Palette, WorleyNoise, render_region, render_supersampled,
+    srgb_to_linear,
+};
+
+// Black and white cells without falloff, so cell edges are as hard as they come
+fn config(color_space: ColorSpace) -> Config {
+    Config {
+        noise: WorleyNoise {
+            seed: 12,
+            depth: 2,
+            cells: Vec2::splat(20.0),
+            ..Default::default()
+        },
+        palette: Palette::new([(Vec3::ZERO, 1), (Vec3::splat(255.0), 1)]),
+        max_dist: MaxDist::Pixels(1e6),
+        dist_power: 0.0,
+        color_space,
+        ..Default::default()
+    }
+}
+
+fn supersampled(config: &Config, factor: usize) -> Vec<U8Vec3> {
+    let mut buffer = Buffer::new(40, 40, U8Vec3::ZERO);
+    render_supersampled(&mut buffer, config, IVec2::new(3, 1), factor);
+    buffer.buff
+}
+
+#[test]
+fn srgb_averages_keep_the_light_of_an_edge() {
+    // A pixel half covered by white
+    let samples = [
+        Vec3::ZERO,
+        Vec3::ZERO,
+        Vec3::splat(255.0),
+        Vec3::splat(255.0),
+    ];
+    let naive = ColorSpace::Linear.average(samples);
+    let correct = ColorSpace::Srgb.average(samples);
+    assert_eq!(naive, Vec3::splat(127.5));
+
+    // Half the light of white is linear 0.5, which the naive average falls far short of
+    let light = |rgb: Vec3| srgb_to_linear(rgb / 255.0).x;
+    assert!((light(correct) - 0.5).abs() < 1e-4, "{correct}");
+    assert!(light(naive) < 0.25);
+}
+
+#[test]
+fn one_sample_is_a_plain_render() {
+    let config = config(ColorSpace::Srgb);
+    let mut buffer = Buffer::new(40, 40, U8Vec3::ZERO);
+    render_region(&mut buffer, &config, IVec2::new(3, 1));
+    assert_eq!(supersampled(&config, 1), buffer.buff);
+}
+
+#[test]
+fn srgb_edges_are_brighter_than_naive_ones() {
+    let correct = supersampled(&config(ColorSpace::Srgb), 4);
+    let naive = supersampled(&config(ColorSpace::Linear), 4);
+    let mut edges = 0;
+    for (c, n) in correct.iter().zip(&naive) {
+        // Pure black and white pixels agree, and mixed ones come out brighter
+        assert!(c.x >= n.x, "{c} is darker than {n}");
+        if c.x != 0 && c.x != 255 {
+            edges += 1;
+            assert!(c.x > n.x);
+        }
+    }
+    assert!(edges > 20);
+}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use glam::{IVec2, USizeVec2, Vec2};
+use layered_worley::{
+    Buffer, CellId, CellPolygon, Config, Region, WorleyNoise, cell_ids, render_svg, trace_cells,
+};
+
+fn ids(rows: &[&str]) -> Buffer<CellId> {
+    let mut buffer = Buffer::new(rows[0].len(), rows.len(), CellId(IVec2::ZERO));
+    for (y, row) in rows.iter().enumerate() {
+        for (x, c) in row.bytes().enumerate() {
+            buffer.buff[x + y * rows[0].len()] = CellId(IVec2::new(c as i32, 0));
+        }
+    }
+    buffer
+}
+
+// Clockwise loops on screen have positive area, holes negative
+fn area(polygon: &CellPolygon) -> i64 {
+    polygon
+        .loops
+        .iter()
+        .map(|corners| {
+            let n = corners.len();
+            (0..n)
+                .map(|i| {
+                    let (a, b) = (corners[i].as_i64vec2(), corners[(i + 1) % n].as_i64vec2());
+                    a.x * b.y - b.x * a.y
+                })
+                .sum::<i64>()
+                / 2
+        })
+        .sum()
+}
+
+#[test]
+fn rectangles_are_four_corners() {
+    let polygons = trace_cells(&ids(&["aab", "aab"]));
+    assert_eq!(polygons.len(), 2);
+    assert_eq!(
+        polygons[0].loops,
+        vec![vec![
+            USizeVec2::new(0, 0),
+            USizeVec2::new(2, 0),
+            USizeVec2::new(2, 2),
+            USizeVec2::new(0, 2)
+        ]]
+    );
+    assert_eq!(area(&polygons[1]), 2);
+}
+
+#[test]
+fn enclosed_cells_cut_holes() {
+    let polygons = trace_cells(&ids(&["aaaa", "abba", "aaaa"]));
+    assert_eq!(polygons[0].loops.len(), 2);
+    assert_eq!(area(&polygons[0]), 10);
+    assert_eq!(area(&polygons[1]), 2);
+}
+
+#[test]
+fn traced_areas_match_pixel_counts() {
+    let config = Config {
+        noise: WorleyNoise {
+            seed: 3,
+            depth: 3,
+            cells: Vec2::splat(20.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let region = Region {
+        x: 5,
+        y: 9,
+        width: 70,
+        height: 50,
+    };
+    let ids = cell_ids(&config, region);
+    let mut counts: HashMap<CellId, i64> = HashMap::new();
+    for &id in &ids.buff {
+        *counts.entry(id).or_default() += 1;
+    }
+    let polygons = trace_cells(&ids);
+    assert_eq!(polygons.len(), counts.len());
+    for polygon in &polygons {
+        assert_eq!(area(polygon), counts[&polygon.cell]);
+    }
+
+    let svg = render_svg(&config, region);
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert_eq!(svg.matches("<path").count(), counts.len());
+}
@@ -0,0 +1,34 @@
+use glam::{IVec2, U8Vec3, Vec2};
+use layered_worley::{Buffer, Config, WorleyNoise, render_region, render_tiled};
+
+#[test]
+fn tiled_render_matches_untiled() {
+    let config = Config {
+        noise: WorleyNoise {
+            seed: 5,
+            depth: 3,
+            cells: Vec2::splat(30.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let origin = IVec2::new(13, 7);
+
+    let mut expected = Buffer::new(70, 45, U8Vec3::ZERO);
+    render_region(&mut expected, &config, origin);
+
+    for tile_size in [1, 8, 16, 64, 128] {
+        let mut tiled = Buffer::new(70, 45, U8Vec3::ZERO);
+        render_tiled(&mut tiled, &config, origin, tile_size);
+        assert_eq!(tiled.buff, expected.buff, "tile size {tile_size}");
+    }
+}
+
+#[test]
+fn tiled_render_of_no_pixels_is_a_no_op() {
+    for (width, height) in [(0, 5), (5, 0), (0, 0)] {
+        let mut buffer = Buffer::new(width, height, U8Vec3::ZERO);
+        render_tiled(&mut buffer, &Config::default(), IVec2::ZERO, 16);
+        assert!(buffer.buff.is_empty());
+    }
+}
@@ -0,0 +1,30 @@
+use glam::Vec3;
+use layered_worley::{ToneMap, linear_to_srgb, srgb_to_linear};
+
+#[test]
+fn srgb_round_trips() {
+    for i in 0..=255 {
+        let c = Vec3::splat(i as f32 / 255.0);
+        assert!((linear_to_srgb(srgb_to_linear(c)) - c).abs().max_element() < 1e-5);
+    }
+}
+
+#[test]
+fn none_leaves_colors_untouched() {
+    let rgb = Vec3::new(12.0, 300.0, 255.0);
+    assert_eq!(ToneMap::None.apply(rgb), rgb);
+}
+
+#[test]
+fn curves_stay_in_range_and_keep_order() {
+    for tonemap in [ToneMap::Reinhard, ToneMap::Aces] {
+        let mut previous = -1.0;
+        for i in 0..=100 {
+            let out = tonemap.apply(Vec3::splat(i as f32 * 10.0));
+            assert!(out.min_element() >= 0.0 && out.max_element() <= 255.0);
+            assert!(out.x >= previous, "{tonemap:?} isn't monotonic at {i}");
+            previous = out.x;
+        }
+        assert_eq!(tonemap.apply(Vec3::ZERO), Vec3::ZERO);
+    }
+}
@@ -0,0 +1,178 @@
+use glam::{IVec2, U8Vec3, Vec2};
+use layered_worley::{Buffer, Config, HashMixer, WorleyNoise, render};
+
+fn toroidal_noise() -> WorleyNoise {
+    WorleyNoise {
+        seed: 7,
+        depth: 3,
+        growth: Vec2::splat(3.0),
+        cells: Vec2::new(40.0, 40.0),
+        toroidal_domain: Some(Vec2::new(200.0, 120.0)),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn toroidal_sampling_is_periodic() {
+    let noise = toroidal_noise();
+    let domain = noise.toroidal_domain.unwrap();
+    for y in (0..120).step_by(7) {
+        for x in (0..200).step_by(7) {
+            let pos = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+            for shift in [Vec2::X, Vec2::Y, -Vec2::ONE, Vec2::new(2.0, -3.0)] {
+                let (cell, dist) = noise.sample(pos);
+                let (shifted_cell, shifted_dist) = noise.sample(pos + shift * domain);
+                assert_eq!(
+                    cell, shifted_cell,
+                    "cell differs at {pos} + {shift} domains"
+                );
+                assert!((dist - shifted_dist).abs() < 1e-3);
+            }
+        }
+    }
+}
+
+#[test]
+fn toroidal_distance_is_continuous_across_the_edge() {
+    let noise = toroidal_noise();
+    let domain = noise.toroidal_domain.unwrap();
+    let eps = 1e-3;
+    for y in 0..120 {
+        let y = y as f32 + 0.5;
+        let (_, before) = noise.worley(Vec2::new(domain.x - eps, y), noise.cells);
+        let (_, after) = noise.worley(Vec2::new(eps, y), noise.cells);
+        assert!(
+            (before - after).abs() < 0.01,
+            "distance jumps from {before} to {after} across x = 0 at y = {y}"
+        );
+    }
+    for x in 0..200 {
+        let x = x as f32 + 0.5;
+        let (_, before) = noise.worley(Vec2::new(x, domain.y - eps), noise.cells);
+        let (_, after) = noise.worley(Vec2::new(x, eps), noise.cells);
+        assert!((before - after).abs() < 0.01);
+    }
+}
+
+fn settled_noise() -> WorleyNoise {
+    WorleyNoise {
+        relaxation_iterations: 1,
+        min_spacing: 0.3,
+        ..toroidal_noise()
+    }
+}
+
+#[test]
+fn relaxed_distance_is_continuous_across_the_seam_at_every_level() {
+    for noise in [
+        settled_noise(),
+        WorleyNoise {
+            relaxation_iterations: 0,
+            ..settled_noise()
+        },
+    ] {
+        let domain = noise.toroidal_domain.unwrap();
+        let eps = 1e-3;
+        let mut cell_size = noise.cells;
+        for _ in 0..=noise.depth {
+            for i in 0..240 {
+                let t = i as f32 * 0.5 + 0.25;
+                let pairs = [
+                    (
+                        Vec2::new(domain.x - eps, t % domain.y),
+                        Vec2::new(eps, t % domain.y),
+                    ),
+                    (
+                        Vec2::new(t % domain.x, domain.y - eps),
+                        Vec2::new(t % domain.x, eps),
+                    ),
+                    (Vec2::new(-eps, t % domain.y), Vec2::new(eps, t % domain.y)),
+                ];
+                for (before, after) in pairs {
+                    let (_, a) = noise.worley(before, cell_size);
+                    let (_, b) = noise.worley(after, cell_size);
+                    assert!(
+                        (a - b).abs() < 4.0 * eps,
+                        "{a} vs {b} between {before} and {after} at cell size {cell_size}"
+                    );
+                }
+            }
+            cell_size /= noise.growth;
+        }
+    }
+}
+
+#[test]
+fn spacing_holds_across_the_seam() {
+    let period = IVec2::new(5, 3);
+    // The legacy hash rarely puts neighboring points close enough to conflict
+    for seed in 0..100 {
+        let noise = WorleyNoise {
+            seed,
+            hash: HashMixer::SplitMix,
+            relaxation_iterations: 0,
+            ..settled_noise()
+        };
+        check_spacing(&noise, period);
+    }
+}
+
+fn check_spacing(noise: &WorleyNoise, period: IVec2) {
+    for y in 0..period.y {
+        for x in 0..period.x {
+            let cell = IVec2::new(x, y);
+            let point = noise.periodic_center(cell, Some(period));
+            for offset in [IVec2::X, IVec2::Y, IVec2::ONE, IVec2::new(1, -1)] {
+                let neighbor = (cell + offset).rem_euclid(period);
+                let other = offset.as_vec2() + noise.periodic_center(neighbor, Some(period));
+                assert!(
+                    point.distance(other) >= noise.min_spacing,
+                    "{cell} and {neighbor} are {} apart",
+                    point.distance(other)
+                );
+            }
+        }
+    }
+}
+
+// A domain one coarse cell wide, where every neighbor is that same cell
+fn one_cell_torus() -> WorleyNoise {
+    WorleyNoise {
+        seed: 7,
+        depth: 2,
+        cells: Vec2::splat(64.0),
+        toroidal_domain: Some(Vec2::splat(64.0)),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn one_cell_torus_has_no_second_cell() {
+    let noise = one_cell_torus();
+    for i in 0..64 {
+        let pos = Vec2::new(i as f32 + 0.5, 63.5 - i as f32);
+        let [(first, _), (second, gap)] = noise.sample_f2(pos);
+        assert_eq!(first, second);
+        assert_eq!(gap, f32::INFINITY);
+    }
+}
+
+#[test]
+fn one_cell_torus_renders_with_color_blend() {
+    let config = Config {
+        noise: one_cell_torus(),
+        color_blend: 0.1,
+        ..Default::default()
+    };
+    let mut blended = Buffer::new(32, 32, U8Vec3::ZERO);
+    render(&mut blended, &config);
+    let mut plain = Buffer::new(32, 32, U8Vec3::ZERO);
+    render(
+        &mut plain,
+        &Config {
+            color_blend: 0.0,
+            ..config
+        },
+    );
+    assert_eq!(blended.buff, plain.buff);
+}
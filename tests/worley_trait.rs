@@ -0,0 +1,60 @@
+use glam::{Vec2, Vec3};
+use layered_worley::{Worley, WorleyNoise, WorleyNoise3};
+
+// Written once against the trait: the fraction of samples landing in the same cell as
+// the first, and the largest distance seen
+fn survey<V: Copy, W: Worley<V>>(noise: &W, positions: impl Iterator<Item = V>) -> (f32, f32) {
+    let samples: Vec<_> = positions.map(|p| noise.sample(p)).collect();
+    let first = samples[0].0;
+    let same = samples.iter().filter(|(cell, _)| *cell == first).count();
+    let max = samples.iter().map(|(_, d)| *d).fold(0.0, f32::max);
+    (same as f32 / samples.len() as f32, max)
+}
+
+#[test]
+fn generic_code_runs_in_2d_and_3d() {
+    let noise2 = WorleyNoise {
+        seed: 3,
+        depth: 3,
+        cells: Vec2::splat(40.0),
+        ..Default::default()
+    };
+    let noise3 = WorleyNoise3 {
+        seed: 3,
+        depth: 3,
+        cells: Vec3::splat(40.0),
+        ..Default::default()
+    };
+
+    let (same2, max2) = survey(
+        &noise2,
+        (0..400).map(|i| Vec2::new(i as f32, i as f32 * 0.5)),
+    );
+    let (same3, max3) = survey(
+        &noise3,
+        (0..400).map(|i| Vec3::new(i as f32, i as f32 * 0.5, i as f32 * 0.25)),
+    );
+    for (same, max) in [(same2, max2), (same3, max3)] {
+        assert!(same > 0.0 && same < 1.0);
+        assert!(max > 0.0 && max.is_finite());
+    }
+    // The trait and the inherent methods agree
+    assert_eq!(
+        Worley::sample(&noise2, Vec2::new(5.0, 9.0)),
+        noise2.sample(Vec2::new(5.0, 9.0))
+    );
+}
+
+#[test]
+fn worley3_feature_points_are_found_exactly() {
+    let noise = WorleyNoise3::default();
+    let cell_size = Vec3::splat(16.0);
+    for x in -2..2 {
+        let cell = glam::IVec3::new(x, 1, -x);
+        let center =
+            (cell.as_vec3() + layered_worley::worley_center3(cell, noise.seed)) * cell_size;
+        let (found, dist) = noise.worley(center, cell_size);
+        assert_eq!(found, cell);
+        assert!(dist < 1e-3);
+    }
+}